@@ -0,0 +1,48 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use thiserror::Error;
+
+/// All possible API errors.
+#[derive(Error, Debug, PartialEq)]
+pub enum Error {
+    /// Occurs, when a string contains characters that are not trytes.
+    #[error("Invalid trytes.")]
+    InvalidTrytes,
+
+    /// Occurs, when trytes do not have the length of a hash.
+    #[error("Invalid hash.")]
+    InvalidHash,
+
+    /// Occurs, when trytes do not have the length of an address.
+    #[error("Invalid address.")]
+    InvalidAddress,
+
+    /// Occurs, when trytes are too long to be a tag.
+    #[error("Invalid tag.")]
+    InvalidTag,
+
+    /// Occurs, when trytes can not be decoded as a transaction.
+    #[error("Invalid transaction.")]
+    InvalidTransaction,
+
+    /// Occurs, when a queried transaction is not in the tangle.
+    #[error("Unknown transaction.")]
+    UnknownTransaction,
+
+    /// Occurs, when a transaction expected to be a tail is not.
+    #[error("Not a tail transaction.")]
+    NotATail,
+
+    /// Occurs, when the ledger worker does not answer a query.
+    #[error("Ledger unavailable.")]
+    LedgerUnavailable,
+}
@@ -8,3 +8,19 @@
 // Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
+
+//! A transport-agnostic query service over the state of a node.
+//!
+//! Front-ends (HTTP, WebSocket, CLI, ...) only deal with the [`ApiService`](crate::ApiService) and the serializable
+//! response types of the [`types`](crate::types) module, never with the protocol internals.
+
+#![warn(missing_docs)]
+
+mod error;
+mod service;
+
+pub mod trytes;
+pub mod types;
+
+pub use error::Error;
+pub use service::ApiService;
@@ -0,0 +1,391 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    trytes::{hash_to_trytes, trits_to_trytes},
+    types::{
+        BalancesResponse, ConsistencyResponse, FindTransactionsResponse, InclusionStatesResponse, MilestoneResponse,
+        NodeInfoResponse, SpentAddressesResponse, TipsResponse, TransactionMetadataResponse, TransactionResponse,
+    },
+    Error,
+};
+
+use bee_crypto::ternary::Hash;
+use bee_ledger::whiteflag::LedgerWorkerEvent;
+use bee_protocol::{tangle::tangle, Milestone, MilestoneIndex, Protocol};
+use bee_tangle::traversal;
+use bee_transaction::bundled::{Address, BundledTransaction as Transaction, BundledTransactionField, Tag};
+
+use futures::channel::{mpsc, oneshot};
+
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Answers queries about the state of the node.
+///
+/// The tangle, the protocol and the ledger have to be initialized before any query is made.
+#[derive(Clone)]
+pub struct ApiService {
+    app_name: String,
+    app_version: String,
+    ledger_worker: mpsc::UnboundedSender<LedgerWorkerEvent>,
+}
+
+impl ApiService {
+    /// Creates a new service, `app_name` and `app_version` being reported as is by `node_info`.
+    pub fn new(app_name: String, app_version: String, ledger_worker: mpsc::UnboundedSender<LedgerWorkerEvent>) -> Self {
+        Self {
+            app_name,
+            app_version,
+            ledger_worker,
+        }
+    }
+
+    fn milestone_hash(index: MilestoneIndex) -> String {
+        hash_to_trytes(&tangle().get_milestone_hash(index).unwrap_or_else(Hash::zeros))
+    }
+
+    /// Returns general information about the node.
+    pub async fn node_info(&self) -> NodeInfoResponse {
+        let last_milestone_index = tangle().get_last_milestone_index();
+        let last_solid_milestone_index = tangle().get_last_solid_milestone_index();
+
+        NodeInfoResponse {
+            app_name: self.app_name.clone(),
+            app_version: self.app_version.clone(),
+            last_milestone: Self::milestone_hash(last_milestone_index),
+            last_milestone_index: *last_milestone_index,
+            last_solid_milestone: Self::milestone_hash(last_solid_milestone_index),
+            last_solid_milestone_index: *last_solid_milestone_index,
+            snapshot_milestone_index: *tangle().get_snapshot_milestone_index(),
+            is_synced: tangle().is_synced(),
+            neighbors: Protocol::num_handshaked_peers(),
+            tips: tangle().num_tips(),
+            transactions_to_request: Protocol::num_requested_transactions(),
+            transactions: tangle().len(),
+            coordinator_address: trits_to_trytes(Protocol::config().coordinator().public_key().to_inner()),
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Clock may have gone backwards")
+                .as_millis() as u64,
+        }
+    }
+
+    /// Returns the current tips of the tangle.
+    pub async fn tips(&self) -> TipsResponse {
+        TipsResponse {
+            hashes: tangle().get_tips().iter().map(hash_to_trytes).collect(),
+        }
+    }
+
+    /// Returns the transaction associated with `hash`, if it is in the tangle.
+    pub async fn transaction(&self, hash: &Hash) -> Option<TransactionResponse> {
+        tangle()
+            .get(hash)
+            .map(|transaction| TransactionResponse::new(hash, &transaction))
+    }
+
+    /// Returns the metadata of the transaction associated with `hash`, if it is in the tangle.
+    pub async fn transaction_metadata(&self, hash: &Hash) -> Option<TransactionMetadataResponse> {
+        tangle()
+            .get_metadata(hash)
+            .map(|metadata| TransactionMetadataResponse::new(hash, &metadata))
+    }
+
+    /// Returns whether each of the transactions associated with `hashes` is confirmed.
+    pub async fn inclusion_states(&self, hashes: &[Hash]) -> InclusionStatesResponse {
+        InclusionStatesResponse {
+            states: hashes
+                .iter()
+                .map(|hash| {
+                    tangle()
+                        .get_metadata(hash)
+                        .map_or(false, |metadata| metadata.flags().is_confirmed())
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the confirmed balance of each of the `addresses`.
+    pub async fn balances(&self, addresses: &[Address]) -> Result<BalancesResponse, Error> {
+        let milestone_index = *tangle().get_last_solid_milestone_index();
+        let mut balances = Vec::with_capacity(addresses.len());
+
+        for address in addresses {
+            let (sender, receiver) = oneshot::channel();
+
+            self.ledger_worker
+                .unbounded_send(LedgerWorkerEvent::GetBalance(address.clone(), sender))
+                .map_err(|_| Error::LedgerUnavailable)?;
+
+            balances.push(receiver.await.map_err(|_| Error::LedgerUnavailable)?);
+        }
+
+        Ok(BalancesResponse {
+            balances,
+            milestone_index,
+        })
+    }
+
+    /// Returns the hashes of the transactions matching all the non-empty criteria, a transaction matching a criterion
+    /// if it matches any of its values. `approvees` matches the transactions directly approving one of its hashes.
+    pub async fn find_transactions(
+        &self,
+        bundles: &[Hash],
+        addresses: &[Address],
+        tags: &[Tag],
+        approvees: &[Hash],
+    ) -> FindTransactionsResponse {
+        let matches = |transaction: &Transaction| {
+            (bundles.is_empty() || bundles.contains(transaction.bundle()))
+                && (addresses.is_empty() || addresses.contains(transaction.address()))
+                && (tags.is_empty() || tags.contains(transaction.tag()))
+        };
+
+        let hashes = if !approvees.is_empty() {
+            approvees
+                .iter()
+                .flat_map(|approvee| tangle().get_children(approvee))
+                .filter(|hash| tangle().get(hash).map_or(false, |transaction| matches(&transaction)))
+                .collect::<HashSet<Hash>>()
+                .into_iter()
+                .collect()
+        } else if !bundles.is_empty() || !addresses.is_empty() || !tags.is_empty() {
+            tangle().filter(|_, transaction, _| matches(transaction))
+        } else {
+            Vec::new()
+        };
+
+        FindTransactionsResponse {
+            hashes: hashes.iter().map(hash_to_trytes).collect(),
+        }
+    }
+
+    /// Returns whether the `tails` are solid and do not reference a conflicting bundle, their past cones being walked
+    /// down to the already confirmed transactions.
+    pub async fn check_consistency(&self, tails: &[Hash]) -> Result<ConsistencyResponse, Error> {
+        for tail in tails {
+            let metadata = tangle().get_metadata(tail).ok_or(Error::UnknownTransaction)?;
+
+            if !metadata.flags().is_tail() {
+                return Err(Error::NotATail);
+            }
+
+            if metadata.flags().is_conflicting() {
+                return Ok(ConsistencyResponse {
+                    state: false,
+                    info: Some(format!(
+                        "Tail {} references a conflicting bundle.",
+                        hash_to_trytes(tail)
+                    )),
+                });
+            }
+
+            if let Some(info) = Self::inconsistency(tail) {
+                return Ok(ConsistencyResponse {
+                    state: false,
+                    info: Some(info),
+                });
+            }
+        }
+
+        Ok(ConsistencyResponse {
+            state: true,
+            info: None,
+        })
+    }
+
+    // Walks the past cone of `tail` down to the confirmed transactions and the solid entry points, and describes the
+    // first reason found for it not to be consistent.
+    fn inconsistency(tail: &Hash) -> Option<String> {
+        let mut info = None;
+        let mut missing = None;
+
+        traversal::visit_parents_depth_first(
+            tangle(),
+            *tail,
+            |hash, _, metadata| !metadata.flags().is_confirmed() && !tangle().is_solid_entry_point(hash),
+            |hash, _, metadata| {
+                if info.is_some() {
+                    return;
+                }
+                if !metadata.flags().is_solid() {
+                    info = Some(format!("Transaction {} is not solid.", hash_to_trytes(hash)));
+                } else if metadata.flags().is_conflicting() {
+                    info = Some(format!(
+                        "Transaction {} references a conflicting bundle.",
+                        hash_to_trytes(hash)
+                    ));
+                }
+            },
+            |hash| {
+                if missing.is_none() && !tangle().is_solid_entry_point(hash) {
+                    missing = Some(*hash);
+                }
+            },
+        );
+
+        info.or_else(|| missing.map(|hash| format!("Transaction {} is missing.", hash_to_trytes(&hash))))
+    }
+
+    /// Returns whether each of the `addresses` was spent from by a confirmed and non conflicting bundle, either before
+    /// the snapshot the node started from or since.
+    pub async fn were_addresses_spent_from(&self, addresses: &[Address]) -> SpentAddressesResponse {
+        SpentAddressesResponse {
+            states: addresses
+                .iter()
+                .map(|address| tangle().is_spent_address(address))
+                .collect(),
+        }
+    }
+
+    /// Submits a transaction to the node, which validates, stores and broadcasts it like any received transaction.
+    pub async fn submit_transaction(&self, transaction: &Transaction) {
+        Protocol::submit_transaction(transaction);
+    }
+
+    /// Submits a transaction to the node, which validates and stores it like any received transaction, without
+    /// broadcasting it.
+    pub async fn store_transaction(&self, transaction: &Transaction) {
+        Protocol::store_transaction(transaction);
+    }
+
+    /// Returns the milestone of index `index`, if it is in the tangle.
+    pub async fn milestone(&self, index: MilestoneIndex) -> Option<MilestoneResponse> {
+        let hash = tangle().get_milestone_hash(index)?;
+        let transaction = tangle().get(&hash)?;
+
+        Some(MilestoneResponse::new(&Milestone::new(hash, index), &transaction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_protocol::tangle::{self, TransactionMetadata};
+    use bee_test::{field::rand_trits_field, transaction::create_random_attached_tx};
+
+    use futures::executor::block_on;
+
+    use std::sync::Once;
+
+    static INIT: Once = Once::new();
+
+    fn service() -> ApiService {
+        INIT.call_once(tangle::init);
+
+        ApiService::new("bee".to_owned(), "0.1.0".to_owned(), mpsc::unbounded().0)
+    }
+
+    fn attach(trunk: Hash, branch: Hash, update: impl Fn(&mut TransactionMetadata)) -> Hash {
+        let (hash, transaction) = create_random_attached_tx(branch, trunk);
+        let mut metadata = TransactionMetadata::new();
+
+        metadata.flags_mut().set_tail();
+        update(&mut metadata);
+        tangle().insert(transaction, hash, metadata);
+
+        hash
+    }
+
+    #[test]
+    fn consistent_cone() {
+        let service = service();
+        let sep = rand_trits_field::<Hash>();
+
+        tangle().add_solid_entry_point(sep, MilestoneIndex(0));
+
+        let confirmed = attach(sep, sep, |metadata| {
+            metadata.flags_mut().set_solid();
+            metadata.flags_mut().set_confirmed();
+        });
+        let solid = attach(confirmed, sep, |metadata| metadata.flags_mut().set_solid());
+        let tail = attach(solid, confirmed, |metadata| metadata.flags_mut().set_solid());
+
+        let response = block_on(service.check_consistency(&[tail])).unwrap();
+
+        assert!(response.state);
+        assert!(response.info.is_none());
+    }
+
+    #[test]
+    fn inconsistent_cone() {
+        let service = service();
+        let sep = rand_trits_field::<Hash>();
+        let unknown = rand_trits_field::<Hash>();
+
+        tangle().add_solid_entry_point(sep, MilestoneIndex(0));
+
+        let not_solid = attach(sep, sep, |_| ());
+        let tail = attach(not_solid, sep, |metadata| metadata.flags_mut().set_solid());
+
+        let response = block_on(service.check_consistency(&[tail])).unwrap();
+
+        assert!(!response.state);
+        assert_eq!(
+            response.info.unwrap(),
+            format!("Transaction {} is not solid.", hash_to_trytes(&not_solid))
+        );
+
+        let tail = attach(unknown, sep, |metadata| metadata.flags_mut().set_solid());
+
+        let response = block_on(service.check_consistency(&[tail])).unwrap();
+
+        assert!(!response.state);
+        assert_eq!(
+            response.info.unwrap(),
+            format!("Transaction {} is missing.", hash_to_trytes(&unknown))
+        );
+
+        let conflicting = attach(sep, sep, |metadata| {
+            metadata.flags_mut().set_solid();
+            metadata.flags_mut().set_confirmed();
+            metadata.flags_mut().set_conflicting();
+        });
+
+        assert!(!block_on(service.check_consistency(&[conflicting])).unwrap().state);
+    }
+
+    #[test]
+    fn consistency_errors() {
+        let service = service();
+        let sep = rand_trits_field::<Hash>();
+        let (not_tail, transaction) = create_random_attached_tx(sep, sep);
+
+        tangle().insert(transaction, not_tail, TransactionMetadata::new());
+
+        assert!(matches!(
+            block_on(service.check_consistency(&[rand_trits_field::<Hash>()])),
+            Err(Error::UnknownTransaction)
+        ));
+        assert!(matches!(
+            block_on(service.check_consistency(&[not_tail])),
+            Err(Error::NotATail)
+        ));
+    }
+
+    #[test]
+    fn spent_addresses() {
+        let service = service();
+        let spent = rand_trits_field::<Address>();
+        let unspent = rand_trits_field::<Address>();
+
+        tangle().add_spent_address(spent.clone());
+
+        assert_eq!(
+            block_on(service.were_addresses_spent_from(&[spent, unspent])).states,
+            vec![true, false]
+        );
+    }
+}
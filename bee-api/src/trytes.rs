@@ -0,0 +1,134 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Conversions between tryte strings, as exchanged with clients, and typed values.
+
+use crate::Error;
+
+use bee_crypto::ternary::{Hash, HASH_LENGTH};
+use bee_ternary::{T1B1Buf, TritBuf, Trits, TryteBuf};
+use bee_transaction::bundled::{
+    Address, BundledTransaction as Transaction, BundledTransactionField, Tag, ADDRESS_TRIT_LEN, TAG_TRIT_LEN,
+    TRANSACTION_TRIT_LEN,
+};
+
+fn trits_from_trytes(trytes: &str) -> Result<TritBuf, Error> {
+    TryteBuf::try_from_str(trytes)
+        .map(|trytes| trytes.as_trits().encode::<T1B1Buf>())
+        .map_err(|_| Error::InvalidTrytes)
+}
+
+/// Encodes trits as a tryte string.
+pub fn trits_to_trytes(trits: &Trits) -> String {
+    trits.iter_trytes().map(char::from).collect()
+}
+
+/// Encodes a hash as a tryte string.
+pub fn hash_to_trytes(hash: &Hash) -> String {
+    trits_to_trytes(hash.as_trits())
+}
+
+/// Decodes a hash from a tryte string.
+pub fn hash_from_trytes(trytes: &str) -> Result<Hash, Error> {
+    let trits = trits_from_trytes(trytes)?;
+
+    if trits.len() != HASH_LENGTH {
+        return Err(Error::InvalidHash);
+    }
+
+    Ok(Hash::from_inner_unchecked(trits))
+}
+
+/// Decodes an address from a tryte string.
+pub fn address_from_trytes(trytes: &str) -> Result<Address, Error> {
+    let trits = trits_from_trytes(trytes)?;
+
+    if trits.len() != ADDRESS_TRIT_LEN {
+        return Err(Error::InvalidAddress);
+    }
+
+    Ok(Address::from_inner_unchecked(trits))
+}
+
+/// Decodes a tag from a tryte string, short tags being padded with `9`s.
+pub fn tag_from_trytes(trytes: &str) -> Result<Tag, Error> {
+    if trytes.len() > TAG_TRIT_LEN / 3 {
+        return Err(Error::InvalidTag);
+    }
+
+    let trits = trits_from_trytes(&format!("{:9<width$}", trytes, width = TAG_TRIT_LEN / 3))?;
+
+    Ok(Tag::from_inner_unchecked(trits))
+}
+
+/// Encodes a transaction as a tryte string.
+pub fn transaction_to_trytes(transaction: &Transaction) -> String {
+    let mut trits = TritBuf::<T1B1Buf>::zeros(TRANSACTION_TRIT_LEN);
+
+    transaction.into_trits_allocated(&mut trits);
+
+    trits_to_trytes(&trits)
+}
+
+/// Decodes a transaction from a tryte string.
+pub fn transaction_from_trytes(trytes: &str) -> Result<Transaction, Error> {
+    let trits = trits_from_trytes(trytes)?;
+
+    if trits.len() != TRANSACTION_TRIT_LEN {
+        return Err(Error::InvalidTransaction);
+    }
+
+    Transaction::from_trits(&trits).map_err(|_| Error::InvalidTransaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "BHCKPSSHXCSCRPKAFBIRACQVIRTLTXEOMNRXHHQESKIRKAUEJCLSXGZKJ9CEFMRNUKDYSIBDKTDWHZEJB";
+
+    #[test]
+    fn hash_roundtrip() {
+        assert_eq!(hash_to_trytes(&hash_from_trytes(HASH).unwrap()), HASH);
+    }
+
+    #[test]
+    fn hash_invalid_trytes() {
+        assert_eq!(hash_from_trytes("ABCabc").unwrap_err(), Error::InvalidTrytes);
+    }
+
+    #[test]
+    fn hash_invalid_length() {
+        assert_eq!(hash_from_trytes(&HASH[..80]).unwrap_err(), Error::InvalidHash);
+    }
+
+    #[test]
+    fn address_invalid_length() {
+        assert_eq!(address_from_trytes("ABC").unwrap_err(), Error::InvalidAddress);
+    }
+
+    #[test]
+    fn tag_padding() {
+        let tag = tag_from_trytes("BEE").unwrap();
+
+        assert_eq!(trits_to_trytes(tag.to_inner()), "BEE999999999999999999999999");
+    }
+
+    #[test]
+    fn tag_invalid_length() {
+        assert_eq!(tag_from_trytes(&HASH[..28]).unwrap_err(), Error::InvalidTag);
+    }
+
+    #[test]
+    fn transaction_invalid_length() {
+        assert_eq!(transaction_from_trytes(HASH).unwrap_err(), Error::InvalidTransaction);
+    }
+}
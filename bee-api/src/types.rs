@@ -0,0 +1,254 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Serializable response types of the API, hashes, addresses and transactions being encoded as tryte strings.
+
+use crate::trytes::{hash_to_trytes, transaction_to_trytes, trits_to_trytes};
+
+use bee_crypto::ternary::Hash;
+use bee_protocol::{tangle::TransactionMetadata, Milestone};
+use bee_transaction::{
+    bundled::{BundledTransaction as Transaction, BundledTransactionField},
+    Vertex,
+};
+
+use serde::Serialize;
+
+/// General information about the node.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfoResponse {
+    /// Name of the node software.
+    pub app_name: String,
+    /// Version of the node software.
+    pub app_version: String,
+    /// Hash of the last known milestone.
+    pub last_milestone: String,
+    /// Index of the last known milestone.
+    pub last_milestone_index: u32,
+    /// Hash of the last solid milestone.
+    pub last_solid_milestone: String,
+    /// Index of the last solid milestone.
+    pub last_solid_milestone_index: u32,
+    /// Index of the milestone of the snapshot the node started from.
+    pub snapshot_milestone_index: u32,
+    /// Whether the last solid milestone is the last known milestone.
+    pub is_synced: bool,
+    /// Number of handshaked peers.
+    pub neighbors: usize,
+    /// Number of tips.
+    pub tips: usize,
+    /// Number of transactions requested and not yet received.
+    pub transactions_to_request: usize,
+    /// Number of transactions in the tangle.
+    pub transactions: usize,
+    /// Address of the coordinator.
+    pub coordinator_address: String,
+    /// Current time of the node, in milliseconds since the Unix epoch.
+    pub time: u64,
+}
+
+/// The current tips of the tangle.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TipsResponse {
+    /// Hashes of the tips.
+    pub hashes: Vec<String>,
+}
+
+/// A transaction of the tangle.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionResponse {
+    /// Hash of the transaction.
+    pub hash: String,
+    /// Raw trytes of the transaction.
+    pub trytes: String,
+    /// Address of the transaction.
+    pub address: String,
+    /// Value of the transaction.
+    pub value: i64,
+    /// Tag of the transaction.
+    pub tag: String,
+    /// Timestamp of the transaction.
+    pub timestamp: u64,
+    /// Index of the transaction in its bundle.
+    pub current_index: usize,
+    /// Index of the last transaction of its bundle.
+    pub last_index: usize,
+    /// Hash of its bundle.
+    pub bundle: String,
+    /// Hash of its trunk.
+    pub trunk: String,
+    /// Hash of its branch.
+    pub branch: String,
+}
+
+impl TransactionResponse {
+    /// Creates the response of a transaction.
+    pub fn new(hash: &Hash, transaction: &Transaction) -> Self {
+        Self {
+            hash: hash_to_trytes(hash),
+            trytes: transaction_to_trytes(transaction),
+            address: trits_to_trytes(transaction.address().to_inner()),
+            value: *transaction.value().to_inner(),
+            tag: trits_to_trytes(transaction.tag().to_inner()),
+            timestamp: transaction.get_timestamp(),
+            current_index: *transaction.index().to_inner(),
+            last_index: *transaction.last_index().to_inner(),
+            bundle: hash_to_trytes(transaction.bundle()),
+            trunk: hash_to_trytes(transaction.trunk()),
+            branch: hash_to_trytes(transaction.branch()),
+        }
+    }
+}
+
+/// The state of a transaction as seen by the node.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMetadataResponse {
+    /// Hash of the transaction.
+    pub hash: String,
+    /// Whether the transaction and its whole past cone are known.
+    pub solid: bool,
+    /// Whether the transaction is the tail of its bundle.
+    pub tail: bool,
+    /// Whether the transaction was requested from a peer.
+    pub requested: bool,
+    /// Whether the transaction is a milestone.
+    pub milestone: bool,
+    /// Whether the transaction is confirmed, i.e. included.
+    pub confirmed: bool,
+    /// Whether the transaction is confirmed but its ledger mutations were ignored.
+    pub conflicting: bool,
+    /// Index of the milestone that confirmed the transaction or of the milestone it is.
+    pub milestone_index: u32,
+    /// Time of arrival, in milliseconds since the Unix epoch.
+    pub arrival_timestamp: u64,
+    /// Time of solidification, in milliseconds since the Unix epoch.
+    pub solidification_timestamp: u64,
+    /// Time of confirmation, as given by the confirming milestone.
+    pub confirmation_timestamp: u64,
+}
+
+impl TransactionMetadataResponse {
+    /// Creates the response of the metadata of a transaction.
+    pub fn new(hash: &Hash, metadata: &TransactionMetadata) -> Self {
+        Self {
+            hash: hash_to_trytes(hash),
+            solid: metadata.flags().is_solid(),
+            tail: metadata.flags().is_tail(),
+            requested: metadata.flags().is_requested(),
+            milestone: metadata.flags().is_milestone(),
+            confirmed: metadata.flags().is_confirmed(),
+            conflicting: metadata.flags().is_conflicting(),
+            milestone_index: *metadata.milestone_index(),
+            arrival_timestamp: metadata.arrival_timestamp(),
+            solidification_timestamp: metadata.solidification_timestamp(),
+            confirmation_timestamp: metadata.confirmation_timestamp(),
+        }
+    }
+}
+
+/// The inclusion states of a list of transactions, in the order they were queried.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionStatesResponse {
+    /// Whether each transaction is confirmed, unknown transactions being reported as not included.
+    pub states: Vec<bool>,
+}
+
+/// The hashes of the transactions matching a query.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindTransactionsResponse {
+    /// Hashes of the matching transactions.
+    pub hashes: Vec<String>,
+}
+
+/// Whether a list of tails can be approved together.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyResponse {
+    /// Whether the tails are consistent.
+    pub state: bool,
+    /// Reason of the inconsistency, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+}
+
+/// Whether a list of addresses were spent from, in the order they were queried.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpentAddressesResponse {
+    /// Whether each address was spent from by a confirmed transaction.
+    pub states: Vec<bool>,
+}
+
+/// The balances of a list of addresses, in the order they were queried.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalancesResponse {
+    /// Balance of each address.
+    pub balances: Vec<u64>,
+    /// Index of the last solid milestone at the time of the query.
+    pub milestone_index: u32,
+}
+
+/// A milestone.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneResponse {
+    /// Index of the milestone.
+    pub index: u32,
+    /// Hash of the tail transaction of the milestone.
+    pub hash: String,
+    /// Timestamp of the milestone.
+    pub timestamp: u64,
+}
+
+impl MilestoneResponse {
+    pub(crate) fn new(milestone: &Milestone, transaction: &Transaction) -> Self {
+        Self {
+            index: *milestone.index(),
+            hash: hash_to_trytes(milestone.hash()),
+            timestamp: transaction.get_timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_camel_case() {
+        let response = BalancesResponse {
+            balances: vec![0, 42],
+            milestone_index: 7,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            r#"{"balances":[0,42],"milestoneIndex":7}"#
+        );
+    }
+
+    #[test]
+    fn serialize_skip_none() {
+        let response = ConsistencyResponse {
+            state: true,
+            info: None,
+        };
+
+        assert_eq!(serde_json::to_string(&response).unwrap(), r#"{"state":true}"#);
+    }
+}
@@ -9,12 +9,15 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::whiteflag::{bundle::load_bundle_builder, metadata::WhiteFlagMetadata, worker::LedgerWorker};
+use crate::whiteflag::{bundle::load_bundle_builder, metadata::WhiteFlagMetadata, worker::LedgerWorker, WhiteFlag};
 
 use bee_crypto::ternary::Hash;
-use bee_protocol::tangle::tangle;
+use bee_protocol::{
+    event::{TransactionConfirmed, TransactionConflicting},
+    tangle::tangle,
+};
 use bee_transaction::{
-    bundled::{Bundle, IncomingBundleBuilderError},
+    bundled::{Bundle, BundledTransactionField, IncomingBundleBuilderError},
     Vertex,
 };
 
@@ -57,6 +60,12 @@ impl LedgerWorker {
                 }
 
                 metadata.tails_included.push(*hash);
+
+                for transaction in bundle {
+                    if *transaction.value().to_inner() < 0 {
+                        tangle().add_spent_address(transaction.address().clone());
+                    }
+                }
             }
         }
 
@@ -73,6 +82,19 @@ impl LedgerWorker {
             // TODO Set OTRSI, ...
             // TODO increment metrics confirmed, zero, value and conflict.
         });
+
+        if let Some(meta) = tangle().get_metadata(&hash) {
+            if conflicting {
+                WhiteFlag::get().bus.dispatch(TransactionConflicting {
+                    hash: *hash,
+                    metadata: meta,
+                });
+            }
+            WhiteFlag::get().bus.dispatch(TransactionConfirmed {
+                hash: *hash,
+                metadata: meta,
+            });
+        }
     }
 
     pub(crate) fn visit_bundles_dfs(&mut self, root: Hash, metadata: &mut WhiteFlagMetadata) -> Result<(), Error> {
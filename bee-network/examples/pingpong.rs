@@ -25,7 +25,7 @@
 
 use bee_common::shutdown::Shutdown;
 use bee_network::{
-    Command::*, EndpointId as EpId, Event, EventSubscriber as Events, Network, NetworkConfig, Origin, Url,
+    Command::*, EndpointId as EpId, Event, EventSubscriber as Events, Identity, Network, NetworkConfig, Origin, Url,
 };
 
 use common::*;
@@ -44,7 +44,7 @@ fn main() {
 
     logger::init(log::LevelFilter::Info);
 
-    let (network, events) = bee_network::init(NetworkConfig::build().finish(), &mut shutdown);
+    let (network, events) = bee_network::init(NetworkConfig::build().finish(), Identity::generate(), &mut shutdown);
 
     let mut node = Node::builder()
         .with_network(network.clone())
@@ -114,7 +114,14 @@ impl Node {
     }
 
     pub async fn add_peer(&mut self, url: Url) {
-        self.network.send(AddEndpoint { url, responder: None }).await.unwrap();
+        self.network
+            .send(AddEndpoint {
+                url,
+                public_key: None,
+                responder: None,
+            })
+            .await
+            .unwrap();
     }
 
     pub async fn shutdown(self) {
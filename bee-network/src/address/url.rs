@@ -11,7 +11,7 @@
 
 use super::{errors::*, Address};
 
-use url::Url as ExternUrl;
+use url::{Host, Url as ExternUrl};
 
 use std::fmt;
 
@@ -33,14 +33,14 @@ impl Protocol {
 }
 
 /// Represents various types of `Url`s.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[non_exhaustive]
-pub enum Url {
-    /// A TCP Url.
-    Tcp(Address),
-
-    /// A UDP Url.
-    Udp(Address),
+///
+/// NOTE: The host name a `Url` was created from is kept, so that it can be resolved again later, e.g. if it points to
+/// a dynamic IP address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Url {
+    address: Address,
+    protocol: Protocol,
+    host: Option<String>,
 }
 
 const TCP: &str = "tcp";
@@ -49,9 +49,10 @@ const UDP: &str = "udp";
 impl Url {
     /// Creates a new `Url`.
     pub fn new(addr: Address, proto: Protocol) -> Self {
-        match proto {
-            Protocol::Tcp => Url::Tcp(addr),
-            Protocol::Udp => Url::Udp(addr),
+        Self {
+            address: addr,
+            protocol: proto,
+            host: None,
         }
     }
 
@@ -60,17 +61,28 @@ impl Url {
     /// NOTE: This function expects an input of the format: tcp://example.com:15600.
     pub async fn from_url_str(url: &str) -> AddressResult<Self> {
         if let Ok(url) = ExternUrl::parse(url) {
-            let host = url.host_str().ok_or(AddressError::UrlDestructFailure)?;
+            let host = url.host().ok_or(AddressError::UrlDestructFailure)?;
             let port = url.port().ok_or(AddressError::UrlDestructFailure)?;
 
-            let host_port = &format!("{}:{}", host, port)[..];
-            let addr = Address::from_addr_str(host_port).await?;
-
-            match url.scheme() {
-                TCP => Ok(Url::new(addr, Protocol::Tcp)),
-                UDP => Ok(Url::new(addr, Protocol::Udp)),
-                _ => Err(AddressError::UnsupportedProtocol),
-            }
+            let protocol = match url.scheme() {
+                TCP => Protocol::Tcp,
+                UDP => Protocol::Udp,
+                _ => return Err(AddressError::UnsupportedProtocol),
+            };
+
+            let host = match host {
+                Host::Domain(domain) => Some(format!("{}:{}", domain, port)),
+                Host::Ipv4(_) | Host::Ipv6(_) => None,
+            };
+
+            let host_port = &format!("{}:{}", url.host_str().ok_or(AddressError::UrlDestructFailure)?, port)[..];
+            let address = Address::from_addr_str(host_port).await?;
+
+            Ok(Self {
+                address,
+                protocol,
+                host,
+            })
         } else {
             Err(AddressError::UrlParseFailure)
         }
@@ -78,25 +90,26 @@ impl Url {
 
     /// Returns the `Address` of this `Url`.
     pub fn address(&self) -> Address {
-        match *self {
-            Url::Tcp(address) | Url::Udp(address) => address,
-        }
+        self.address
     }
 
     /// Returns the `Protocol` of this `Url`.
     pub fn protocol(&self) -> Protocol {
-        match *self {
-            Url::Tcp(_) => Protocol::Tcp,
-            Url::Udp(_) => Protocol::Udp,
-        }
+        self.protocol
+    }
+
+    /// Returns the host name and port (e.g. "example.com:15600") this `Url` was created from, if it wasn't created
+    /// from an IP address.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
     }
 }
 
 impl fmt::Display for Url {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Url::Tcp(ref address) => write!(f, "tcp://{}", address),
-            Url::Udp(ref address) => write!(f, "udp://{}", address),
+        match self.protocol {
+            Protocol::Tcp => write!(f, "tcp://{}", self.address),
+            Protocol::Udp => write!(f, "udp://{}", self.address),
         }
     }
 }
@@ -154,4 +167,21 @@ mod tests {
         assert_eq!(Protocol::Tcp, url.protocol());
         assert_eq!("tcp://[::1]:15600", url.to_string());
     }
+
+    #[test]
+    fn keep_host_of_url_from_domain_name() {
+        let url = block_on(Url::from_url_str("tcp://localhost:15600"));
+        let url = url.expect("parsing url failed");
+
+        assert_eq!(Some("localhost:15600"), url.host());
+        assert_eq!(15600, *url.address().port());
+    }
+
+    #[test]
+    fn no_host_of_url_from_ip_address() {
+        let url = block_on(Url::from_url_str("tcp://[::1]:15600"));
+        let url = url.expect("parsing url failed");
+
+        assert_eq!(None, url.host());
+    }
 }
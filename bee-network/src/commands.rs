@@ -9,11 +9,15 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{address::url::Url, endpoint::EndpointId};
+use crate::{
+    address::url::Url,
+    endpoint::{state::ConnectionState, Endpoint, EndpointId},
+    identity::PublicKey,
+};
 
 use futures::channel::{mpsc, oneshot};
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
 // TODO: do not expose `futures::Sender, futures::Receiver` directly to make sure
 // we can version up independently
@@ -37,6 +41,9 @@ pub enum Command {
         /// `Url` of the `Endpoint`.
         url: Url,
 
+        /// The public key the `Endpoint` is pinned to, connections authenticated by another key being refused.
+        public_key: Option<PublicKey>,
+
         /// Result responder.
         responder: Option<Responder<bool>>,
     },
@@ -68,6 +75,19 @@ pub enum Command {
         responder: Option<Responder<bool>>,
     },
 
+    /// Bans an `Endpoint`, disconnecting it and refusing connections from and to it for a while. An authenticated
+    /// endpoint is banned by its public key, otherwise by its IP address.
+    BanEndpoint {
+        /// The id of the `Endpoint` to ban.
+        epid: EndpointId,
+
+        /// How long the ban lasts.
+        duration: Duration,
+
+        /// Result responder.
+        responder: Option<Responder<bool>>,
+    },
+
     /// Sends a message to a connected `Endpoint`.
     SendMessage {
         /// The id of the `Endpoint` to send the message to.
@@ -101,6 +121,21 @@ pub enum Command {
         /// Result responder.
         responder: Option<Responder<bool>>,
     },
+
+    /// Queries the connection state of an `Endpoint`.
+    QueryEndpointState {
+        /// The id of the `Endpoint` to query.
+        epid: EndpointId,
+
+        /// Responds with the connection state, or `None` if the `Endpoint` isn't a contact.
+        responder: Responder<Option<ConnectionState>>,
+    },
+
+    /// Lists the contacts, i.e. the added `Endpoint`s, whether connected or not.
+    ListEndpoints {
+        /// Responds with each contact and its connection state.
+        responder: Responder<Vec<(Endpoint, ConnectionState)>>,
+    },
 }
 
 impl fmt::Display for Command {
@@ -114,6 +149,13 @@ impl fmt::Display for Command {
 
             Command::Disconnect { epid, .. } => write!(f, "Command::Disconnect {{ {} }}", epid),
 
+            Command::BanEndpoint { epid, duration, .. } => write!(
+                f,
+                "Command::BanEndpoint {{ {}, duration: {}s }}",
+                epid,
+                duration.as_secs()
+            ),
+
             Command::SendMessage { epid, .. } => write!(f, "Command::SendMessage {{ {} }}", epid),
 
             Command::MulticastMessage { epids, .. } => {
@@ -121,6 +163,10 @@ impl fmt::Display for Command {
             }
 
             Command::BroadcastMessage { .. } => write!(f, "Command::BroadcastMessage"),
+
+            Command::QueryEndpointState { epid, .. } => write!(f, "Command::QueryEndpointState {{ {} }}", epid),
+
+            Command::ListEndpoints { .. } => write!(f, "Command::ListEndpoints"),
         }
     }
 }
@@ -155,7 +201,11 @@ mod tests {
 
         spawn(async move {
             sender
-                .send(Command::AddEndpoint { url, responder: None })
+                .send(Command::AddEndpoint {
+                    url,
+                    public_key: None,
+                    responder: None,
+                })
                 .await
                 .unwrap();
         });
@@ -187,6 +237,7 @@ mod tests {
             sender
                 .send(Command::AddEndpoint {
                     url,
+                    public_key: None,
                     responder: Some(responder),
                 })
                 .await
@@ -197,7 +248,7 @@ mod tests {
         spawn(async move {
             while let Some(command) = receiver.next().await {
                 match command {
-                    Command::AddEndpoint { url, responder } => {
+                    Command::AddEndpoint { url, responder, .. } => {
                         assert_eq!(URL, url.to_string(), "Unexpected URL");
                         received_command = true;
 
@@ -9,7 +9,10 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::address::{Address, Port};
+use crate::{
+    address::{Address, Port},
+    endpoint::outbox::OutboxPolicy,
+};
 
 use serde::Deserialize;
 
@@ -21,6 +24,19 @@ pub struct NetworkConfigBuilder {
     binding_port: Option<u16>,
     binding_addr: Option<IpAddr>,
     reconnect_interval: Option<u64>,
+    max_reconnect_interval: Option<u64>,
+    max_reconnect_attempts: Option<u32>,
+    resolve_interval: Option<u64>,
+    secure: Option<bool>,
+    max_inbound_connections: Option<usize>,
+    max_outbound_connections: Option<usize>,
+    max_connection_attempts: Option<u32>,
+    outbox_capacity: Option<usize>,
+    outbox_policy: Option<OutboxPolicy>,
+    max_upload_rate: Option<u64>,
+    max_download_rate: Option<u64>,
+    max_peer_upload_rate: Option<u64>,
+    max_peer_download_rate: Option<u64>,
 }
 
 impl NetworkConfigBuilder {
@@ -46,12 +62,97 @@ impl NetworkConfigBuilder {
         self
     }
 
-    /// Sets the interval (in seconds) reconnection attempts occur.
+    /// Sets the interval (in seconds) before the first reconnection attempt, which doubles after each failed one.
     pub fn reconnect_interval(mut self, interval: u64) -> Self {
         self.reconnect_interval.replace(interval);
         self
     }
 
+    /// Sets the maximum interval (in seconds) between reconnection attempts.
+    pub fn max_reconnect_interval(mut self, interval: u64) -> Self {
+        self.max_reconnect_interval.replace(interval);
+        self
+    }
+
+    /// Sets the number of failed reconnection attempts in a row after which an endpoint is only connected manually, 0
+    /// meaning unlimited.
+    pub fn max_reconnect_attempts(mut self, max: u32) -> Self {
+        self.max_reconnect_attempts.replace(max);
+        self
+    }
+
+    /// Sets the interval (in seconds) host names of endpoints are resolved again.
+    pub fn resolve_interval(mut self, interval: u64) -> Self {
+        self.resolve_interval.replace(interval);
+        self
+    }
+
+    /// Sets whether TCP connections are encrypted and authenticated with the node identity, which is opt-in since
+    /// both sides must enable it to connect.
+    ///
+    /// NOTE: The identity of a peer is only ever taken from a secure connection, so without one peers can only be told
+    /// apart by their address and endpoints can't be pinned to a public key.
+    ///
+    /// NOTE: UDP has no secure channel yet, so UDP endpoints can only be added if connections aren't secure.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure.replace(secure);
+        self
+    }
+
+    /// Sets the maximum number of inbound connections.
+    pub fn max_inbound_connections(mut self, max: usize) -> Self {
+        self.max_inbound_connections.replace(max);
+        self
+    }
+
+    /// Sets the maximum number of outbound connections.
+    pub fn max_outbound_connections(mut self, max: usize) -> Self {
+        self.max_outbound_connections.replace(max);
+        self
+    }
+
+    /// Sets the maximum number of connection attempts per IP address and minute.
+    pub fn max_connection_attempts(mut self, max: u32) -> Self {
+        self.max_connection_attempts.replace(max);
+        self
+    }
+
+    /// Sets the number of messages that can be queued for a single endpoint.
+    pub fn outbox_capacity(mut self, capacity: usize) -> Self {
+        self.outbox_capacity.replace(capacity);
+        self
+    }
+
+    /// Sets what happens to messages for an endpoint whose outbox is full.
+    pub fn outbox_policy(mut self, policy: OutboxPolicy) -> Self {
+        self.outbox_policy.replace(policy);
+        self
+    }
+
+    /// Sets the maximum upload rate (in bytes per second) over all connections, 0 meaning unlimited.
+    pub fn max_upload_rate(mut self, rate: u64) -> Self {
+        self.max_upload_rate.replace(rate);
+        self
+    }
+
+    /// Sets the maximum download rate (in bytes per second) over all connections, 0 meaning unlimited.
+    pub fn max_download_rate(mut self, rate: u64) -> Self {
+        self.max_download_rate.replace(rate);
+        self
+    }
+
+    /// Sets the maximum upload rate (in bytes per second) of a single connection, 0 meaning unlimited.
+    pub fn max_peer_upload_rate(mut self, rate: u64) -> Self {
+        self.max_peer_upload_rate.replace(rate);
+        self
+    }
+
+    /// Sets the maximum download rate (in bytes per second) of a single connection, 0 meaning unlimited.
+    pub fn max_peer_download_rate(mut self, rate: u64) -> Self {
+        self.max_peer_download_rate.replace(rate);
+        self
+    }
+
     /// Builds the network config.
     pub fn finish(self) -> NetworkConfig {
         NetworkConfig {
@@ -61,6 +162,43 @@ impl NetworkConfigBuilder {
                 self.reconnect_interval
                     .unwrap_or(crate::constants::DEFAULT_RECONNECT_INTERVAL),
             ),
+            max_reconnect_interval: Duration::from_secs(
+                self.max_reconnect_interval
+                    .unwrap_or(crate::constants::DEFAULT_MAX_RECONNECT_INTERVAL),
+            ),
+            max_reconnect_attempts: self
+                .max_reconnect_attempts
+                .unwrap_or(crate::constants::DEFAULT_MAX_RECONNECT_ATTEMPTS),
+            resolve_interval: Duration::from_secs(
+                self.resolve_interval
+                    .unwrap_or(crate::constants::DEFAULT_RESOLVE_INTERVAL),
+            ),
+            secure: self.secure.unwrap_or(crate::constants::DEFAULT_SECURE),
+            max_inbound_connections: self
+                .max_inbound_connections
+                .unwrap_or(crate::constants::DEFAULT_MAX_INBOUND_CONNECTIONS),
+            max_outbound_connections: self
+                .max_outbound_connections
+                .unwrap_or(crate::constants::DEFAULT_MAX_OUTBOUND_CONNECTIONS),
+            max_connection_attempts: self
+                .max_connection_attempts
+                .unwrap_or(crate::constants::DEFAULT_MAX_CONNECTION_ATTEMPTS),
+            outbox_capacity: self
+                .outbox_capacity
+                .unwrap_or(crate::constants::DEFAULT_OUTBOX_CAPACITY),
+            outbox_policy: self.outbox_policy.unwrap_or(crate::constants::DEFAULT_OUTBOX_POLICY),
+            max_upload_rate: self
+                .max_upload_rate
+                .unwrap_or(crate::constants::DEFAULT_MAX_UPLOAD_RATE),
+            max_download_rate: self
+                .max_download_rate
+                .unwrap_or(crate::constants::DEFAULT_MAX_DOWNLOAD_RATE),
+            max_peer_upload_rate: self
+                .max_peer_upload_rate
+                .unwrap_or(crate::constants::DEFAULT_MAX_PEER_UPLOAD_RATE),
+            max_peer_download_rate: self
+                .max_peer_download_rate
+                .unwrap_or(crate::constants::DEFAULT_MAX_PEER_DOWNLOAD_RATE),
         }
     }
 }
@@ -71,6 +209,19 @@ pub struct NetworkConfig {
     pub(crate) binding_port: Port,
     pub(crate) binding_addr: IpAddr,
     pub(crate) reconnect_interval: Duration,
+    pub(crate) max_reconnect_interval: Duration,
+    pub(crate) max_reconnect_attempts: u32,
+    pub(crate) resolve_interval: Duration,
+    pub(crate) secure: bool,
+    pub(crate) max_inbound_connections: usize,
+    pub(crate) max_outbound_connections: usize,
+    pub(crate) max_connection_attempts: u32,
+    pub(crate) outbox_capacity: usize,
+    pub(crate) outbox_policy: OutboxPolicy,
+    pub(crate) max_upload_rate: u64,
+    pub(crate) max_download_rate: u64,
+    pub(crate) max_peer_upload_rate: u64,
+    pub(crate) max_peer_download_rate: u64,
 }
 
 impl NetworkConfig {
@@ -97,8 +248,73 @@ impl NetworkConfig {
         self.binding_addr
     }
 
-    /// Returns the interval between reconnect attempts.
+    /// Returns the interval before the first reconnect attempt.
     pub fn reconnect_interval(&self) -> Duration {
         self.reconnect_interval
     }
+
+    /// Returns the maximum interval between reconnect attempts.
+    pub fn max_reconnect_interval(&self) -> Duration {
+        self.max_reconnect_interval
+    }
+
+    /// Returns the number of failed reconnect attempts in a row after which an endpoint is parked.
+    pub fn max_reconnect_attempts(&self) -> u32 {
+        self.max_reconnect_attempts
+    }
+
+    /// Returns the interval between resolutions of the host names of endpoints.
+    pub fn resolve_interval(&self) -> Duration {
+        self.resolve_interval
+    }
+
+    /// Returns whether TCP connections are encrypted and authenticated.
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Returns the maximum number of inbound connections.
+    pub fn max_inbound_connections(&self) -> usize {
+        self.max_inbound_connections
+    }
+
+    /// Returns the maximum number of outbound connections.
+    pub fn max_outbound_connections(&self) -> usize {
+        self.max_outbound_connections
+    }
+
+    /// Returns the maximum number of connection attempts per IP address and minute.
+    pub fn max_connection_attempts(&self) -> u32 {
+        self.max_connection_attempts
+    }
+
+    /// Returns the number of messages that can be queued for a single endpoint.
+    pub fn outbox_capacity(&self) -> usize {
+        self.outbox_capacity
+    }
+
+    /// Returns what happens to messages for an endpoint whose outbox is full.
+    pub fn outbox_policy(&self) -> OutboxPolicy {
+        self.outbox_policy
+    }
+
+    /// Returns the maximum upload rate (in bytes per second) over all connections.
+    pub fn max_upload_rate(&self) -> u64 {
+        self.max_upload_rate
+    }
+
+    /// Returns the maximum download rate (in bytes per second) over all connections.
+    pub fn max_download_rate(&self) -> u64 {
+        self.max_download_rate
+    }
+
+    /// Returns the maximum upload rate (in bytes per second) of a single connection.
+    pub fn max_peer_upload_rate(&self) -> u64 {
+        self.max_peer_upload_rate
+    }
+
+    /// Returns the maximum download rate (in bytes per second) of a single connection.
+    pub fn max_peer_download_rate(&self) -> u64 {
+        self.max_peer_download_rate
+    }
 }
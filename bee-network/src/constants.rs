@@ -9,7 +9,12 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use std::net::{IpAddr, Ipv4Addr};
+use crate::endpoint::outbox::OutboxPolicy;
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
 
 pub(crate) const DEFAULT_BINDING_PORT: u16 = 15600;
 pub(crate) const DEFAULT_BINDING_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0));
@@ -17,4 +22,30 @@ pub(crate) const DEFAULT_BINDING_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0
 pub(crate) const MAX_BUFFER_SIZE: usize = 1654;
 pub(crate) const BYTES_CHANNEL_CAPACITY: usize = 10000;
 
+// NOTE: The IPv6 minimum MTU of 1280 bytes minus the IPv6 and UDP headers, so that datagrams don't get fragmented on
+// any path, including tunnels and VPNs whose MTU is below the Ethernet one.
+pub(crate) const MAX_DATAGRAM_SIZE: usize = 1232;
+
+// NOTE: Time given to a TCP connection to be established, and to a secure handshake to be completed.
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+pub(crate) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub(crate) const DEFAULT_RECONNECT_INTERVAL: u64 = 60;
+pub(crate) const DEFAULT_MAX_RECONNECT_INTERVAL: u64 = 600;
+// NOTE: 0 means that endpoints are never parked.
+pub(crate) const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 0;
+pub(crate) const DEFAULT_RESOLVE_INTERVAL: u64 = 300;
+pub(crate) const DEFAULT_SECURE: bool = false;
+pub(crate) const DEFAULT_MAX_INBOUND_CONNECTIONS: usize = 8;
+pub(crate) const DEFAULT_MAX_OUTBOUND_CONNECTIONS: usize = 8;
+pub(crate) const DEFAULT_MAX_CONNECTION_ATTEMPTS: u32 = 10;
+pub(crate) const DEFAULT_OUTBOX_CAPACITY: usize = 1000;
+pub(crate) const DEFAULT_OUTBOX_POLICY: OutboxPolicy = OutboxPolicy::Drop;
+// NOTE: A rate of 0 bytes per second means unlimited.
+pub(crate) const DEFAULT_MAX_UPLOAD_RATE: u64 = 0;
+pub(crate) const DEFAULT_MAX_DOWNLOAD_RATE: u64 = 0;
+pub(crate) const DEFAULT_MAX_PEER_UPLOAD_RATE: u64 = 0;
+pub(crate) const DEFAULT_MAX_PEER_DOWNLOAD_RATE: u64 = 0;
+
+// NOTE: The window in which the connection attempts from an IP address are limited.
+pub(crate) const CONNECTION_ATTEMPTS_WINDOW: Duration = Duration::from_secs(60);
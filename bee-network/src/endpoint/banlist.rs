@@ -0,0 +1,115 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{address::Address, identity::PublicKey};
+
+use async_std::net::IpAddr;
+use dashmap::DashMap;
+
+use std::time::{Duration, Instant};
+
+/// What a ban applies to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Banned {
+    /// Every connection from and to an IP address.
+    Address(IpAddr),
+
+    /// Every secure connection authenticated with a public key, whatever its address.
+    PublicKey(PublicKey),
+}
+
+impl Banned {
+    /// Returns what an endpoint is banned by: its public key if it is authenticated, since several endpoints might
+    /// share an IP address, e.g. behind a NAT, otherwise its IP address.
+    pub fn endpoint(address: &Address, public_key: Option<PublicKey>) -> Self {
+        match public_key {
+            Some(public_key) => Banned::PublicKey(public_key),
+            None => Banned::Address(address.ip()),
+        }
+    }
+}
+
+/// IP addresses and public keys that are banned until a certain point in time.
+///
+/// NOTE: It is shared between the endpoint worker, which bans endpoints, and the TCP worker, which refuses their
+/// connection attempts.
+#[derive(Default)]
+pub struct BanList {
+    inner: DashMap<Banned, Instant>,
+}
+
+impl BanList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bans an address or public key for a certain duration, replacing any earlier ban. Returns whether it was banned
+    /// already.
+    pub fn insert(&self, banned: Banned, duration: Duration) -> bool {
+        self.inner.insert(banned, Instant::now() + duration).is_some()
+    }
+
+    /// Lifts the ban of an address or public key if it expired. A ban that has been renewed in the meantime is kept.
+    pub fn remove_expired(&self, banned: &Banned) -> bool {
+        self.inner
+            .remove_if(banned, |_, until| *until <= Instant::now())
+            .is_some()
+    }
+
+    pub fn contains(&self, banned: &Banned) -> bool {
+        self.inner.get(banned).map_or(false, |until| *until > Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ban_and_expire() {
+        let banlist = BanList::new();
+        let addr = Banned::Address("127.0.0.1".parse().unwrap());
+
+        assert!(!banlist.insert(addr, Duration::from_secs(0)));
+        assert!(!banlist.contains(&addr));
+        assert!(banlist.remove_expired(&addr));
+        assert!(!banlist.remove_expired(&addr));
+    }
+
+    #[test]
+    fn renewed_ban_is_kept() {
+        let banlist = BanList::new();
+        let addr = Banned::Address("127.0.0.1".parse().unwrap());
+
+        banlist.insert(addr, Duration::from_secs(0));
+        assert!(banlist.insert(addr, Duration::from_secs(60)));
+
+        assert!(banlist.contains(&addr));
+        assert!(!banlist.remove_expired(&addr));
+        assert!(!banlist.contains(&Banned::Address("127.0.0.2".parse().unwrap())));
+    }
+
+    #[test]
+    fn ban_authenticated_endpoint_by_public_key() {
+        let banlist = BanList::new();
+        let address = Address::from(std::net::SocketAddr::new("127.0.0.1".parse().unwrap(), 15600));
+        let public_key = *crate::identity::Identity::generate().public_key();
+
+        banlist.insert(Banned::endpoint(&address, Some(public_key)), Duration::from_secs(60));
+
+        assert!(banlist.contains(&Banned::PublicKey(public_key)));
+        assert!(!banlist.contains(&Banned::Address(address.ip())));
+
+        banlist.insert(Banned::endpoint(&address, None), Duration::from_secs(60));
+
+        assert!(banlist.contains(&Banned::Address(address.ip())));
+    }
+}
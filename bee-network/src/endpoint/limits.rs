@@ -0,0 +1,144 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use super::{origin::Origin, EndpointId as EpId};
+
+use async_std::net::IpAddr;
+use dashmap::DashSet;
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Keeps track of the connected endpoints by origin, to enforce the maximum number of connections of each.
+///
+/// NOTE: It is shared between the endpoint worker, which keeps track of the connections, and the TCP worker, which
+/// refuses inbound connections early once the limit is reached.
+pub struct ConnectionLimits {
+    max_inbound: usize,
+    max_outbound: usize,
+    inbound: DashSet<EpId>,
+    outbound: DashSet<EpId>,
+}
+
+impl ConnectionLimits {
+    pub fn new(max_inbound: usize, max_outbound: usize) -> Self {
+        Self {
+            max_inbound,
+            max_outbound,
+            inbound: DashSet::new(),
+            outbound: DashSet::new(),
+        }
+    }
+
+    /// Returns whether another connection of that origin is allowed.
+    pub fn admits(&self, origin: &Origin) -> bool {
+        match origin {
+            Origin::Inbound => self.inbound.len() < self.max_inbound,
+            Origin::Outbound => self.outbound.len() < self.max_outbound,
+            Origin::Unbound => true,
+        }
+    }
+
+    pub fn insert(&self, epid: EpId, origin: &Origin) {
+        match origin {
+            Origin::Inbound => self.inbound.insert(epid),
+            Origin::Outbound => self.outbound.insert(epid),
+            Origin::Unbound => false,
+        };
+    }
+
+    pub fn remove(&self, epid: &EpId) {
+        self.inbound.remove(epid);
+        self.outbound.remove(epid);
+    }
+}
+
+/// Counts the connection attempts per IP address within a time window.
+pub struct ConnectionAttempts {
+    max_attempts: u32,
+    window: Duration,
+    inner: HashMap<IpAddr, (Instant, u32)>,
+}
+
+impl ConnectionAttempts {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            inner: HashMap::new(),
+        }
+    }
+
+    /// Records a connection attempt from that address, and returns whether it is within the limit.
+    pub fn record(&mut self, addr: IpAddr) -> bool {
+        let now = Instant::now();
+        let window = self.window;
+
+        self.inner.retain(|_, (start, _)| now.duration_since(*start) < window);
+
+        let (_, attempts) = self.inner.entry(addr).or_insert((now, 0));
+        *attempts += 1;
+
+        *attempts <= self.max_attempts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::address::Address;
+
+    use std::net::SocketAddr;
+
+    fn epid(port: u16) -> EpId {
+        Address::from(SocketAddr::new("127.0.0.1".parse().unwrap(), port)).into()
+    }
+
+    #[test]
+    fn limit_connections_by_origin() {
+        let limits = ConnectionLimits::new(1, 2);
+
+        limits.insert(epid(1), &Origin::Inbound);
+        limits.insert(epid(2), &Origin::Outbound);
+
+        assert!(!limits.admits(&Origin::Inbound));
+        assert!(limits.admits(&Origin::Outbound));
+
+        limits.insert(epid(3), &Origin::Outbound);
+        assert!(!limits.admits(&Origin::Outbound));
+
+        limits.remove(&epid(1));
+        assert!(limits.admits(&Origin::Inbound));
+    }
+
+    #[test]
+    fn limit_connection_attempts_per_address() {
+        let mut attempts = ConnectionAttempts::new(2, Duration::from_secs(60));
+        let addr: IpAddr = "127.0.0.1".parse().unwrap();
+
+        assert!(attempts.record(addr));
+        assert!(attempts.record(addr));
+        assert!(!attempts.record(addr));
+        assert!(attempts.record("127.0.0.2".parse().unwrap()));
+    }
+
+    #[test]
+    fn reset_connection_attempts_after_window() {
+        let mut attempts = ConnectionAttempts::new(1, Duration::from_secs(0));
+        let addr: IpAddr = "127.0.0.1".parse().unwrap();
+
+        assert!(attempts.record(addr));
+        assert!(attempts.record(addr));
+    }
+}
@@ -9,20 +9,34 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+pub mod banlist;
+pub mod limits;
 pub mod origin;
 pub mod outbox;
+pub mod state;
 pub mod store;
 pub mod whitelist;
 pub mod worker;
 
-use crate::address::{
-    url::{Protocol, Url},
-    Address,
+use crate::{
+    address::{
+        url::{Protocol, Url},
+        Address,
+    },
+    identity::PublicKey,
 };
 
+use log::warn;
+
 use std::fmt;
 
-/// The id of an `Endpoint`.
+/// The id of an `Endpoint`, which is its address.
+///
+/// NOTE: Endpoints, and the peers of the protocol on top of them, are still keyed by address, since the public key of
+/// an endpoint is only known once a secure connection is established. Pinned endpoints are whitelisted, banned and
+/// deduplicated by their authenticated public key instead, e.g. several of them behind the same NAT.
+///
+/// TODO: Key connections by their authenticated public key, and only use the address to locate the endpoint.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct EndpointId {
     inner: Address,
@@ -36,9 +50,13 @@ impl From<Address> for EndpointId {
 
 impl From<Url> for EndpointId {
     fn from(url: Url) -> Self {
-        match url {
-            Url::Tcp(socket_addr) | Url::Udp(socket_addr) => Self { inner: socket_addr },
-        }
+        Self::from(&url)
+    }
+}
+
+impl From<&Url> for EndpointId {
+    fn from(url: &Url) -> Self {
+        Self { inner: url.address() }
     }
 }
 
@@ -59,6 +77,12 @@ pub struct Endpoint {
 
     /// The protocol used to communicate with that endpoint.
     pub protocol: Protocol,
+
+    /// The host name and port the address is resolved from, if any.
+    pub host: Option<String>,
+
+    /// The public key the endpoint authenticated with, if connected securely.
+    pub public_key: Option<PublicKey>,
 }
 
 impl Endpoint {
@@ -68,6 +92,8 @@ impl Endpoint {
             id: address.into(),
             address,
             protocol,
+            host: None,
+            public_key: None,
         }
     }
     /// Creates an endpoint from a `Url`.
@@ -75,7 +101,26 @@ impl Endpoint {
         let address = url.address();
         let protocol = url.protocol();
 
-        Endpoint::new(address, protocol)
+        Endpoint {
+            host: url.host().map(ToOwned::to_owned),
+            ..Endpoint::new(address, protocol)
+        }
+    }
+
+    /// Resolves the host name of the endpoint again, and returns the new address if it changed.
+    ///
+    /// NOTE: The id of the endpoint stays the same, so that it can still be referred to after the change.
+    pub(crate) async fn resolve(&self) -> Option<Address> {
+        let host = self.host.as_ref()?;
+
+        match Address::from_addr_str(host).await {
+            Ok(address) if address != self.address => Some(address),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Resolving {} failed: {:?}.", host, e);
+                None
+            }
+        }
     }
 }
 
@@ -116,5 +161,15 @@ mod tests {
         assert_eq!("[::1]:16000", ep.id.to_string());
         assert_eq!(Protocol::Udp, ep.protocol);
         assert_eq!("[::1]:16000", ep.address.to_string());
+        assert_eq!(None, ep.host);
+    }
+
+    #[test]
+    fn resolve_unchanged_address() {
+        let url = block_on(Url::from_url_str("tcp://localhost:16000")).unwrap();
+        let ep = Endpoint::from_url(url);
+
+        assert_eq!(Some("localhost:16000".to_owned()), ep.host);
+        assert_eq!(None, block_on(ep.resolve()));
     }
 }
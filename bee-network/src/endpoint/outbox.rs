@@ -9,12 +9,14 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{constants::BYTES_CHANNEL_CAPACITY, endpoint::EndpointId as EpId};
+use crate::endpoint::EndpointId as EpId;
 
 use bee_common::worker::Error as WorkerError;
 
 use async_std::sync::Arc;
 use futures::{channel::mpsc, sink::SinkExt};
+use log::*;
+use serde::Deserialize;
 
 use std::collections::{hash_map::Entry, HashMap};
 
@@ -23,19 +25,34 @@ pub type BytesSender = mpsc::Sender<Arc<Vec<u8>>>;
 pub type BytesReceiver = mpsc::Receiver<Arc<Vec<u8>>>;
 
 // TODO: rename to `message_channel`
-pub fn bytes_channel() -> (BytesSender, BytesReceiver) {
-    mpsc::channel(BYTES_CHANNEL_CAPACITY)
+pub fn bytes_channel(capacity: usize) -> (BytesSender, BytesReceiver) {
+    mpsc::channel(capacity)
+}
+
+/// What happens to a message for an endpoint whose outbox is full.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxPolicy {
+    /// The message is dropped, so that a slow endpoint can't hold up the others.
+    Drop,
+
+    /// The message waits for free space, which also holds up the messages for all other endpoints.
+    Backpressure,
 }
 
 /// Responsible for sending messages (i.e. chunks of bytes) to the writer tasks handling the recipients.
 pub struct Outbox {
     inner: HashMap<EpId, BytesSender>,
+    policy: OutboxPolicy,
 }
 
 impl Outbox {
     /// Creates a new instance of `Self`.
-    pub fn new() -> Self {
-        Self { inner: HashMap::new() }
+    pub fn new(policy: OutboxPolicy) -> Self {
+        Self {
+            inner: HashMap::new(),
+            policy,
+        }
     }
 
     /// Inserts a new outgoing communication channel to a recipient referred to by its `EndpointId`.
@@ -64,8 +81,7 @@ impl Outbox {
     pub async fn send(&mut self, bytes: Vec<u8>, recipient: &EpId) -> Result<bool, WorkerError> {
         let bytes = Arc::new(bytes);
         if let Some(sender) = self.inner.get_mut(recipient) {
-            sender.send(bytes).await?;
-            Ok(true)
+            enqueue(sender, bytes, recipient, self.policy).await
         } else {
             Ok(false)
         }
@@ -79,10 +95,8 @@ impl Outbox {
         let bytes = Arc::new(bytes);
         let mut num_sends = 0;
 
-        // TODO: Do not block!
         for (epid, sender) in self.inner.iter_mut() {
-            if recipients.contains(epid) {
-                sender.send(Arc::clone(&bytes)).await?;
+            if recipients.contains(epid) && enqueue(sender, Arc::clone(&bytes), epid, self.policy).await? {
                 num_sends += 1;
             }
         }
@@ -98,12 +112,63 @@ impl Outbox {
         let bytes = Arc::new(bytes);
         let mut num_sends = 0;
 
-        // TODO: Do not block!
-        for (_, sender) in self.inner.iter_mut() {
-            sender.send(Arc::clone(&bytes)).await?;
-            num_sends += 1;
+        for (epid, sender) in self.inner.iter_mut() {
+            if enqueue(sender, Arc::clone(&bytes), epid, self.policy).await? {
+                num_sends += 1;
+            }
         }
 
         Ok(num_sends > 0)
     }
 }
+
+/// Queues `bytes` for the writer task of `epid`, and returns whether they were queued.
+async fn enqueue(
+    sender: &mut BytesSender,
+    bytes: Arc<Vec<u8>>,
+    epid: &EpId,
+    policy: OutboxPolicy,
+) -> Result<bool, WorkerError> {
+    match policy {
+        OutboxPolicy::Drop => match sender.try_send(bytes) {
+            Ok(()) => Ok(true),
+            Err(e) if e.is_full() => {
+                trace!("Outbox of {} is full, dropping message.", epid);
+                Ok(false)
+            }
+            Err(e) => Err(e.into_send_error().into()),
+        },
+        OutboxPolicy::Backpressure => {
+            sender.send(bytes).await?;
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::address::Address;
+
+    use async_std::{net::SocketAddr, task::block_on};
+    use futures::stream::StreamExt;
+
+    #[test]
+    fn drop_policy_drops_when_full() {
+        let epid = EpId::from(Address::from("127.0.0.1:15600".parse::<SocketAddr>().unwrap()));
+        let (sender, mut receiver) = bytes_channel(0);
+        let mut outbox = Outbox::new(OutboxPolicy::Drop);
+
+        assert!(outbox.insert(epid, sender));
+
+        block_on(async {
+            assert!(outbox.send(vec![1], &epid).await.unwrap());
+            assert!(!outbox.send(vec![2], &epid).await.unwrap());
+            assert!(!outbox.broadcast(vec![3]).await.unwrap());
+
+            assert_eq!(*receiver.next().await.unwrap(), vec![1]);
+            assert!(outbox.send(vec![4], &epid).await.unwrap());
+        });
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use super::EndpointId as EpId;
+
+use rand::Rng;
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+/// The state of the connection to an endpoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConnectionState {
+    /// Not connected, and no connection attempt is scheduled.
+    Disconnected,
+
+    /// A connection attempt is in progress.
+    Connecting,
+
+    /// Connected.
+    Connected,
+
+    /// The last connection attempts failed, and the next one is made after a delay.
+    Backoff {
+        /// The number of failed connection attempts in a row.
+        attempts: u32,
+
+        /// The delay before the next connection attempt.
+        delay: Duration,
+    },
+
+    /// Too many connection attempts failed in a row, so no more are made until connecting manually.
+    Parked,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Disconnected => write!(f, "disconnected"),
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Backoff { attempts, delay } => write!(
+                f,
+                "backing off for {}s after {} failed attempts",
+                delay.as_secs(),
+                attempts
+            ),
+            ConnectionState::Parked => write!(f, "parked"),
+        }
+    }
+}
+
+/// Exponential backoff between connection attempts, with some jitter so that endpoints don't retry in lockstep.
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    max_attempts: u32,
+}
+
+impl Backoff {
+    /// Creates a new backoff, that never parks an endpoint if `max_attempts` is 0.
+    pub fn new(initial: Duration, max: Duration, max_attempts: u32) -> Self {
+        Self {
+            initial,
+            max,
+            max_attempts,
+        }
+    }
+
+    /// Returns the delay after that many failed attempts in a row, without jitter.
+    fn base_delay(&self, attempts: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+
+        self.initial.checked_mul(factor).unwrap_or(self.max).min(self.max)
+    }
+
+    /// Returns the delay after that many failed attempts in a row, increased by up to a fifth.
+    fn delay(&self, attempts: u32) -> Duration {
+        let delay = self.base_delay(attempts);
+
+        delay + delay.mul_f64(rand::thread_rng().gen_range(0.0, 0.2))
+    }
+}
+
+/// What is kept track of for each contact.
+struct Contact {
+    state: ConnectionState,
+    // The number of failed connection attempts in a row.
+    attempts: u32,
+    // Changes whenever scheduled connection attempts become obsolete, e.g. because the endpoint is connected manually.
+    generation: u64,
+}
+
+/// Keeps track of the connection states of the contacts.
+pub struct ConnectionStates {
+    inner: HashMap<EpId, Contact>,
+    backoff: Backoff,
+    next_generation: u64,
+}
+
+impl ConnectionStates {
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            inner: HashMap::new(),
+            backoff,
+            next_generation: 0,
+        }
+    }
+
+    pub fn insert(&mut self, epid: EpId) {
+        let generation = self.next_generation();
+
+        self.inner.entry(epid).or_insert(Contact {
+            state: ConnectionState::Disconnected,
+            attempts: 0,
+            generation,
+        });
+    }
+
+    pub fn remove(&mut self, epid: &EpId) {
+        self.inner.remove(epid);
+    }
+
+    pub fn get(&self, epid: &EpId) -> Option<ConnectionState> {
+        self.inner.get(epid).map(|contact| contact.state)
+    }
+
+    /// Returns the generation of the endpoint, which connection attempts are scheduled with. Attempts scheduled with
+    /// an older generation are obsolete.
+    pub fn generation(&self, epid: &EpId) -> Option<u64> {
+        self.inner.get(epid).map(|contact| contact.generation)
+    }
+
+    pub fn connecting(&mut self, epid: &EpId) {
+        self.set(epid, ConnectionState::Connecting);
+    }
+
+    /// Marks the endpoint as connected, which resets its backoff.
+    pub fn connected(&mut self, epid: &EpId) {
+        self.set(epid, ConnectionState::Connected);
+
+        if let Some(contact) = self.inner.get_mut(epid) {
+            contact.attempts = 0;
+        }
+    }
+
+    pub fn disconnected(&mut self, epid: &EpId) {
+        self.set(epid, ConnectionState::Disconnected);
+    }
+
+    /// Resets the backoff of the endpoint, e.g. because it is connected manually, which makes the connection attempts
+    /// scheduled so far obsolete.
+    pub fn reset(&mut self, epid: &EpId) {
+        let generation = self.next_generation();
+
+        if let Some(contact) = self.inner.get_mut(epid) {
+            if let ConnectionState::Backoff { .. } | ConnectionState::Parked = contact.state {
+                contact.state = ConnectionState::Disconnected;
+            }
+            contact.attempts = 0;
+            contact.generation = generation;
+        }
+    }
+
+    /// Records a failed connection attempt, and returns the delay before the next one, or `None` if the endpoint
+    /// got parked.
+    pub fn failed(&mut self, epid: &EpId) -> Option<Duration> {
+        let backoff = &self.backoff;
+        let contact = self.inner.get_mut(epid)?;
+
+        contact.attempts += 1;
+
+        if backoff.max_attempts > 0 && contact.attempts >= backoff.max_attempts {
+            contact.state = ConnectionState::Parked;
+            None
+        } else {
+            let delay = backoff.delay(contact.attempts);
+            contact.state = ConnectionState::Backoff {
+                attempts: contact.attempts,
+                delay,
+            };
+            Some(delay)
+        }
+    }
+
+    /// Records a connection attempt that has been refused locally, e.g. because the endpoint is banned, and returns
+    /// the delay before the next one. It doesn't count as a failed attempt, so it never parks the endpoint.
+    pub fn refused(&mut self, epid: &EpId) -> Option<Duration> {
+        let backoff = &self.backoff;
+        let contact = self.inner.get_mut(epid)?;
+
+        let delay = backoff.delay(contact.attempts.max(1));
+        contact.state = ConnectionState::Backoff {
+            attempts: contact.attempts,
+            delay,
+        };
+        Some(delay)
+    }
+
+    fn set(&mut self, epid: &EpId, new_state: ConnectionState) {
+        if let Some(contact) = self.inner.get_mut(epid) {
+            contact.state = new_state;
+        }
+    }
+
+    fn next_generation(&mut self) -> u64 {
+        self.next_generation += 1;
+        self.next_generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::address::Address;
+
+    use async_std::net::SocketAddr;
+
+    fn epid() -> EpId {
+        EpId::from(Address::from("127.0.0.1:15600".parse::<SocketAddr>().unwrap()))
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let backoff = Backoff::new(Duration::from_secs(5), Duration::from_secs(60), 0);
+
+        assert_eq!(backoff.base_delay(1), Duration::from_secs(5));
+        assert_eq!(backoff.base_delay(2), Duration::from_secs(10));
+        assert_eq!(backoff.base_delay(4), Duration::from_secs(40));
+        assert_eq!(backoff.base_delay(5), Duration::from_secs(60));
+        assert_eq!(backoff.base_delay(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn jitter_only_adds_a_fifth() {
+        let backoff = Backoff::new(Duration::from_secs(10), Duration::from_secs(60), 0);
+
+        for _ in 0..100 {
+            let delay = backoff.delay(1);
+            assert!(delay >= Duration::from_secs(10));
+            assert!(delay < Duration::from_secs(12));
+        }
+    }
+
+    #[test]
+    fn park_after_max_attempts_until_reset() {
+        let epid = epid();
+        let mut states = ConnectionStates::new(Backoff::new(Duration::from_secs(1), Duration::from_secs(60), 3));
+
+        states.insert(epid);
+        assert_eq!(states.get(&epid), Some(ConnectionState::Disconnected));
+
+        states.connecting(&epid);
+        assert!(states.failed(&epid).is_some());
+        states.connecting(&epid);
+        assert!(states.failed(&epid).is_some());
+        assert!(matches!(
+            states.get(&epid),
+            Some(ConnectionState::Backoff { attempts: 2, .. })
+        ));
+
+        states.connecting(&epid);
+        assert!(states.failed(&epid).is_none());
+        assert_eq!(states.get(&epid), Some(ConnectionState::Parked));
+
+        states.reset(&epid);
+        assert_eq!(states.get(&epid), Some(ConnectionState::Disconnected));
+        assert!(matches!(
+            states.failed(&epid),
+            Some(delay) if delay < Duration::from_secs(2)
+        ));
+    }
+
+    #[test]
+    fn connecting_resets_attempts() {
+        let epid = epid();
+        let mut states = ConnectionStates::new(Backoff::new(Duration::from_secs(1), Duration::from_secs(60), 2));
+
+        states.insert(epid);
+        assert!(states.failed(&epid).is_some());
+
+        states.connected(&epid);
+        assert_eq!(states.get(&epid), Some(ConnectionState::Connected));
+
+        assert!(states.failed(&epid).is_some());
+    }
+
+    #[test]
+    fn refusals_never_park() {
+        let epid = epid();
+        let mut states = ConnectionStates::new(Backoff::new(Duration::from_secs(1), Duration::from_secs(60), 1));
+
+        states.insert(epid);
+
+        for _ in 0..10 {
+            states.connecting(&epid);
+            assert!(states.refused(&epid).is_some());
+        }
+        assert!(matches!(
+            states.get(&epid),
+            Some(ConnectionState::Backoff { attempts: 0, .. })
+        ));
+
+        assert!(states.failed(&epid).is_none());
+        assert_eq!(states.get(&epid), Some(ConnectionState::Parked));
+    }
+
+    #[test]
+    fn reset_makes_scheduled_attempts_obsolete() {
+        let (epid, other) = (
+            epid(),
+            EpId::from(Address::from("127.0.0.1:15601".parse::<SocketAddr>().unwrap())),
+        );
+        let mut states = ConnectionStates::new(Backoff::new(Duration::from_secs(1), Duration::from_secs(60), 0));
+
+        states.insert(epid);
+        states.insert(other);
+
+        let scheduled = states.generation(&epid);
+        assert_ne!(scheduled, states.generation(&other));
+
+        states.failed(&epid);
+        assert_eq!(states.generation(&epid), scheduled);
+
+        states.reset(&epid);
+        assert!(states.generation(&epid).is_some());
+        assert_ne!(states.generation(&epid), scheduled);
+
+        states.remove(&epid);
+        assert_eq!(states.generation(&epid), None);
+    }
+}
@@ -42,8 +42,6 @@ impl Endpoints {
         self.inner.remove(id).is_some()
     }
 
-    // TODO: see if we need this API in the future.
-    #[allow(dead_code)]
     pub fn get(&self, id: &EndpointId) -> Option<&Endpoint> {
         self.inner.get(id)
     }
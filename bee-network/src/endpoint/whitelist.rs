@@ -11,6 +11,8 @@
 
 use super::EndpointId as EpId;
 
+use crate::identity::PublicKey;
+
 use async_std::net::IpAddr;
 use dashmap::DashMap;
 
@@ -53,33 +55,59 @@ pub fn drop() {
     }
 }
 
+// NOTE: Pinned endpoints are keyed by their public key, their address is only used to locate them. Endpoints that
+// aren't pinned can only be identified by their address.
 pub struct WhiteList {
-    inner: DashMap<EpId, IpAddr>,
+    keys: DashMap<PublicKey, EpId>,
+    addresses: DashMap<EpId, IpAddr>,
 }
 
 impl WhiteList {
     pub fn new() -> Self {
         Self {
-            inner: DashMap::with_capacity(INITIAL_WHITELIST_CAPACITY),
+            keys: DashMap::with_capacity(INITIAL_WHITELIST_CAPACITY),
+            addresses: DashMap::with_capacity(INITIAL_WHITELIST_CAPACITY),
         }
     }
 
-    pub fn insert(&self, epid: EpId, addr: IpAddr) -> bool {
-        self.inner.insert(epid, addr).is_some()
+    pub fn insert(&self, epid: EpId, addr: IpAddr, public_key: Option<PublicKey>) -> bool {
+        let replaced = self.remove(&epid);
+
+        match public_key {
+            Some(public_key) => self.keys.insert(public_key, epid).is_some() || replaced,
+            None => self.addresses.insert(epid, addr).is_some() || replaced,
+        }
     }
 
     pub fn remove(&self, epid: &EpId) -> bool {
-        self.inner.remove(epid).is_some()
-    }
+        let len = self.keys.len();
+        self.keys.retain(|_, e| *e != *epid);
 
-    // TODO: re-resolve domain names
-    #[allow(dead_code)]
-    pub async fn refresh(&self) {
-        todo!("implement refresh")
+        self.addresses.remove(epid).is_some() || self.keys.len() != len
     }
 
     pub fn contains_address(&self, addr: &IpAddr) -> bool {
-        self.inner.iter().any(|r| r.value() == addr)
+        self.addresses.iter().any(|r| r.value() == addr)
+    }
+
+    /// Returns whether any endpoint is pinned to that public key.
+    pub fn contains_public_key(&self, public_key: &PublicKey) -> bool {
+        self.keys.contains_key(public_key)
+    }
+
+    /// Returns the public key an endpoint is pinned to, if any.
+    pub fn pinned_key(&self, epid: &EpId) -> Option<PublicKey> {
+        self.keys.iter().find(|r| r.value() == epid).map(|r| *r.key())
+    }
+
+    /// Returns whether a connection from that address, authenticated with that public key if any, is accepted.
+    ///
+    /// NOTE: A pinned public key identifies its endpoint whatever address it connects from, e.g. from behind a NAT.
+    pub fn accepts(&self, addr: &IpAddr, public_key: Option<&PublicKey>) -> bool {
+        match public_key {
+            Some(public_key) if self.contains_public_key(public_key) => true,
+            _ => self.contains_address(addr),
+        }
     }
 }
 
@@ -127,4 +155,52 @@ mod tests {
         let _ = get();
         drop();
     }
+
+    #[test]
+    fn accepts_pinned_key_only() {
+        let whitelist = WhiteList::new();
+        let addr: IpAddr = "127.0.0.1".parse().unwrap();
+        let pinned_key = *crate::identity::Identity::generate().public_key();
+        let other_key = *crate::identity::Identity::generate().public_key();
+        let epid = EpId::from(crate::address::Address::from(std::net::SocketAddr::new(addr, 15600)));
+
+        whitelist.insert(epid, addr, Some(pinned_key));
+
+        assert_eq!(whitelist.pinned_key(&epid), Some(pinned_key));
+        assert!(whitelist.accepts(&addr, Some(&pinned_key)));
+        assert!(!whitelist.accepts(&addr, Some(&other_key)));
+        assert!(!whitelist.accepts(&addr, None));
+        assert!(!whitelist.accepts(&"127.0.0.2".parse().unwrap(), None));
+    }
+
+    #[test]
+    fn accepts_pinned_key_from_any_address() {
+        let whitelist = WhiteList::new();
+        let addr: IpAddr = "127.0.0.1".parse().unwrap();
+        let pinned_key = *crate::identity::Identity::generate().public_key();
+        let epid = EpId::from(crate::address::Address::from(std::net::SocketAddr::new(addr, 15600)));
+
+        whitelist.insert(epid, addr, Some(pinned_key));
+
+        assert!(whitelist.contains_public_key(&pinned_key));
+        assert!(whitelist.accepts(&"127.0.0.2".parse().unwrap(), Some(&pinned_key)));
+    }
+
+    #[test]
+    fn pinned_keys_share_an_address() {
+        let whitelist = WhiteList::new();
+        let addr: IpAddr = "127.0.0.1".parse().unwrap();
+        let first_key = *crate::identity::Identity::generate().public_key();
+        let second_key = *crate::identity::Identity::generate().public_key();
+        let first_epid = EpId::from(crate::address::Address::from(std::net::SocketAddr::new(addr, 15600)));
+        let second_epid = EpId::from(crate::address::Address::from(std::net::SocketAddr::new(addr, 15601)));
+
+        whitelist.insert(first_epid, addr, Some(first_key));
+        whitelist.insert(second_epid, addr, Some(second_key));
+
+        assert!(whitelist.remove(&first_epid));
+        assert!(!whitelist.accepts(&addr, Some(&first_key)));
+        assert!(whitelist.accepts(&addr, Some(&second_key)));
+        assert_eq!(whitelist.pinned_key(&second_epid), Some(second_key));
+    }
 }
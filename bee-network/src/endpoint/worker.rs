@@ -12,18 +12,35 @@
 use super::whitelist;
 
 use crate::{
-    address::url::{Protocol, Url},
+    address::{
+        url::{Protocol, Url},
+        Address,
+    },
     commands::{Command, CommandReceiver as Commands, Responder},
-    endpoint::{outbox::Outbox, store::Endpoints, Endpoint as Ep, EndpointId as EpId},
-    events::{Event, EventPublisher as Notifier, EventPublisher as Publisher, EventSubscriber as Events},
-    tcp,
-    utils::time,
+    config::NetworkConfig,
+    endpoint::{
+        banlist::{BanList, Banned},
+        limits::ConnectionLimits,
+        origin::Origin,
+        outbox::{Outbox, OutboxPolicy},
+        state::{Backoff, ConnectionState, ConnectionStates},
+        store::Endpoints,
+        Endpoint as Ep, EndpointId as EpId,
+    },
+    events::{
+        Event, EventPublisher as Notifier, EventPublisher as Publisher, EventSubscriber as Events, RefusalReason,
+    },
+    identity::{Identity, PublicKey},
+    memory, tcp,
+    udp::{self, InstructionSender as Udp},
+    utils::{shaping::Shaping, time},
 };
 
 use bee_common::{shutdown::ShutdownListener as Shutdown, worker::Error as WorkerError};
 
 use async_std::{
     prelude::*,
+    sync::Arc,
     task::{self, spawn},
 };
 use futures::{select, sink::SinkExt, FutureExt};
@@ -31,13 +48,26 @@ use log::*;
 
 use std::time::Duration;
 
-pub struct EndpointWorker {
+/// Everything needed to connect to endpoints.
+pub(crate) struct Dialer {
+    pub udp: Udp,
+    pub identity: Option<Arc<Identity>>,
+    pub banlist: Arc<BanList>,
+    pub shaping: Shaping,
+    pub memory: Option<memory::Node>,
+}
+
+pub(crate) struct EndpointWorker {
     commands: Commands,
     events: Events,
     shutdown: Shutdown,
     notifier: Notifier,
     publisher: Publisher,
-    reconnect_interval: Duration,
+    dialer: Dialer,
+    limits: Arc<ConnectionLimits>,
+    states: ConnectionStates,
+    outbox_policy: OutboxPolicy,
+    resolve_interval: Duration,
 }
 
 impl EndpointWorker {
@@ -47,7 +77,9 @@ impl EndpointWorker {
         shutdown: Shutdown,
         notifier: Notifier,
         publisher: Publisher,
-        reconnect_interval: Duration,
+        dialer: Dialer,
+        limits: Arc<ConnectionLimits>,
+        config: &NetworkConfig,
     ) -> Self {
         Self {
             commands,
@@ -55,7 +87,15 @@ impl EndpointWorker {
             shutdown,
             notifier,
             publisher,
-            reconnect_interval,
+            dialer,
+            limits,
+            states: ConnectionStates::new(Backoff::new(
+                config.reconnect_interval,
+                config.max_reconnect_interval,
+                config.max_reconnect_attempts,
+            )),
+            outbox_policy: config.outbox_policy,
+            resolve_interval: config.resolve_interval,
         }
     }
 
@@ -66,13 +106,19 @@ impl EndpointWorker {
 
         // TODO: those two probably need to be merged as each connected endpoint is also part of the outbox
         let mut connected = Endpoints::new();
-        let mut outbox = Outbox::new();
+        let mut outbox = Outbox::new(self.outbox_policy);
 
         let commands = &mut self.commands;
         let events = &mut self.events;
         let shutdown = &mut self.shutdown;
         let publisher = &mut self.publisher;
 
+        spawn(raise_event_after_delay(
+            Event::ResolveAddresses,
+            self.resolve_interval,
+            self.notifier.clone(),
+        ));
+
         loop {
             select! {
                 command = commands.next().fuse() => {
@@ -87,8 +133,9 @@ impl EndpointWorker {
                     debug!("Received {}.", command);
 
                     match command {
-                        Command::AddEndpoint { url, responder } => {
-                            let res = add_endpoint(&mut contacts, url, &mut self.notifier).await?;
+                        Command::AddEndpoint { url, public_key, responder } => {
+                            let res = add_endpoint(&mut contacts, &mut self.states, &self.dialer, url, public_key,
+                                &mut self.notifier).await?;
 
                             if let Some(responder) = responder {
                                 if responder.send(res).is_err() {
@@ -98,7 +145,7 @@ impl EndpointWorker {
                         },
                         Command::RemoveEndpoint { epid, responder } => {
                             let res = rmv_endpoint(epid, &mut contacts, &mut connected, &mut outbox,
-                                &mut self.notifier).await?;
+                                &self.limits, &mut self.states, &mut self.notifier).await?;
 
                             if let Some(responder) = responder {
                                 if responder.send(res).is_err() {
@@ -107,10 +154,14 @@ impl EndpointWorker {
                             }
                         },
                         Command::Connect { epid, responder } => {
-                            try_connect(epid, self.reconnect_interval, &mut contacts, &mut connected, responder, &mut self.notifier).await?;
+                            // NOTE: Connecting manually resets the backoff, and unparks the endpoint.
+                            self.states.reset(&epid);
+
+                            try_connect(epid, &self.dialer, &contacts, &mut self.states, &self.limits, responder, &mut self.notifier).await?;
                         },
                         Command::Disconnect { epid, responder } => {
-                            let is_disconnected = disconnect(epid, &mut connected, &mut outbox).await;
+                            let is_disconnected = disconnect(epid, &mut connected, &mut outbox, &self.limits,
+                                &mut self.states).await;
 
                             if let Some(responder) = responder {
                                 if responder.send(is_disconnected).is_err() {
@@ -128,6 +179,47 @@ impl EndpointWorker {
                             }
 
                         },
+                        Command::BanEndpoint { epid, duration, responder } => {
+                            // NOTE: Endpoints that aren't connected are banned by the key they are pinned to, if any.
+                            let banned = connected
+                                .get(&epid)
+                                .or_else(|| contacts.get(&epid))
+                                .map(|ep| (ep.address, ep.public_key.or_else(|| whitelist::get().pinned_key(&epid))));
+
+                            let res = if let Some((address, public_key)) = banned {
+                                self.dialer.banlist.insert(Banned::endpoint(&address, public_key), duration);
+
+                                info!("Banned {} for {}s.", epid, duration.as_secs());
+
+                                publisher.send(Event::EndpointBanned { epid, address, public_key, duration }).await?;
+
+                                if disconnect(epid, &mut connected, &mut outbox, &self.limits,
+                                    &mut self.states).await {
+                                    publisher
+                                        .send(Event::EndpointDisconnected {
+                                            epid,
+                                            total: connected.num(),
+                                        })
+                                        .await?;
+                                }
+
+                                spawn(raise_event_after_delay(
+                                    Event::EndpointUnbanned { epid, address, public_key },
+                                    duration,
+                                    self.notifier.clone(),
+                                ));
+
+                                true
+                            } else {
+                                false
+                            };
+
+                            if let Some(responder) = responder {
+                                if responder.send(res).is_err() {
+                                    warn!("Error sending command response.");
+                                };
+                            }
+                        },
                         Command::SendMessage { epid, bytes, responder } => {
                             let res = send_bytes(&epid, bytes, &mut outbox).await?;
 
@@ -155,6 +247,21 @@ impl EndpointWorker {
                                 };
                             }
                         },
+                        Command::QueryEndpointState { epid, responder } => {
+                            if responder.send(self.states.get(&epid)).is_err() {
+                                warn!("Error sending command response.");
+                            };
+                        },
+                        Command::ListEndpoints { responder } => {
+                            let endpoints = contacts
+                                .iter()
+                                .filter_map(|(epid, ep)| self.states.get(epid).map(|state| (ep.clone(), state)))
+                                .collect();
+
+                            if responder.send(endpoints).is_err() {
+                                warn!("Error sending command response.");
+                            };
+                        },
                     }
 
                 },
@@ -175,23 +282,46 @@ impl EndpointWorker {
                         Event::EndpointRemoved { epid, total } => {
                             publisher.send(Event::EndpointRemoved { epid, total }).await?;
                         },
-                        Event::NewConnection { ep, origin, sender } => {
+                        Event::NewConnection { ep, origin, public_key, sender } => {
                             let epid = ep.id;
                             let addr = ep.address;
 
-                            outbox.insert(epid, sender);
-                            connected.insert(ep);
+                            if self.limits.admits(&origin) {
+                                outbox.insert(epid, sender);
+                                connected.insert(Ep { public_key, ..ep });
+                                self.limits.insert(epid, &origin);
+                                self.states.connected(&epid);
+
+                                publisher.send(Event::EndpointConnected {
+                                    epid,
+                                    address: addr,
+                                    origin,
+                                    public_key,
+                                    timestamp: time::timestamp_millis(),
+                                    total: connected.num(),
+                                }).await?
+                            } else {
+                                // NOTE: Dropping the sender closes the connection.
+                                drop(sender);
+
+                                // NOTE: A live connection with the same endpoint keeps its state.
+                                if !connected.contains(&epid) {
+                                    self.states.disconnected(&epid);
+                                }
 
-                            publisher.send(Event::EndpointConnected {
-                                epid,
-                                address: addr,
-                                origin,
-                                timestamp: time::timestamp_millis(),
-                                total: connected.num(),
-                            }).await?
+                                let reason = match origin {
+                                    Origin::Inbound => RefusalReason::InboundLimit,
+                                    _ => RefusalReason::OutboundLimit,
+                                };
+
+                                warn!("Refused connection with {}: {}.", addr, reason);
+
+                                publisher.send(Event::ConnectionRefused { address: addr, reason }).await?
+                            }
                         },
                         Event::LostConnection { epid } => {
-                            let is_disconnected = disconnect(epid, &mut connected, &mut outbox).await;
+                            let is_disconnected = disconnect(epid, &mut connected, &mut outbox, &self.limits,
+                                &mut self.states).await;
 
                             if is_disconnected {
                                 publisher
@@ -204,22 +334,90 @@ impl EndpointWorker {
 
                             // TODO: do not try to reconnect to duplicate endpoints
                             // NOTE: 'try_connect' will check if 'epid' is part of the contact list
-                            try_connect(epid, self.reconnect_interval, &mut contacts, &mut connected, None, &mut self.notifier).await?;
+                            try_connect(epid, &self.dialer, &contacts, &mut self.states, &self.limits, None, &mut self.notifier).await?;
                         }
-                        Event::MessageSent { epid, num_bytes } => {
+                        Event::MessageSent { epid, num_bytes, total_bytes } => {
                             publisher.send(Event::MessageSent {
                                 epid,
                                 num_bytes,
+                                total_bytes,
                             }).await?
                         },
-                        Event::MessageReceived { epid, bytes } => {
+                        Event::MessageReceived { epid, bytes, total_bytes } => {
                             publisher.send(Event::MessageReceived {
                                 epid,
                                 bytes,
+                                total_bytes,
                             }).await?
                         },
-                        Event::TryConnect { epid, responder } => {
-                            try_connect(epid, self.reconnect_interval, &mut contacts, &mut connected, responder, &mut self.notifier).await?;
+                        Event::TryConnect { epid, generation, responder } => {
+                            // NOTE: The endpoint might have been connected manually, or removed, in the meantime.
+                            if self.states.generation(&epid) == Some(generation) {
+                                try_connect(epid, &self.dialer, &contacts, &mut self.states, &self.limits, responder, &mut self.notifier).await?;
+                            } else if let Some(responder) = responder {
+                                if responder.send(false).is_err() {
+                                    error!("Failed to send command response.");
+                                }
+                            }
+                        }
+                        Event::ConnectionFailed { epid, responder } => {
+                            // NOTE: The endpoint might have been removed in the meantime.
+                            if self.states.get(&epid) == Some(ConnectionState::Connecting) {
+                                retry_later(epid, &mut self.states, responder, &self.notifier);
+                            } else if let Some(responder) = responder {
+                                if responder.send(false).is_err() {
+                                    error!("Failed to send command response.");
+                                }
+                            }
+                        }
+                        Event::EndpointUnbanned { epid, address, public_key } => {
+                            // NOTE: The ban might have been renewed in the meantime.
+                            if self.dialer.banlist.remove_expired(&Banned::endpoint(&address, public_key)) {
+                                info!("Ban of {} expired.", epid);
+
+                                publisher.send(Event::EndpointUnbanned { epid, address, public_key }).await?;
+                            }
+                        },
+                        Event::ConnectionRefused { address, reason } => {
+                            publisher.send(Event::ConnectionRefused { address, reason }).await?
+                        },
+                        Event::ResolveAddresses => {
+                            // NOTE: Host names are resolved in their own tasks, so that slow lookups don't hold up the
+                            // worker; changed addresses are reported with `AddressResolved` events.
+                            for (_, ep) in contacts.iter().filter(|(_, ep)| ep.host.is_some()) {
+                                spawn(resolve(ep.clone(), self.notifier.clone()));
+                            }
+
+                            spawn(raise_event_after_delay(
+                                Event::ResolveAddresses,
+                                self.resolve_interval,
+                                self.notifier.clone(),
+                            ));
+                        }
+                        Event::AddressResolved { epid, address } => {
+                            if let Some(old_address) = update_address(epid, address, &mut contacts) {
+                                publisher.send(Event::EndpointAddressChanged {
+                                    epid,
+                                    old_address,
+                                    new_address: address,
+                                }).await?;
+
+                                // NOTE: Datagrams are sent to the address the endpoint had when it got connected, so
+                                // reconnect it. TCP connections are kept until they get lost.
+                                let is_udp = connected.get(&epid).map_or(false, |ep| ep.protocol.is_udp());
+
+                                if is_udp && disconnect(epid, &mut connected, &mut outbox, &self.limits,
+                                    &mut self.states).await {
+                                    publisher
+                                        .send(Event::EndpointDisconnected {
+                                            epid,
+                                            total: connected.num(),
+                                        })
+                                        .await?;
+
+                                    try_connect(epid, &self.dialer, &contacts, &mut self.states, &self.limits, None, &mut self.notifier).await?;
+                                }
+                            }
                         }
                         _ => (),
                     }
@@ -237,15 +435,37 @@ impl EndpointWorker {
 }
 
 #[inline(always)]
-async fn add_endpoint(contacts: &mut Endpoints, url: Url, notifier: &mut Notifier) -> Result<bool, WorkerError> {
+async fn add_endpoint(
+    contacts: &mut Endpoints,
+    states: &mut ConnectionStates,
+    dialer: &Dialer,
+    url: Url,
+    public_key: Option<PublicKey>,
+    notifier: &mut Notifier,
+) -> Result<bool, WorkerError> {
     let ep = Ep::from_url(url);
     let epid = ep.id;
+    let ip = ep.address.ip();
+
+    // NOTE: Datagrams are neither encrypted nor authenticated, and their source address can be spoofed.
+    if ep.protocol.is_udp() && dialer.memory.is_none() && (public_key.is_some() || dialer.identity.is_some()) {
+        warn!("Not adding {}: UDP endpoints can neither be pinned nor secured.", epid);
+        return Ok(false);
+    }
+
+    // NOTE: Public keys are only authenticated by secure connections, so a pinned endpoint could never be connected.
+    if public_key.is_some() && dialer.identity.is_none() {
+        warn!("Not adding {}: pinning a public key requires secure connections.", epid);
+        return Ok(false);
+    }
 
     if contacts.insert(ep) {
+        states.insert(epid);
+
         // add its ip to the whitelist, so that we can make sure that we accept only connections
         // from known peers
         let whitelist = whitelist::get();
-        whitelist.insert(epid, url.address().ip());
+        whitelist.insert(epid, ip, public_key);
 
         notifier
             .send(Event::EndpointAdded {
@@ -265,9 +485,13 @@ async fn rmv_endpoint(
     contacts: &mut Endpoints,
     connected: &mut Endpoints,
     outbox: &mut Outbox,
+    limits: &ConnectionLimits,
+    states: &mut ConnectionStates,
     notifier: &mut Notifier,
 ) -> Result<bool, WorkerError> {
     // NOTE: current default behavior is to drop connections once the contact is removed
+    limits.remove(&epid);
+    states.remove(&epid);
     let removed_recipient = outbox.remove(&epid);
     let removed_contact = contacts.remove(&epid);
     let removed_connected = connected.remove(&epid);
@@ -297,16 +521,19 @@ async fn rmv_endpoint(
 #[inline(always)]
 async fn try_connect(
     epid: EpId,
-    reconnect_interval: Duration,
-    contacts: &mut Endpoints,
-    connected: &mut Endpoints,
+    dialer: &Dialer,
+    contacts: &Endpoints,
+    states: &mut ConnectionStates,
+    limits: &ConnectionLimits,
     responder: Option<Responder<bool>>,
     notifier: &mut Notifier,
 ) -> Result<bool, WorkerError> {
     // Try to find the endpoint in our servers list.
-    if let Some(ep) = contacts.get_mut(&epid) {
-        // if ep.is_connected() {
-        if connected.contains(&ep.id) {
+    if let Some(ep) = contacts.get(&epid) {
+        if matches!(
+            states.get(&epid),
+            Some(ConnectionState::Connecting) | Some(ConnectionState::Connected) | Some(ConnectionState::Parked)
+        ) {
             if let Some(responder) = responder {
                 match responder.send(false) {
                     Ok(_) => (),
@@ -317,41 +544,103 @@ async fn try_connect(
             }
             Ok(false)
         } else {
-            match ep.protocol {
-                Protocol::Tcp => {
-                    if tcp::try_connect(&ep.id, &ep.address, notifier.clone()).await.is_ok() {
-                        connected.insert(ep.clone());
+            let is_banned = dialer.banlist.contains(&Banned::Address(ep.address.ip()))
+                || whitelist::get().pinned_key(&epid).map_or(false, |public_key| {
+                    dialer.banlist.contains(&Banned::PublicKey(public_key))
+                });
+
+            let refusal = if is_banned {
+                Some(RefusalReason::Banned)
+            } else if !limits.admits(&Origin::Outbound) {
+                Some(RefusalReason::OutboundLimit)
+            } else {
+                None
+            };
+
+            if let Some(reason) = refusal {
+                debug!("Not connecting to {}: {}.", epid, reason);
+
+                notifier
+                    .send(Event::ConnectionRefused {
+                        address: ep.address,
+                        reason,
+                    })
+                    .await?;
+
+                // NOTE: Try again later, the ban might have expired or a connection been released by then. The
+                // refusal doesn't count as a failed attempt, so it doesn't park the endpoint.
+                match states.refused(&epid) {
+                    Some(delay) => schedule_retry(epid, delay, states, responder, notifier),
+                    None => {
                         if let Some(responder) = responder {
-                            match responder.send(true) {
-                                Ok(_) => (),
-                                Err(_) => {
-                                    error!("Failed to send command response.");
-                                }
+                            if responder.send(false).is_err() {
+                                error!("Failed to send command response.");
                             }
                         }
-                        Ok(true)
-                    } else {
-                        // If connection attempt fails, issue a `TryConnect` event after a certain delay.
-                        // NOTE: It won't be raised, if the endpoint has been removed in the mean time.
-                        spawn(raise_event_after_delay(
-                            Event::TryConnect { epid, responder },
-                            reconnect_interval,
-                            notifier.clone(),
-                        ));
-                        Ok(false)
                     }
                 }
-                Protocol::Udp => {
+
+                return Ok(false);
+            }
+
+            states.connecting(&epid);
+
+            match ep.protocol {
+                Protocol::Udp if dialer.memory.is_none() => {
+                    // NOTE: The host name of the endpoint might point to another address by now, in which case the
+                    // endpoint gets reconnected once it has been resolved.
+                    spawn(resolve(ep.clone(), notifier.clone()));
+
+                    let is_connected = udp::connect(ep, dialer.udp.clone(), &dialer.shaping, notifier.clone())
+                        .await
+                        .is_ok();
+
+                    if !is_connected {
+                        states.disconnected(&epid);
+                    }
                     if let Some(responder) = responder {
-                        match responder.send(true) {
+                        match responder.send(is_connected) {
                             Ok(_) => (),
                             Err(_) => {
                                 error!("Failed to send response.");
                             }
                         }
                     }
-                    Ok(true)
+                    Ok(is_connected)
                 }
+                _ => match &dialer.memory {
+                    // NOTE: Simulated nodes are connected in memory, whatever the protocol of the endpoint.
+                    Some(memory) => {
+                        if memory.connect(ep, &dialer.shaping, notifier.clone()).await.is_ok() {
+                            if let Some(responder) = responder {
+                                match responder.send(true) {
+                                    Ok(_) => (),
+                                    Err(_) => {
+                                        error!("Failed to send command response.");
+                                    }
+                                }
+                            }
+                            Ok(true)
+                        } else {
+                            retry_later(epid, states, responder, notifier);
+                            Ok(false)
+                        }
+                    }
+                    // NOTE: Resolving, dialing and the secure handshake are done in their own task, so that an
+                    // unresponsive endpoint doesn't hold up the worker; a failure is reported with a
+                    // `ConnectionFailed` event.
+                    None => {
+                        spawn(dial(
+                            ep.clone(),
+                            dialer.identity.clone(),
+                            dialer.banlist.clone(),
+                            dialer.shaping.clone(),
+                            responder,
+                            notifier.clone(),
+                        ));
+                        Ok(true)
+                    }
+                },
             }
         }
     } else {
@@ -367,6 +656,120 @@ async fn try_connect(
     }
 }
 
+async fn dial(
+    ep: Ep,
+    identity: Option<Arc<Identity>>,
+    banlist: Arc<BanList>,
+    shaping: Shaping,
+    responder: Option<Responder<bool>>,
+    mut notifier: Notifier,
+) {
+    let epid = ep.id;
+    // NOTE: The host name of the endpoint might point to another address by now.
+    let address = resolve(ep, notifier.clone()).await;
+
+    match tcp::try_connect(&epid, &address, identity, &banlist, &shaping, notifier.clone()).await {
+        Ok(()) => {
+            if let Some(responder) = responder {
+                if responder.send(true).is_err() {
+                    error!("Failed to send command response.");
+                }
+            }
+        }
+        Err(_) => {
+            if notifier
+                .send(Event::ConnectionFailed { epid, responder })
+                .await
+                .is_err()
+            {
+                warn!("Failed to send 'ConnectionFailed' notification.");
+            }
+        }
+    }
+}
+
+/// Records a failed connection attempt, and issues a `TryConnect` event after the backoff delay of the endpoint, unless
+/// it got parked.
+#[inline(always)]
+fn retry_later(epid: EpId, states: &mut ConnectionStates, responder: Option<Responder<bool>>, notifier: &Notifier) {
+    match states.failed(&epid) {
+        Some(delay) => schedule_retry(epid, delay, states, responder, notifier),
+        None => {
+            warn!("Giving up on connecting to {} after too many failed attempts.", epid);
+
+            if let Some(responder) = responder {
+                if responder.send(false).is_err() {
+                    error!("Failed to send command response.");
+                }
+            }
+        }
+    }
+}
+
+/// Issues a `TryConnect` event after a delay, which is dropped if the endpoint got connected manually or removed by
+/// then.
+#[inline(always)]
+fn schedule_retry(
+    epid: EpId,
+    delay: Duration,
+    states: &ConnectionStates,
+    responder: Option<Responder<bool>>,
+    notifier: &Notifier,
+) {
+    if let Some(generation) = states.generation(&epid) {
+        debug!("Retrying to connect to {} in {}s.", epid, delay.as_secs());
+
+        spawn(raise_event_after_delay(
+            Event::TryConnect {
+                epid,
+                generation,
+                responder,
+            },
+            delay,
+            notifier.clone(),
+        ));
+    }
+}
+
+/// Resolves the host name of an endpoint, if any, and returns its current address. A changed address is reported
+/// with an `AddressResolved` event.
+async fn resolve(ep: Ep, mut notifier: Notifier) -> Address {
+    match ep.resolve().await {
+        Some(address) => {
+            if notifier
+                .send(Event::AddressResolved { epid: ep.id, address })
+                .await
+                .is_err()
+            {
+                warn!("Failed to send 'AddressResolved' notification.");
+            }
+            address
+        }
+        None => ep.address,
+    }
+}
+
+/// Updates the address of an endpoint, and returns the previous one if it changed.
+///
+/// NOTE: The id of the endpoint, and so its whitelist entry, stays the same. Connections to the endpoint keep that id,
+/// whatever address they are established with.
+#[inline(always)]
+fn update_address(epid: EpId, new_address: Address, contacts: &mut Endpoints) -> Option<Address> {
+    // NOTE: The endpoint might have been removed, or already been updated by another lookup, in the meantime.
+    let ep = contacts.get_mut(&epid).filter(|ep| ep.address != new_address)?;
+    let old_address = ep.address;
+
+    info!("Address of {} changed from {} to {}.", epid, old_address, new_address);
+
+    ep.address = new_address;
+
+    // Keep accepting connections from the endpoint at its new address.
+    let whitelist = whitelist::get();
+    whitelist.insert(epid, new_address.ip(), whitelist.pinned_key(&epid));
+
+    Some(old_address)
+}
+
 #[inline(always)]
 async fn raise_event_after_delay(event: Event, delay: Duration, mut notifier: Notifier) -> Result<(), WorkerError> {
     task::sleep(delay).await;
@@ -375,7 +778,15 @@ async fn raise_event_after_delay(event: Event, delay: Duration, mut notifier: No
 }
 
 #[inline(always)]
-async fn disconnect(epid: EpId, connected: &mut Endpoints, outbox: &mut Outbox) -> bool {
+async fn disconnect(
+    epid: EpId,
+    connected: &mut Endpoints,
+    outbox: &mut Outbox,
+    limits: &ConnectionLimits,
+    states: &mut ConnectionStates,
+) -> bool {
+    limits.remove(&epid);
+    states.disconnected(&epid);
     let removed_recipient = outbox.remove(&epid);
     let removed_connected = connected.remove(&epid);
 
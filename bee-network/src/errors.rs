@@ -27,6 +27,18 @@ pub enum ConnectionError {
     #[error(display = "Connection attempt failed")]
     ConnectionAttemptFailed,
 
+    #[error(display = "Secure handshake failed")]
+    SecureHandshakeFailed,
+
+    #[error(display = "Secure handshake timed out")]
+    SecureHandshakeTimedOut,
+
+    #[error(display = "Secure channel failure")]
+    SecureChannelFailure,
+
+    #[error(display = "Unexpected public key")]
+    UnexpectedPublicKey,
+
     #[error(display = "Sending event failed")]
     SendingEventFailed(#[source] futures::channel::mpsc::SendError),
 }
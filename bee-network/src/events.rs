@@ -13,10 +13,38 @@ use crate::{
     address::Address,
     commands::Responder,
     endpoint::{origin::Origin, outbox::BytesSender, Endpoint, EndpointId},
+    identity::PublicKey,
 };
 
 use futures::channel::mpsc;
-use std::fmt;
+use std::{fmt, time::Duration};
+
+/// Reasons for a connection to be refused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RefusalReason {
+    /// The IP address or the public key of the endpoint is banned.
+    Banned,
+
+    /// The maximum number of inbound connections is reached.
+    InboundLimit,
+
+    /// The maximum number of outbound connections is reached.
+    OutboundLimit,
+
+    /// The IP address of the endpoint attempted to connect too often.
+    TooManyAttempts,
+}
+
+impl fmt::Display for RefusalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RefusalReason::Banned => write!(f, "banned"),
+            RefusalReason::InboundLimit => write!(f, "inbound limit reached"),
+            RefusalReason::OutboundLimit => write!(f, "outbound limit reached"),
+            RefusalReason::TooManyAttempts => write!(f, "too many attempts"),
+        }
+    }
+}
 
 /// Network events.
 #[derive(Debug)]
@@ -47,6 +75,9 @@ pub enum Event {
         /// Information about which endpoint initiated the connection.
         origin: Origin,
 
+        /// The authenticated public key of the endpoint, if the connection is secure.
+        public_key: Option<PublicKey>,
+
         /// The channel half to send messages over this connection.
         sender: BytesSender,
     },
@@ -68,6 +99,9 @@ pub enum Event {
         /// Information about which endpoint initiated the connection.
         origin: Origin,
 
+        /// The authenticated public key of the endpoint, if the connection is secure.
+        public_key: Option<PublicKey>,
+
         /// The timestamp when the connection was established.
         timestamp: u64,
 
@@ -91,6 +125,9 @@ pub enum Event {
 
         /// The number of bytes sent.
         num_bytes: usize,
+
+        /// The total number of bytes sent to that `Endpoint` over its current connection.
+        total_bytes: u64,
     },
 
     /// Signals that a message has been received.
@@ -100,6 +137,69 @@ pub enum Event {
 
         /// The raw bytes of the message.
         bytes: Vec<u8>,
+
+        /// The total number of bytes received from that `Endpoint` over its current connection.
+        total_bytes: u64,
+    },
+
+    /// Signals that the host name of an `Endpoint` resolved to a new address.
+    EndpointAddressChanged {
+        /// The id of the `Endpoint`, which stays the same.
+        epid: EndpointId,
+
+        /// The previous address of the endpoint.
+        old_address: Address,
+
+        /// The new address of the endpoint.
+        new_address: Address,
+    },
+
+    /// Signals that an `Endpoint` has been banned.
+    EndpointBanned {
+        /// The id of the banned `Endpoint`.
+        epid: EndpointId,
+
+        /// The address of the banned endpoint, whose IP address is refused unless it is authenticated.
+        address: Address,
+
+        /// The authenticated public key of the banned endpoint, which is refused instead of its IP address, if any.
+        public_key: Option<PublicKey>,
+
+        /// How long the ban lasts.
+        duration: Duration,
+    },
+
+    /// Signals that the ban of an `Endpoint` expired.
+    EndpointUnbanned {
+        /// The id of the previously banned `Endpoint`.
+        epid: EndpointId,
+
+        /// The address of the previously banned endpoint.
+        address: Address,
+
+        /// The authenticated public key of the previously banned endpoint, if any.
+        public_key: Option<PublicKey>,
+    },
+
+    /// Signals that a connection has been refused.
+    ConnectionRefused {
+        /// The address of the refused endpoint.
+        address: Address,
+
+        /// The reason the connection has been refused.
+        reason: RefusalReason,
+    },
+
+    /// Signals that the host names of all `Endpoint`s should be resolved again.
+    ResolveAddresses,
+
+    /// Signals that the host name of an `Endpoint` has been resolved to another address.
+    AddressResolved {
+        /// The id of the `Endpoint`.
+        epid: EndpointId,
+
+        /// The address the host name resolved to.
+        address: Address,
     },
 
     /// Signals the next connection attempt to an `Endpoint`.
@@ -107,6 +207,19 @@ pub enum Event {
         /// The id of the `Endpoint`.
         epid: EndpointId,
 
+        /// The generation of the connection state of the `Endpoint` the attempt has been scheduled in. The attempt is
+        /// dropped if it is obsolete by now.
+        generation: u64,
+
+        /// The success responder.
+        responder: Option<Responder<bool>>,
+    },
+
+    /// Signals that a connection attempt to an `Endpoint` failed.
+    ConnectionFailed {
+        /// The id of the `Endpoint`.
+        epid: EndpointId,
+
         /// The success responder.
         responder: Option<Responder<bool>>,
     },
@@ -132,6 +245,7 @@ impl fmt::Display for Event {
                 origin,
                 timestamp,
                 total,
+                ..
             } => write!(
                 f,
                 "Event::EndpointConnected {{ {}, address: {}, origin: {}, ts: {}, num_connected: {} }}",
@@ -144,15 +258,68 @@ impl fmt::Display for Event {
                 epid, total
             ),
 
-            Event::MessageSent { epid, num_bytes } => {
-                write!(f, "Event::MessageSent {{ {}, num_bytes: {} }}", epid, num_bytes)
+            Event::MessageSent {
+                epid,
+                num_bytes,
+                total_bytes,
+            } => write!(
+                f,
+                "Event::MessageSent {{ {}, num_bytes: {}, total_bytes: {} }}",
+                epid, num_bytes, total_bytes
+            ),
+
+            Event::MessageReceived {
+                epid,
+                bytes,
+                total_bytes,
+            } => write!(
+                f,
+                "Event::MessageReceived {{ {}, num_bytes: {}, total_bytes: {} }}",
+                epid,
+                bytes.len(),
+                total_bytes
+            ),
+
+            Event::EndpointAddressChanged {
+                epid,
+                old_address,
+                new_address,
+            } => write!(
+                f,
+                "Event::EndpointAddressChanged {{ {}, old_address: {}, new_address: {} }}",
+                epid, old_address, new_address
+            ),
+
+            Event::EndpointBanned {
+                epid,
+                address,
+                duration,
+                ..
+            } => write!(
+                f,
+                "Event::EndpointBanned {{ {}, address: {}, duration: {}s }}",
+                epid,
+                address,
+                duration.as_secs()
+            ),
+
+            Event::EndpointUnbanned { epid, address, .. } => {
+                write!(f, "Event::EndpointUnbanned {{ {}, address: {} }}", epid, address)
             }
 
-            Event::MessageReceived { epid, bytes } => {
-                write!(f, "Event::MessageReceived {{ {}, num_bytes: {} }}", epid, bytes.len())
+            Event::ConnectionRefused { address, reason } => {
+                write!(f, "Event::ConnectionRefused {{ {}, reason: {} }}", address, reason)
+            }
+
+            Event::ResolveAddresses => write!(f, "Event::ResolveAddresses"),
+
+            Event::AddressResolved { epid, address } => {
+                write!(f, "Event::AddressResolved {{ {}, address: {} }}", epid, address)
             }
 
             Event::TryConnect { epid, .. } => write!(f, "Event::TryConnect {{ {} }}", epid),
+
+            Event::ConnectionFailed { epid, .. } => write!(f, "Event::ConnectionFailed {{ {} }}", epid),
         }
     }
 }
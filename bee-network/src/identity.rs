@@ -0,0 +1,178 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use err_derive::Error;
+use rand::rngs::OsRng;
+use x25519_dalek::{PublicKey as DalekPublicKey, StaticSecret};
+
+use std::{fmt, str::FromStr};
+
+/// Length of public and private keys, in bytes.
+pub const KEY_LENGTH: usize = 32;
+
+/// Errors related to node identities.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum IdentityError {
+    /// The key is not given as the hex encoding of `KEY_LENGTH` bytes.
+    #[error(display = "Invalid key")]
+    InvalidKey,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<[u8; KEY_LENGTH], IdentityError> {
+    if hex.len() != 2 * KEY_LENGTH || !hex.is_ascii() {
+        return Err(IdentityError::InvalidKey);
+    }
+
+    let mut bytes = [0u8; KEY_LENGTH];
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| IdentityError::InvalidKey)?;
+    }
+
+    Ok(bytes)
+}
+
+/// The static public key identifying a node on secure connections.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PublicKey([u8; KEY_LENGTH]);
+
+impl PublicKey {
+    /// Creates a public key from its raw bytes.
+    pub fn from_bytes(bytes: [u8; KEY_LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the raw bytes of the public key.
+    pub fn as_bytes(&self) -> &[u8; KEY_LENGTH] {
+        &self.0
+    }
+
+    /// Extracts the public key an endpoint is pinned to from the user part of its URL, if any.
+    ///
+    /// NOTE: This function expects an input of the format: tcp://<hex public key>@example.com:15600.
+    pub fn from_url_str(url: &str) -> Result<Option<Self>, IdentityError> {
+        let authority = url.split("://").nth(1).unwrap_or(url);
+
+        match authority.find('@') {
+            Some(i) => Ok(Some(authority[..i].parse()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = IdentityError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Ok(Self(from_hex(hex)?))
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+/// The static keypair of a node, used to authenticate and encrypt its connections.
+#[derive(Clone)]
+pub struct Identity {
+    private_key: [u8; KEY_LENGTH],
+    public_key: PublicKey,
+}
+
+impl Identity {
+    /// Generates a new random identity.
+    pub fn generate() -> Self {
+        Self::from_private_key(StaticSecret::new(&mut OsRng).to_bytes())
+    }
+
+    /// Restores an identity from its private key, the public key being derived from it.
+    pub fn from_private_key(private_key: [u8; KEY_LENGTH]) -> Self {
+        let public_key = DalekPublicKey::from(&StaticSecret::from(private_key));
+
+        Self {
+            private_key,
+            public_key: PublicKey(*public_key.as_bytes()),
+        }
+    }
+
+    /// Restores an identity from the hex encoding of its private key.
+    pub fn from_private_key_hex(hex: &str) -> Result<Self, IdentityError> {
+        Ok(Self::from_private_key(from_hex(hex)?))
+    }
+
+    /// Returns the private key.
+    pub fn private_key(&self) -> &[u8; KEY_LENGTH] {
+        &self.private_key
+    }
+
+    /// Returns the hex encoding of the private key.
+    pub fn private_key_hex(&self) -> String {
+        to_hex(&self.private_key)
+    }
+
+    /// Returns the public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // NOTE: never print the private key.
+        write!(f, "Identity {{ public_key: {} }}", self.public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_key_hex_roundtrip() {
+        let public_key = *Identity::generate().public_key();
+
+        assert_eq!(public_key.to_string().parse::<PublicKey>(), Ok(public_key));
+        assert_eq!("abcd".parse::<PublicKey>(), Err(IdentityError::InvalidKey));
+        assert_eq!(
+            "zz".repeat(KEY_LENGTH).parse::<PublicKey>(),
+            Err(IdentityError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn identity_from_private_key() {
+        let identity = Identity::generate();
+        let restored = Identity::from_private_key_hex(&identity.private_key_hex()).unwrap();
+
+        assert_eq!(restored.public_key(), identity.public_key());
+    }
+
+    #[test]
+    fn public_key_from_url_str() {
+        let public_key = *Identity::generate().public_key();
+
+        assert_eq!(
+            PublicKey::from_url_str(&format!("tcp://{}@127.0.0.1:15600", public_key)),
+            Ok(Some(public_key))
+        );
+        assert_eq!(PublicKey::from_url_str("tcp://127.0.0.1:15600"), Ok(None));
+        assert_eq!(
+            PublicKey::from_url_str("tcp://abcd@127.0.0.1:15600"),
+            Err(IdentityError::InvalidKey)
+        );
+    }
+}
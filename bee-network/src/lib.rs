@@ -28,42 +28,65 @@
 pub use address::{url::Url, Address, Port};
 pub use commands::{response_channel, Command, Requester, Responder};
 pub use config::{NetworkConfig, NetworkConfigBuilder};
-pub use endpoint::{origin::Origin, Endpoint, EndpointId};
-pub use events::{Event, EventSubscriber};
+pub use endpoint::{origin::Origin, outbox::OutboxPolicy, state::ConnectionState, Endpoint, EndpointId};
+pub use events::{Event, EventSubscriber, RefusalReason};
+pub use identity::{Identity, IdentityError, PublicKey, KEY_LENGTH};
+pub use memory::MemoryNetwork;
 
 pub use network::Network;
 
 mod address;
 mod commands;
+mod config;
 mod constants;
 mod endpoint;
 mod errors;
 mod events;
+mod identity;
+mod memory;
 mod network;
 mod tcp;
-// mod udp;
-mod config;
+mod udp;
 mod utils;
 
-use endpoint::{whitelist, worker::EndpointWorker as EpWorker};
+use constants::CONNECTION_ATTEMPTS_WINDOW;
+use endpoint::{
+    banlist::BanList,
+    limits::{ConnectionAttempts, ConnectionLimits},
+    whitelist,
+    worker::{Dialer, EndpointWorker as EpWorker},
+};
 use events::EventSubscriber as Events;
 use tcp::worker::TcpWorker;
-// use udp::worker::UdpWorker;
+use udp::worker::UdpWorker;
+use utils::shaping::Shaping;
 
 use bee_common::shutdown::Shutdown;
 
-use async_std::task::spawn;
+use async_std::{sync::Arc, task::spawn};
 use futures::channel::oneshot;
 
 /// Initializes the network layer.
-pub fn init(config: NetworkConfig, shutdown: &mut Shutdown) -> (Network, Events) {
+///
+/// The `identity` is only used to secure TCP connections if the config enables it.
+pub fn init(config: NetworkConfig, identity: Identity, shutdown: &mut Shutdown) -> (Network, Events) {
     let (command_sender, commands) = commands::command_channel();
     let (event_sender, events) = events::event_channel();
     let (internal_event_sender, internal_events) = events::event_channel();
 
     let (epw_sd_sender, epw_shutdown) = oneshot::channel();
     let (tcp_sd_sender, tcp_shutdown) = oneshot::channel();
-    // let (udp_sd_sender, udp_shutdown) = oneshot::channel();
+    let (udp_sd_sender, udp_shutdown) = oneshot::channel();
+    let (udp_sender, udp_instructions) = udp::instruction_channel();
+
+    let identity = if config.secure { Some(Arc::new(identity)) } else { None };
+
+    let banlist = Arc::new(BanList::new());
+    let limits = Arc::new(ConnectionLimits::new(
+        config.max_inbound_connections,
+        config.max_outbound_connections,
+    ));
+    let shaping = Shaping::new(&config);
 
     let ep_worker = EpWorker::new(
         commands,
@@ -71,15 +94,37 @@ pub fn init(config: NetworkConfig, shutdown: &mut Shutdown) -> (Network, Events)
         epw_shutdown,
         internal_event_sender.clone(),
         event_sender,
-        config.reconnect_interval,
+        Dialer {
+            udp: udp_sender,
+            identity: identity.clone(),
+            banlist: banlist.clone(),
+            shaping: shaping.clone(),
+            memory: None,
+        },
+        limits.clone(),
+        &config,
     );
 
-    let tcp_worker = TcpWorker::new(config.socket_addr(), internal_event_sender, tcp_shutdown);
-    // let udp_worker = UdpWorker::new(binding_addr, internal_event_sender.clone(), udp_shutdown);
+    let tcp_worker = TcpWorker::new(
+        config.socket_addr(),
+        identity,
+        banlist,
+        limits,
+        ConnectionAttempts::new(config.max_connection_attempts, CONNECTION_ATTEMPTS_WINDOW),
+        shaping,
+        internal_event_sender.clone(),
+        tcp_shutdown,
+    );
+    let udp_worker = UdpWorker::new(
+        config.socket_addr(),
+        udp_instructions,
+        internal_event_sender,
+        udp_shutdown,
+    );
 
     shutdown.add_worker_shutdown(epw_sd_sender, spawn(ep_worker.run()));
     shutdown.add_worker_shutdown(tcp_sd_sender, spawn(tcp_worker.run()));
-    // shutdown.add_worker_shutdown(udp_sd_sender, spawn(udp_worker.run()));
+    shutdown.add_worker_shutdown(udp_sd_sender, spawn(udp_worker.run()));
 
     whitelist::init();
     shutdown.add_action(|| whitelist::drop());
@@ -0,0 +1,359 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! An in-process transport, that connects simulated nodes without any sockets.
+//!
+//! Every node gets the same `Network` and `Event` API as with real sockets, while the links between them can be given a
+//! latency, a packet loss and partitions. Loss is decided by a random number generator per link direction, seeded from
+//! the seed of the network and the addresses of both ends, so that runs can be reproduced whatever the order in which
+//! the links get scheduled.
+//!
+//! NOTE: Only the network layer is simulated. The protocol and the tangle are process-wide singletons, so multi-node
+//! tests of the handshake, gossip, solidification or milestone synchronization can't be written against this transport;
+//! it only allows testing the exchange of raw messages between nodes.
+
+use crate::{
+    address::Address,
+    commands,
+    config::NetworkConfig,
+    endpoint::{
+        banlist::BanList,
+        limits::ConnectionLimits,
+        origin::Origin,
+        outbox::{bytes_channel, BytesReceiver},
+        whitelist,
+        worker::{Dialer, EndpointWorker as EpWorker},
+        Endpoint, EndpointId as EpId,
+    },
+    errors::{ConnectionError, ConnectionResult},
+    events::{self, Event, EventPublisher as Notifier, EventSubscriber as Events},
+    network::Network,
+    udp,
+    utils::shaping::Shaping,
+};
+
+use bee_common::shutdown::Shutdown;
+
+use async_std::{
+    net::SocketAddr,
+    sync::Arc,
+    task::{sleep, spawn},
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    prelude::*,
+};
+use log::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// A simulated network of in-process nodes.
+///
+/// NOTE: Simulated nodes accept every inbound connection, since the whitelist is shared by all nodes of the process.
+#[derive(Clone)]
+pub struct MemoryNetwork {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    nodes: HashMap<SocketAddr, Notifier>,
+    partitions: HashSet<(SocketAddr, SocketAddr)>,
+    latency: Duration,
+    packet_loss: f64,
+    seed: u64,
+}
+
+impl Inner {
+    fn is_partitioned(&self, a: SocketAddr, b: SocketAddr) -> bool {
+        self.partitions.contains(&(a, b)) || self.partitions.contains(&(b, a))
+    }
+}
+
+impl MemoryNetwork {
+    /// Creates a new simulated network, whose packet loss is drawn from generators seeded from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                nodes: HashMap::new(),
+                partitions: HashSet::new(),
+                latency: Duration::from_secs(0),
+                packet_loss: 0.0,
+                seed,
+            })),
+        }
+    }
+
+    /// Sets the time it takes a message to reach another node.
+    pub fn set_latency(&self, latency: Duration) {
+        self.lock().latency = latency;
+    }
+
+    /// Sets the probability (between 0 and 1) of a message getting lost.
+    pub fn set_packet_loss(&self, packet_loss: f64) {
+        self.lock().packet_loss = packet_loss;
+    }
+
+    /// Separates two nodes, so that they can neither connect nor exchange messages anymore.
+    pub fn partition(&self, a: Address, b: Address) {
+        self.lock().partitions.insert((*a, *b));
+    }
+
+    /// Reunites two nodes that have been separated.
+    pub fn heal(&self, a: Address, b: Address) {
+        let mut inner = self.lock();
+
+        inner.partitions.remove(&(*a, *b));
+        inner.partitions.remove(&(*b, *a));
+    }
+
+    /// Initializes a simulated node at the socket address of the `config`.
+    ///
+    /// NOTE: The whitelist is shared by all simulated nodes, so it is only dropped once the last of them shuts down.
+    pub fn init(&self, config: NetworkConfig, shutdown: &mut Shutdown) -> (Network, Events) {
+        let (command_sender, commands) = commands::command_channel();
+        let (event_sender, events) = events::event_channel();
+        let (internal_event_sender, internal_events) = events::event_channel();
+
+        let (epw_sd_sender, epw_shutdown) = oneshot::channel();
+
+        // NOTE: There is no UDP worker, simulated nodes exchange all messages in memory.
+        let (udp_sender, _) = udp::instruction_channel();
+
+        let address = *config.socket_addr();
+
+        {
+            let mut inner = self.lock();
+
+            if inner.nodes.is_empty() {
+                whitelist::init();
+            }
+            inner.nodes.insert(address, internal_event_sender.clone());
+        }
+
+        let ep_worker = EpWorker::new(
+            commands,
+            internal_events,
+            epw_shutdown,
+            internal_event_sender,
+            event_sender,
+            Dialer {
+                udp: udp_sender,
+                identity: None,
+                banlist: Arc::new(BanList::new()),
+                shaping: Shaping::new(&config),
+                memory: Some(Node {
+                    network: self.clone(),
+                    address,
+                }),
+            },
+            Arc::new(ConnectionLimits::new(
+                config.max_inbound_connections,
+                config.max_outbound_connections,
+            )),
+            &config,
+        );
+
+        shutdown.add_worker_shutdown(epw_sd_sender, spawn(ep_worker.run()));
+
+        let network = self.clone();
+        shutdown.add_action(move || network.remove(address));
+
+        (Network::new(config, command_sender), events)
+    }
+
+    fn remove(&self, address: SocketAddr) {
+        let mut inner = self.lock();
+
+        if inner.nodes.remove(&address).is_some() && inner.nodes.is_empty() {
+            whitelist::drop();
+        }
+    }
+
+    /// Returns the generator deciding the loss of the messages from `from` to `to`.
+    fn rng(&self, from: SocketAddr, to: SocketAddr) -> StdRng {
+        // NOTE: `DefaultHasher::new` always uses the same keys, so the seed only depends on the addresses.
+        let mut hasher = DefaultHasher::new();
+
+        self.lock().seed.hash(&mut hasher);
+        from.hash(&mut hasher);
+        to.hash(&mut hasher);
+
+        StdRng::seed_from_u64(hasher.finish())
+    }
+
+    /// Returns when a message from `from` to `to` arrives, or `None` if it gets lost.
+    fn deliver_at(&self, from: SocketAddr, to: SocketAddr, rng: &mut StdRng) -> Option<Instant> {
+        let inner = self.lock();
+
+        // NOTE: Drawn for every message, so that the sequence of losses doesn't depend on the partitions.
+        let is_lost = rng.gen::<f64>() < inner.packet_loss;
+
+        if inner.is_partitioned(from, to) || is_lost {
+            None
+        } else {
+            Some(Instant::now() + inner.latency)
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("poisoned memory network")
+    }
+}
+
+/// A simulated node, that connects to the other nodes of its `MemoryNetwork`.
+pub(crate) struct Node {
+    network: MemoryNetwork,
+    address: SocketAddr,
+}
+
+impl Node {
+    /// Connects to the simulated node at the address of `ep`.
+    pub async fn connect(&self, ep: &Endpoint, shaping: &Shaping, mut notifier: Notifier) -> ConnectionResult<()> {
+        info!("Trying to connect to {}...", ep.id);
+
+        let remote_notifier = {
+            let inner = self.network.lock();
+
+            if inner.is_partitioned(self.address, *ep.address) {
+                None
+            } else {
+                inner.nodes.get(&*ep.address).cloned()
+            }
+        };
+
+        let mut remote_notifier = match remote_notifier {
+            Some(remote_notifier) => remote_notifier,
+            None => {
+                warn!("Connecting to {} failed: unreachable.", ep.id);
+                return Err(ConnectionError::ConnectionAttemptFailed);
+            }
+        };
+
+        let local = End {
+            address: self.address,
+            epid: ep.id,
+            notifier: notifier.clone(),
+        };
+        let remote = End {
+            address: *ep.address,
+            epid: EpId::from(Address::from(self.address)),
+            notifier: remote_notifier.clone(),
+        };
+
+        let (sender, receiver) = bytes_channel(shaping.outbox_capacity);
+        let (remote_sender, remote_receiver) = bytes_channel(shaping.outbox_capacity);
+
+        spawn(link(self.network.clone(), local.clone(), remote.clone(), receiver));
+        spawn(link(self.network.clone(), remote, local, remote_receiver));
+
+        info!(
+            "Sucessfully established connection to {} ({}).",
+            ep.address,
+            Origin::Outbound
+        );
+
+        remote_notifier
+            .send(Event::NewConnection {
+                ep: Endpoint::new(Address::from(self.address), ep.protocol),
+                origin: Origin::Inbound,
+                public_key: None,
+                sender: remote_sender,
+            })
+            .await?;
+
+        Ok(notifier
+            .send(Event::NewConnection {
+                ep: ep.clone(),
+                origin: Origin::Outbound,
+                public_key: None,
+                sender,
+            })
+            .await?)
+    }
+}
+
+/// One end of a simulated connection.
+#[derive(Clone)]
+struct End {
+    address: SocketAddr,
+    // The id this end knows the other end by.
+    epid: EpId,
+    notifier: Notifier,
+}
+
+/// Carries the messages of one direction of a simulated connection.
+async fn link(network: MemoryNetwork, from: End, to: End, mut bytes_rx: BytesReceiver) {
+    let (mut in_flight, mut arrivals) = mpsc::unbounded::<(Instant, Vec<u8>)>();
+    let (to_address, to_epid, mut to_notifier) = (to.address, to.epid, to.notifier);
+
+    // NOTE: Messages are delivered by their own task, so that the latency doesn't limit the throughput.
+    let delivery = spawn(async move {
+        let mut total_bytes = 0;
+
+        while let Some((deliver_at, bytes)) = arrivals.next().await {
+            let now = Instant::now();
+            if deliver_at > now {
+                sleep(deliver_at - now).await;
+            }
+
+            total_bytes += bytes.len() as u64;
+
+            let event = Event::MessageReceived {
+                epid: to_epid,
+                bytes,
+                total_bytes,
+            };
+            if to_notifier.send(event).await.is_err() {
+                warn!("Failed to send 'MessageReceived' notification.");
+            }
+        }
+
+        // NOTE: The sending end dropped the connection.
+        if to_notifier.send(Event::LostConnection { epid: to_epid }).await.is_err() {
+            warn!("Failed to send 'LostConnection' notification.");
+        }
+    });
+
+    let mut notifier = from.notifier;
+    let mut rng = network.rng(from.address, to_address);
+    let mut total_bytes = 0;
+
+    while let Some(bytes) = bytes_rx.next().await {
+        match network.deliver_at(from.address, to_address, &mut rng) {
+            Some(deliver_at) => {
+                if in_flight.send((deliver_at, bytes.to_vec())).await.is_err() {
+                    break;
+                }
+            }
+            None => trace!("Message from {} to {} got lost.", from.address, to_address),
+        }
+
+        total_bytes += bytes.len() as u64;
+
+        let event = Event::MessageSent {
+            epid: from.epid,
+            num_bytes: bytes.len(),
+            total_bytes,
+        };
+        if notifier.send(event).await.is_err() {
+            warn!("Failed to send 'MessageSent' notification.");
+        }
+    }
+
+    drop(in_flight);
+    delivery.await;
+}
@@ -11,6 +11,8 @@
 
 use crate::{endpoint::origin::Origin, errors::ConnectionResult};
 
+use super::noise::SecureChannel;
+
 use async_std::{
     net::{SocketAddr, TcpStream},
     sync::Arc,
@@ -24,6 +26,7 @@ pub struct TcpConnection {
     pub local_addr: SocketAddr,
     pub remote_addr: SocketAddr,
     pub stream: Arc<TcpStream>,
+    pub secure_channel: Option<SecureChannel>,
 }
 
 impl TcpConnection {
@@ -37,6 +40,7 @@ impl TcpConnection {
             local_addr,
             remote_addr,
             stream,
+            secure_channel: None,
         })
     }
 }
@@ -50,7 +54,6 @@ impl fmt::Display for TcpConnection {
 impl Eq for TcpConnection {}
 impl PartialEq for TcpConnection {
     fn eq(&self, other: &Self) -> bool {
-        // TODO: use socket address instead of IP
-        self.remote_addr.ip() == other.remote_addr.ip()
+        self.remote_addr == other.remote_addr
     }
 }
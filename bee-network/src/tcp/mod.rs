@@ -10,87 +10,212 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 pub mod connection;
+pub mod noise;
 pub mod worker;
 
 use connection::TcpConnection;
+use noise::{read_frame, write_frame, SecureChannel, MAX_PAYLOAD_SIZE};
 
 use crate::{
     address::{url::Protocol, Address},
-    constants::MAX_BUFFER_SIZE,
+    constants::{CONNECT_TIMEOUT, HANDSHAKE_TIMEOUT, MAX_BUFFER_SIZE},
     endpoint::{
+        banlist::{BanList, Banned},
         origin::Origin,
         outbox::{bytes_channel, BytesReceiver},
-        Endpoint, EndpointId as EpId,
+        whitelist, Endpoint, EndpointId as EpId,
     },
     errors::{ConnectionError, ConnectionResult},
     events::{Event, EventPublisher as Notifier},
+    identity::{Identity, PublicKey},
+    utils::shaping::{Limiter, Shaping},
 };
 
-use async_std::{net::TcpStream, sync::Arc, task::spawn};
+use async_std::{future::timeout, net::TcpStream, sync::Arc, task::spawn};
 use futures::{channel::oneshot, prelude::*, select};
 use log::*;
 
 /// Tries to connect to an endpoint.
-pub(crate) async fn try_connect(epid: &EpId, addr: &Address, notifier: Notifier) -> ConnectionResult<()> {
+pub(crate) async fn try_connect(
+    epid: &EpId,
+    addr: &Address,
+    identity: Option<Arc<Identity>>,
+    banlist: &BanList,
+    shaping: &Shaping,
+    notifier: Notifier,
+) -> ConnectionResult<()> {
     info!("Trying to connect to {}...", epid);
 
-    match TcpStream::connect(**addr).await {
-        Ok(stream) => {
-            let conn = match TcpConnection::new(stream, Origin::Outbound) {
-                Ok(conn) => conn,
-                Err(e) => {
-                    error!["Error creating TCP connection: {:?}.", e];
-                    return Err(ConnectionError::ConnectionAttemptFailed);
-                }
-            };
-
-            info!(
-                "Sucessfully established connection to {} ({}).",
-                conn.remote_addr,
-                Origin::Outbound
-            );
+    let stream = match timeout(CONNECT_TIMEOUT, TcpStream::connect(**addr)).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => {
+            warn!("Connecting to {} failed: {:?}.", epid, e);
+            return Err(ConnectionError::ConnectionAttemptFailed);
+        }
+        Err(_) => {
+            warn!("Connecting to {} timed out.", epid);
+            return Err(ConnectionError::ConnectionAttemptFailed);
+        }
+    };
 
-            Ok(spawn_connection_workers(conn, notifier).await?)
+    let conn = match TcpConnection::new(stream, Origin::Outbound) {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!["Error creating TCP connection: {:?}.", e];
+            return Err(ConnectionError::ConnectionAttemptFailed);
         }
+    };
+
+    let pinned_key = whitelist::get().pinned_key(epid);
+    // NOTE: The endpoint might turn out to authenticate with a banned public key, even if it isn't pinned to it.
+    let conn = match secure(conn, identity.as_deref(), |key| {
+        pinned_key.map_or(true, |pinned_key| key == Some(&pinned_key))
+            && key.map_or(true, |key| !banlist.contains(&Banned::PublicKey(*key)))
+    })
+    .await
+    {
+        Ok(conn) => conn,
         Err(e) => {
-            warn!("Connecting to {} failed: {:?}.", epid, e);
-            Err(ConnectionError::ConnectionAttemptFailed)
+            warn!("Securing connection to {} failed: {:?}.", epid, e);
+            return Err(ConnectionError::ConnectionAttemptFailed);
         }
+    };
+
+    info!(
+        "Sucessfully established connection to {} ({}).",
+        conn.remote_addr,
+        Origin::Outbound
+    );
+
+    // NOTE: Keep the id of the endpoint, which might differ from its current address.
+    let ep = Endpoint {
+        id: *epid,
+        ..Endpoint::new(*addr, Protocol::Tcp)
+    };
+
+    Ok(spawn_connection_workers(conn, ep, shaping, notifier).await?)
+}
+
+/// Runs the secure handshake if an identity is given, then checks the public key of the remote, if any, with
+/// `is_allowed`. The handshake fails if the remote doesn't complete it within `HANDSHAKE_TIMEOUT`.
+///
+/// NOTE: Without a secure channel there is no public key, so pinned endpoints can't be connected.
+pub(crate) async fn secure(
+    mut conn: TcpConnection,
+    identity: Option<&Identity>,
+    is_allowed: impl Fn(Option<&PublicKey>) -> bool,
+) -> ConnectionResult<TcpConnection> {
+    if let Some(identity) = identity {
+        let initiator = matches!(conn.origin, Origin::Outbound);
+
+        let channel = timeout(HANDSHAKE_TIMEOUT, noise::handshake(&conn.stream, identity, initiator))
+            .await
+            .map_err(|_| ConnectionError::SecureHandshakeTimedOut)??;
+
+        conn.secure_channel.replace(channel);
+    }
+
+    if is_allowed(conn.secure_channel.as_ref().map(|channel| &channel.remote_public_key)) {
+        Ok(conn)
+    } else {
+        Err(ConnectionError::UnexpectedPublicKey)
     }
 }
 
-pub(crate) async fn spawn_connection_workers(conn: TcpConnection, mut notifier: Notifier) -> ConnectionResult<()> {
+pub(crate) async fn spawn_connection_workers(
+    conn: TcpConnection,
+    ep: Endpoint,
+    shaping: &Shaping,
+    mut notifier: Notifier,
+) -> ConnectionResult<()> {
     debug!("Spawning TCP connection workers...");
 
-    let addr: Address = conn.remote_addr.into();
-    let proto = Protocol::Tcp;
     let origin = conn.origin;
+    let public_key = conn.secure_channel.as_ref().map(|channel| channel.remote_public_key);
 
-    let ep = Endpoint::new(addr, proto);
-
-    let (sender, receiver) = bytes_channel();
+    let (sender, receiver) = bytes_channel(shaping.outbox_capacity);
     let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
-    spawn(writer(ep.id, conn.stream.clone(), receiver, shutdown_sender));
-    spawn(reader(ep.id, conn.stream.clone(), notifier.clone(), shutdown_receiver));
+    let upload = shaping.upload.limiter();
+    let download = shaping.download.limiter();
+
+    match conn.secure_channel {
+        Some(channel) => {
+            spawn(secure_writer(
+                ep.id,
+                conn.stream.clone(),
+                channel.clone(),
+                receiver,
+                upload,
+                notifier.clone(),
+                shutdown_sender,
+            ));
+            spawn(secure_reader(
+                ep.id,
+                conn.stream.clone(),
+                channel,
+                download,
+                notifier.clone(),
+                shutdown_receiver,
+            ));
+        }
+        None => {
+            spawn(writer(
+                ep.id,
+                conn.stream.clone(),
+                receiver,
+                upload,
+                notifier.clone(),
+                shutdown_sender,
+            ));
+            spawn(reader(
+                ep.id,
+                conn.stream.clone(),
+                download,
+                notifier.clone(),
+                shutdown_receiver,
+            ));
+        }
+    }
 
-    Ok(notifier.send(Event::NewConnection { ep, origin, sender }).await?)
+    Ok(notifier
+        .send(Event::NewConnection {
+            ep,
+            origin,
+            public_key,
+            sender,
+        })
+        .await?)
 }
 
-async fn writer(epid: EpId, stream: Arc<TcpStream>, bytes_rx: BytesReceiver, sd: oneshot::Sender<()>) {
+async fn writer(
+    epid: EpId,
+    stream: Arc<TcpStream>,
+    bytes_rx: BytesReceiver,
+    mut upload: Limiter,
+    mut notifier: Notifier,
+    sd: oneshot::Sender<()>,
+) {
     debug!("Starting connection writer task for {}...", epid);
 
     let mut stream = &*stream;
     let mut bytes_rx = bytes_rx.fuse();
+    let mut total_bytes = 0;
 
     loop {
         select! {
             bytes_out = bytes_rx.next() => {
                 if let Some(bytes_out) = bytes_out {
+                    upload.throttle(bytes_out.len()).await;
 
                     match stream.write_all(&*bytes_out).await {
                         Ok(_) => {
-                            // NOTE: if we should need it, we can raise [`Event::BytesSent`] here.
+                            total_bytes += bytes_out.len() as u64;
+
+                            let event = Event::MessageSent { epid, num_bytes: bytes_out.len(), total_bytes };
+                            if notifier.send(event).await.is_err() {
+                                warn!("Failed to send 'MessageSent' notification.");
+                            }
                         },
                         Err(e) => {
                             error!("Sending bytes failed: {:?}.", e);
@@ -112,12 +237,19 @@ async fn writer(epid: EpId, stream: Arc<TcpStream>, bytes_rx: BytesReceiver, sd:
     debug!("Connection writer event loop for {} stopped.", epid);
 }
 
-async fn reader(epid: EpId, stream: Arc<TcpStream>, mut notifier: Notifier, mut sd: oneshot::Receiver<()>) {
+async fn reader(
+    epid: EpId,
+    stream: Arc<TcpStream>,
+    mut download: Limiter,
+    mut notifier: Notifier,
+    mut sd: oneshot::Receiver<()>,
+) {
     debug!("Starting connection reader event loop for {}...", epid);
 
     let mut stream = &*stream;
     let mut buffer = vec![0; MAX_BUFFER_SIZE];
     let shutdown = &mut sd;
+    let mut total_bytes = 0;
 
     loop {
         select! {
@@ -137,7 +269,11 @@ async fn reader(epid: EpId, stream: Arc<TcpStream>, mut notifier: Notifier, mut
                             let mut bytes = vec![0u8; num_read];
                             bytes.copy_from_slice(&buffer[0..num_read]);
 
-                            if notifier.send(Event::MessageReceived { epid, bytes }).await.is_err() {
+                            // NOTE: Delaying the next read lets the TCP flow control slow down the sender.
+                            download.throttle(num_read).await;
+                            total_bytes += num_read as u64;
+
+                            if notifier.send(Event::MessageReceived { epid, bytes, total_bytes }).await.is_err() {
                                 warn!("Failed to send 'MessageReceived' notification.");
                             }
                         }
@@ -155,3 +291,110 @@ async fn reader(epid: EpId, stream: Arc<TcpStream>, mut notifier: Notifier, mut
     }
     debug!("Connection reader event loop for {} stopped.", epid);
 }
+
+async fn secure_writer(
+    epid: EpId,
+    stream: Arc<TcpStream>,
+    channel: SecureChannel,
+    mut bytes_rx: BytesReceiver,
+    mut upload: Limiter,
+    mut notifier: Notifier,
+    sd: oneshot::Sender<()>,
+) {
+    debug!("Starting secure connection writer task for {}...", epid);
+
+    let mut nonce = 0;
+    let mut total_bytes = 0;
+
+    // NOTE: If the bytes sender gets dropped (which happens when the connection pool is dropped), we break out of the
+    // loop.
+    'outer: while let Some(bytes_out) = bytes_rx.next().await {
+        for payload in bytes_out.chunks(MAX_PAYLOAD_SIZE) {
+            upload.throttle(payload.len()).await;
+
+            let written = match channel.encrypt(nonce, payload) {
+                Ok(message) => write_frame(&stream, &message).await.map_err(ConnectionError::from),
+                Err(e) => Err(e),
+            };
+
+            nonce += 1;
+
+            if let Err(e) = written {
+                // NOTE: A lost frame desynchronizes the nonces, so the connection can't be used anymore.
+                error!("Sending bytes failed: {:?}.", e);
+                break 'outer;
+            }
+        }
+
+        total_bytes += bytes_out.len() as u64;
+
+        let event = Event::MessageSent {
+            epid,
+            num_bytes: bytes_out.len(),
+            total_bytes,
+        };
+        if notifier.send(event).await.is_err() {
+            warn!("Failed to send 'MessageSent' notification.");
+        }
+    }
+
+    if sd.send(()).is_err() {
+        trace!("Reader task shut down before writer task.");
+    }
+
+    debug!("Secure connection writer event loop for {} stopped.", epid);
+}
+
+async fn secure_reader(
+    epid: EpId,
+    stream: Arc<TcpStream>,
+    channel: SecureChannel,
+    mut download: Limiter,
+    mut notifier: Notifier,
+    mut sd: oneshot::Receiver<()>,
+) {
+    debug!("Starting secure connection reader event loop for {}...", epid);
+
+    let shutdown = &mut sd;
+    let mut nonce = 0;
+    let mut total_bytes = 0;
+
+    loop {
+        select! {
+            message = read_frame(&stream).fuse() => {
+                let bytes = message
+                    .map_err(ConnectionError::from)
+                    .and_then(|message| channel.decrypt(nonce, &message));
+
+                nonce += 1;
+
+                match bytes {
+                    Ok(bytes) => {
+                        download.throttle(bytes.len()).await;
+                        total_bytes += bytes.len() as u64;
+
+                        if notifier.send(Event::MessageReceived { epid, bytes, total_bytes }).await.is_err() {
+                            warn!("Failed to send 'MessageReceived' notification.");
+                        }
+                    }
+                    Err(e) => {
+                        trace!("Receiving bytes failed: {:?}.", e);
+
+                        if notifier.send(Event::LostConnection { epid }).await.is_err() {
+                            warn!("Failed to send 'LostConnection' notification.");
+                        }
+
+                        // NOTE: local reader shut down first (we were disconnected or the channel got corrupted)
+                        break;
+                    }
+                }
+            },
+            _ = shutdown.fuse() => {
+                // NOTE: local writer shut down first (we disconnected)
+                break;
+            }
+        }
+    }
+
+    debug!("Secure connection reader event loop for {} stopped.", epid);
+}
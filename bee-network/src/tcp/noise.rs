@@ -0,0 +1,225 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! A Noise XX secure channel, authenticating both sides by their static identity keys.
+//!
+//! Handshake and transport messages are framed by their length as a big-endian `u16`.
+
+use crate::{
+    errors::{ConnectionError, ConnectionResult},
+    identity::{Identity, PublicKey, KEY_LENGTH},
+};
+
+use async_std::{io, net::TcpStream, sync::Arc};
+use futures::prelude::*;
+use log::*;
+use snow::{Builder, HandshakeState, StatelessTransportState};
+
+const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+const MAX_MESSAGE_SIZE: usize = 65535;
+const TAG_SIZE: usize = 16;
+
+/// Maximum number of plaintext bytes carried by a single transport message.
+pub(crate) const MAX_PAYLOAD_SIZE: usize = MAX_MESSAGE_SIZE - TAG_SIZE;
+
+/// An established secure channel.
+#[derive(Clone)]
+pub struct SecureChannel {
+    /// The static public key of the remote, authenticated by the handshake.
+    pub remote_public_key: PublicKey,
+    transport: Arc<StatelessTransportState>,
+}
+
+impl SecureChannel {
+    /// Encrypts the `nonce`-th message sent over this channel.
+    pub(crate) fn encrypt(&self, nonce: u64, payload: &[u8]) -> ConnectionResult<Vec<u8>> {
+        let mut message = vec![0u8; payload.len() + TAG_SIZE];
+
+        let len = self
+            .transport
+            .write_message(nonce, payload, &mut message)
+            .map_err(|_| ConnectionError::SecureChannelFailure)?;
+        message.truncate(len);
+
+        Ok(message)
+    }
+
+    /// Decrypts the `nonce`-th message received over this channel.
+    pub(crate) fn decrypt(&self, nonce: u64, message: &[u8]) -> ConnectionResult<Vec<u8>> {
+        let mut payload = vec![0u8; message.len()];
+
+        let len = self
+            .transport
+            .read_message(nonce, message, &mut payload)
+            .map_err(|_| ConnectionError::SecureChannelFailure)?;
+        payload.truncate(len);
+
+        Ok(payload)
+    }
+}
+
+pub(crate) async fn write_frame(mut stream: &TcpStream, message: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(2 + message.len());
+
+    frame.extend_from_slice(&(message.len() as u16).to_be_bytes());
+    frame.extend_from_slice(message);
+
+    stream.write_all(&frame).await
+}
+
+pub(crate) async fn read_frame(mut stream: &TcpStream) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 2];
+    stream.read_exact(&mut len).await?;
+
+    let mut message = vec![0u8; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut message).await?;
+
+    Ok(message)
+}
+
+async fn send(stream: &TcpStream, state: &mut HandshakeState) -> ConnectionResult<()> {
+    let mut message = vec![0u8; MAX_MESSAGE_SIZE];
+
+    let len = state
+        .write_message(&[], &mut message)
+        .map_err(|_| ConnectionError::SecureHandshakeFailed)?;
+
+    Ok(write_frame(stream, &message[..len]).await?)
+}
+
+async fn receive(stream: &TcpStream, state: &mut HandshakeState) -> ConnectionResult<()> {
+    let message = read_frame(stream).await?;
+    let mut payload = vec![0u8; MAX_MESSAGE_SIZE];
+
+    state
+        .read_message(&message, &mut payload)
+        .map_err(|_| ConnectionError::SecureHandshakeFailed)?;
+
+    Ok(())
+}
+
+/// Runs the Noise XX handshake, the connecting side being the initiator.
+pub(crate) async fn handshake(
+    stream: &TcpStream,
+    identity: &Identity,
+    initiator: bool,
+) -> ConnectionResult<SecureChannel> {
+    let params = NOISE_PARAMS
+        .parse()
+        .map_err(|_| ConnectionError::SecureHandshakeFailed)?;
+    let builder = Builder::new(params).local_private_key(identity.private_key());
+    let mut state = if initiator {
+        builder.build_initiator()
+    } else {
+        builder.build_responder()
+    }
+    .map_err(|_| ConnectionError::SecureHandshakeFailed)?;
+
+    // -> e
+    // <- e, ee, s, es
+    // -> s, se
+    if initiator {
+        send(stream, &mut state).await?;
+        receive(stream, &mut state).await?;
+        send(stream, &mut state).await?;
+    } else {
+        receive(stream, &mut state).await?;
+        send(stream, &mut state).await?;
+        receive(stream, &mut state).await?;
+    }
+
+    let remote_public_key = match state.get_remote_static() {
+        Some(key) if key.len() == KEY_LENGTH => {
+            let mut bytes = [0u8; KEY_LENGTH];
+            bytes.copy_from_slice(key);
+            PublicKey::from_bytes(bytes)
+        }
+        _ => return Err(ConnectionError::SecureHandshakeFailed),
+    };
+    let transport = state
+        .into_stateless_transport_mode()
+        .map_err(|_| ConnectionError::SecureHandshakeFailed)?;
+
+    debug!("Secure handshake with {} completed.", remote_public_key);
+
+    Ok(SecureChannel {
+        remote_public_key,
+        transport: Arc::new(transport),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_std::{
+        net::TcpListener,
+        task::{block_on, spawn},
+    };
+
+    #[test]
+    fn handshake_and_exchange_on_loopback() {
+        let server_identity = Identity::generate();
+        let client_identity = Identity::generate();
+        let server_public_key = *server_identity.public_key();
+        let client_public_key = *client_identity.public_key();
+
+        block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let server = spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let channel = handshake(&stream, &server_identity, false).await.unwrap();
+                let message = read_frame(&stream).await.unwrap();
+
+                (channel.remote_public_key, channel.decrypt(0, &message).unwrap())
+            });
+
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let channel = handshake(&stream, &client_identity, true).await.unwrap();
+            let message = channel.encrypt(0, b"ping").unwrap();
+
+            assert_ne!(&message[..], b"ping");
+            write_frame(&stream, &message).await.unwrap();
+
+            assert_eq!(channel.remote_public_key, server_public_key);
+            assert_eq!(server.await, (client_public_key, b"ping".to_vec()));
+        });
+    }
+
+    #[test]
+    fn replayed_message_is_rejected() {
+        let identity = Identity::generate();
+
+        block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let server = spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let channel = handshake(&stream, &Identity::generate(), false).await.unwrap();
+                let message = read_frame(&stream).await.unwrap();
+
+                channel.decrypt(1, &message).is_err()
+            });
+
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let channel = handshake(&stream, &identity, true).await.unwrap();
+
+            write_frame(&stream, &channel.encrypt(0, b"ping").unwrap())
+                .await
+                .unwrap();
+
+            assert!(server.await);
+        });
+    }
+}
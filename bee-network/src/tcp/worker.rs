@@ -10,29 +10,55 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    address::Address,
-    endpoint::{origin::Origin, whitelist},
-    events::EventPublisher as Notifier,
+    address::{url::Protocol, Address},
+    endpoint::{
+        banlist::{BanList, Banned},
+        limits::{ConnectionAttempts, ConnectionLimits},
+        origin::Origin,
+        whitelist, Endpoint,
+    },
+    events::{Event, EventPublisher as Notifier, RefusalReason},
+    identity::Identity,
+    utils::shaping::Shaping,
 };
 
-use super::{connection::TcpConnection, spawn_connection_workers};
+use super::{connection::TcpConnection, secure, spawn_connection_workers};
 
 use bee_common::{shutdown::ShutdownListener as Shutdown, worker::Error as WorkerError};
 
-use async_std::net::TcpListener;
+use async_std::{net::TcpListener, sync::Arc, task::spawn};
 use futures::{prelude::*, select};
 use log::*;
 
 pub(crate) struct TcpWorker {
     binding_addr: Address,
+    identity: Option<Arc<Identity>>,
+    banlist: Arc<BanList>,
+    limits: Arc<ConnectionLimits>,
+    attempts: ConnectionAttempts,
+    shaping: Shaping,
     notifier: Notifier,
     shutdown: Shutdown,
 }
 
 impl TcpWorker {
-    pub fn new(binding_addr: Address, notifier: Notifier, shutdown: Shutdown) -> Self {
+    pub fn new(
+        binding_addr: Address,
+        identity: Option<Arc<Identity>>,
+        banlist: Arc<BanList>,
+        limits: Arc<ConnectionLimits>,
+        attempts: ConnectionAttempts,
+        shaping: Shaping,
+        notifier: Notifier,
+        shutdown: Shutdown,
+    ) -> Self {
         Self {
             binding_addr,
+            identity,
+            banlist,
+            limits,
+            attempts,
+            shaping,
             notifier,
             shutdown,
         }
@@ -63,28 +89,48 @@ impl TcpWorker {
                                     }
                                 };
 
-                                let whitelist = whitelist::get();
+                                let ip = conn.remote_addr.ip();
+
+                                let refusal = if !self.attempts.record(ip) {
+                                    Some(RefusalReason::TooManyAttempts)
+                                } else if self.banlist.contains(&Banned::Address(ip)) {
+                                    Some(RefusalReason::Banned)
+                                } else if !self.limits.admits(&Origin::Inbound) {
+                                    // NOTE: Checked again once connected, since the secure handshake takes a while.
+                                    Some(RefusalReason::InboundLimit)
+                                } else {
+                                    None
+                                };
 
-                                // Update IP addresses if necessary
-                                // whitelist.refresh().await;
+                                if let Some(reason) = refusal {
+                                    warn!("Refused connection from '{}': {}.", ip, reason);
 
-                                // Immediatedly drop stream, if it's associated IP address isn't whitelisted
-                                if !whitelist.contains_address(&conn.remote_addr.ip()) {
-                                    warn!("Contacted by unknown IP address '{}'.", &conn.remote_addr.ip());
-                                    warn!("Connection disallowed.");
+                                    let event = Event::ConnectionRefused { address: conn.remote_addr.into(), reason };
+                                    if self.notifier.send(event).await.is_err() {
+                                        warn!("Failed to send 'ConnectionRefused' notification.");
+                                    }
                                     continue;
                                 }
 
-                                info!(
-                                    "Sucessfully established connection to {} ({}).",
-                                    conn.remote_addr,
-                                    Origin::Inbound
-                                );
+                                let whitelist = whitelist::get();
 
-                                match spawn_connection_workers(conn, self.notifier.clone()).await {
-                                    Ok(_) => (),
-                                    Err(_) => (),
+                                // Immediatedly drop stream, if it's associated IP address isn't whitelisted. Secure
+                                // connections are checked after the handshake instead, since pinned endpoints are
+                                // identified by their public key and may connect from any address.
+                                if self.identity.is_none() && !whitelist.contains_address(&conn.remote_addr.ip()) {
+                                    warn!("Contacted by unknown IP address '{}'.", &conn.remote_addr.ip());
+                                    warn!("Connection disallowed.");
+                                    continue;
                                 }
+
+                                // NOTE: The secure handshake is done in its own task to keep accepting connections.
+                                spawn(accept(
+                                    conn,
+                                    self.identity.clone(),
+                                    self.banlist.clone(),
+                                    self.shaping.clone(),
+                                    self.notifier.clone(),
+                                ));
                             }
                             Err(e) => {
                                 error!("Accepting connection failed: {:?}.", e);
@@ -104,3 +150,52 @@ impl TcpWorker {
         Ok(())
     }
 }
+
+async fn accept(
+    conn: TcpConnection,
+    identity: Option<Arc<Identity>>,
+    banlist: Arc<BanList>,
+    shaping: Shaping,
+    mut notifier: Notifier,
+) {
+    let ip = conn.remote_addr.ip();
+
+    let conn = match secure(conn, identity.as_deref(), |public_key| {
+        whitelist::get().accepts(&ip, public_key)
+    })
+    .await
+    {
+        Ok(conn) => conn,
+        Err(e) => {
+            warn!("Connection from '{}' disallowed: {:?}.", ip, e);
+            return;
+        }
+    };
+
+    // NOTE: Authenticated endpoints are banned by their public key, whatever address they connect from.
+    let public_key = conn.secure_channel.as_ref().map(|channel| channel.remote_public_key);
+    if public_key.map_or(false, |public_key| banlist.contains(&Banned::PublicKey(public_key))) {
+        warn!("Refused connection from '{}': {}.", ip, RefusalReason::Banned);
+
+        let event = Event::ConnectionRefused {
+            address: conn.remote_addr.into(),
+            reason: RefusalReason::Banned,
+        };
+        if notifier.send(event).await.is_err() {
+            warn!("Failed to send 'ConnectionRefused' notification.");
+        }
+        return;
+    }
+
+    info!(
+        "Sucessfully established connection to {} ({}).",
+        conn.remote_addr,
+        Origin::Inbound
+    );
+
+    let ep = Endpoint::new(conn.remote_addr.into(), Protocol::Tcp);
+
+    if let Err(e) = spawn_connection_workers(conn, ep, &shaping, notifier).await {
+        error!("Spawning connection workers failed: {:?}.", e);
+    }
+}
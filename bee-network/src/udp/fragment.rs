@@ -0,0 +1,179 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Splitting of messages into datagrams that don't get fragmented by IP, and their reassembly.
+//!
+//! Each datagram starts with a header made of the id of the message (`u16`, big-endian), the index of the fragment and
+//! the number of fragments of the message.
+
+use crate::constants::MAX_DATAGRAM_SIZE;
+
+use std::collections::VecDeque;
+
+const HEADER_SIZE: usize = 4;
+const MAX_FRAGMENT_SIZE: usize = MAX_DATAGRAM_SIZE - HEADER_SIZE;
+const MAX_FRAGMENTS: usize = 4;
+// Number of messages from the same address that can be reassembled at once, the oldest being dropped beyond.
+const MAX_PARTIAL_MESSAGES: usize = 4;
+
+/// The maximum size of a message sent over UDP.
+pub(crate) const MAX_MESSAGE_SIZE: usize = MAX_FRAGMENTS * MAX_FRAGMENT_SIZE;
+
+/// Splits a message of at most `MAX_MESSAGE_SIZE` bytes into datagrams.
+pub(crate) fn split(id: u16, bytes: &[u8]) -> Vec<Vec<u8>> {
+    let fragments = if bytes.is_empty() {
+        vec![bytes]
+    } else {
+        bytes.chunks(MAX_FRAGMENT_SIZE).collect()
+    };
+    let count = fragments.len() as u8;
+
+    fragments
+        .into_iter()
+        .enumerate()
+        .map(|(index, fragment)| {
+            let mut datagram = Vec::with_capacity(HEADER_SIZE + fragment.len());
+
+            datagram.extend_from_slice(&id.to_be_bytes());
+            datagram.push(index as u8);
+            datagram.push(count);
+            datagram.extend_from_slice(fragment);
+
+            datagram
+        })
+        .collect()
+}
+
+struct PartialMessage {
+    id: u16,
+    fragments: Vec<Option<Vec<u8>>>,
+}
+
+/// Reassembles the messages received from one address.
+#[derive(Default)]
+pub(crate) struct Reassembler {
+    partial: VecDeque<PartialMessage>,
+}
+
+impl Reassembler {
+    /// Adds a datagram, and returns the message it completes, if any. Malformed datagrams are ignored.
+    pub(crate) fn add(&mut self, datagram: &[u8]) -> Option<Vec<u8>> {
+        if datagram.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let id = u16::from_be_bytes([datagram[0], datagram[1]]);
+        let (index, count) = (datagram[2] as usize, datagram[3] as usize);
+        let fragment = &datagram[HEADER_SIZE..];
+
+        if count == 0 || count > MAX_FRAGMENTS || index >= count {
+            return None;
+        }
+        if count == 1 {
+            return Some(fragment.to_vec());
+        }
+
+        let position = match self
+            .partial
+            .iter()
+            .position(|message| message.id == id && message.fragments.len() == count)
+        {
+            Some(position) => position,
+            None => {
+                if self.partial.len() == MAX_PARTIAL_MESSAGES {
+                    self.partial.pop_front();
+                }
+                self.partial.push_back(PartialMessage {
+                    id,
+                    fragments: vec![None; count],
+                });
+                self.partial.len() - 1
+            }
+        };
+
+        let message = &mut self.partial[position];
+        message.fragments[index] = Some(fragment.to_vec());
+
+        if message.fragments.iter().all(Option::is_some) {
+            self.partial
+                .remove(position)
+                .map(|message| message.fragments.into_iter().flatten().flatten().collect())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_reassemble() {
+        let mut reassembler = Reassembler::default();
+
+        for len in &[0, 1, MAX_FRAGMENT_SIZE, MAX_FRAGMENT_SIZE + 1, MAX_MESSAGE_SIZE] {
+            let bytes = (0..*len).map(|i| i as u8).collect::<Vec<u8>>();
+            let datagrams = split(*len as u16, &bytes);
+
+            assert_eq!(
+                datagrams.len(),
+                ((len + MAX_FRAGMENT_SIZE - 1) / MAX_FRAGMENT_SIZE).max(1)
+            );
+            assert!(datagrams.iter().all(|datagram| datagram.len() <= MAX_DATAGRAM_SIZE));
+
+            let (last, first) = datagrams.split_last().unwrap();
+            for datagram in first {
+                assert_eq!(reassembler.add(datagram), None);
+            }
+            assert_eq!(reassembler.add(last), Some(bytes));
+        }
+    }
+
+    #[test]
+    fn reassemble_out_of_order_and_interleaved() {
+        let mut reassembler = Reassembler::default();
+        let a = split(1, &[1u8; MAX_MESSAGE_SIZE]);
+        let b = split(2, &[2u8; MAX_FRAGMENT_SIZE + 1]);
+
+        assert_eq!(reassembler.add(&a[3]), None);
+        assert_eq!(reassembler.add(&b[1]), None);
+        assert_eq!(reassembler.add(&a[0]), None);
+        assert_eq!(reassembler.add(&a[2]), None);
+        assert_eq!(reassembler.add(&b[0]), Some(vec![2u8; MAX_FRAGMENT_SIZE + 1]));
+        assert_eq!(reassembler.add(&a[1]), Some(vec![1u8; MAX_MESSAGE_SIZE]));
+    }
+
+    #[test]
+    fn drop_oldest_partial_message() {
+        let mut reassembler = Reassembler::default();
+        let messages = (0..=MAX_PARTIAL_MESSAGES as u16)
+            .map(|id| split(id, &[0u8; MAX_FRAGMENT_SIZE + 1]))
+            .collect::<Vec<_>>();
+
+        for message in &messages {
+            assert_eq!(reassembler.add(&message[0]), None);
+        }
+
+        assert!(reassembler.add(&messages[1][1]).is_some());
+        assert_eq!(reassembler.add(&messages[0][1]), None);
+    }
+
+    #[test]
+    fn ignore_malformed_datagrams() {
+        let mut reassembler = Reassembler::default();
+
+        assert_eq!(reassembler.add(&[0, 0, 0]), None);
+        assert_eq!(reassembler.add(&[0, 0, 0, 0]), None);
+        assert_eq!(reassembler.add(&[0, 0, 2, 2, 1]), None);
+        assert_eq!(reassembler.add(&[0, 0, 0, MAX_FRAGMENTS as u8 + 1, 1]), None);
+    }
+}
@@ -9,5 +9,216 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-pub mod connection;
+mod fragment;
 pub mod worker;
+
+use crate::{
+    constants::BYTES_CHANNEL_CAPACITY,
+    endpoint::{
+        origin::Origin,
+        outbox::{bytes_channel, BytesReceiver},
+        Endpoint, EndpointId as EpId,
+    },
+    errors::ConnectionResult,
+    events::{Event, EventPublisher as Notifier},
+    utils::shaping::{Limiter, Shaping},
+};
+
+use fragment::MAX_MESSAGE_SIZE;
+
+use async_std::{net::SocketAddr, task::spawn};
+use futures::{channel::mpsc, prelude::*};
+use log::*;
+
+/// Instructions for the UDP worker, which owns the socket shared by all UDP endpoints.
+#[derive(Debug)]
+pub(crate) enum Instruction {
+    /// Starts accepting datagrams from that address, which are attributed to that endpoint.
+    Register(SocketAddr, EpId),
+
+    /// Stops accepting datagrams from that address.
+    Unregister(SocketAddr),
+
+    /// Sends a datagram to that address.
+    Send(SocketAddr, Vec<u8>),
+}
+
+pub(crate) type InstructionSender = mpsc::Sender<Instruction>;
+pub(crate) type InstructionReceiver = mpsc::Receiver<Instruction>;
+
+pub(crate) fn instruction_channel() -> (InstructionSender, InstructionReceiver) {
+    mpsc::channel(BYTES_CHANNEL_CAPACITY)
+}
+
+/// "Connects" to a UDP endpoint, which only means starting to exchange datagrams with it.
+///
+/// NOTE: There is no inbound UDP connection, both sides have to add each other as `udp://` endpoint.
+///
+/// NOTE: Datagrams are neither encrypted nor authenticated, so UDP endpoints can only be added if connections aren't
+/// secure, and can't be pinned to a public key.
+///
+/// NOTE: Only the upload is shaped, since delaying datagrams from one endpoint would delay those of all the others.
+///
+/// NOTE: Messages are split into datagrams small enough not to be fragmented by IP, see `fragment`.
+pub(crate) async fn connect(
+    ep: &Endpoint,
+    udp: InstructionSender,
+    shaping: &Shaping,
+    mut notifier: Notifier,
+) -> ConnectionResult<()> {
+    debug!("Spawning UDP connection writer...");
+
+    let (sender, receiver) = bytes_channel(shaping.outbox_capacity);
+
+    spawn(writer(
+        ep.id,
+        *ep.address,
+        receiver,
+        shaping.upload.limiter(),
+        udp,
+        notifier.clone(),
+    ));
+
+    info!("Exchanging datagrams with {} ({}).", ep.address, Origin::Outbound);
+
+    Ok(notifier
+        .send(Event::NewConnection {
+            ep: ep.clone(),
+            origin: Origin::Outbound,
+            public_key: None,
+            sender,
+        })
+        .await?)
+}
+
+async fn writer(
+    epid: EpId,
+    addr: SocketAddr,
+    mut bytes_rx: BytesReceiver,
+    mut upload: Limiter,
+    mut udp: InstructionSender,
+    mut notifier: Notifier,
+) {
+    debug!("Starting UDP connection writer task for {}...", epid);
+
+    let mut total_bytes = 0;
+    let mut id = 0u16;
+
+    if udp.send(Instruction::Register(addr, epid)).await.is_err() {
+        warn!("UDP worker unavailable, dropping connection to {}.", epid);
+        return;
+    }
+
+    // NOTE: If the bytes sender gets dropped (which happens when the endpoint disconnects), we break out of the loop.
+    'messages: while let Some(bytes) = bytes_rx.next().await {
+        if bytes.len() > MAX_MESSAGE_SIZE {
+            warn!(
+                "Dropping message of {} bytes to {}, exceeding the maximum message size of {} bytes.",
+                bytes.len(),
+                epid,
+                MAX_MESSAGE_SIZE
+            );
+            continue;
+        }
+
+        upload.throttle(bytes.len()).await;
+
+        let num_bytes = bytes.len();
+
+        for datagram in fragment::split(id, &bytes) {
+            if udp.send(Instruction::Send(addr, datagram)).await.is_err() {
+                break 'messages;
+            }
+        }
+
+        id = id.wrapping_add(1);
+
+        total_bytes += num_bytes as u64;
+
+        let event = Event::MessageSent {
+            epid,
+            num_bytes,
+            total_bytes,
+        };
+        if notifier.send(event).await.is_err() {
+            warn!("Failed to send 'MessageSent' notification.");
+        }
+    }
+
+    if udp.send(Instruction::Unregister(addr)).await.is_err() {
+        trace!("UDP worker shut down before writer task.");
+    }
+
+    debug!("UDP connection writer for {} stopped.", epid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        address::{url::Protocol, Address},
+        config::NetworkConfig,
+        constants::MAX_DATAGRAM_SIZE,
+        events::event_channel,
+    };
+
+    use fragment::Reassembler;
+
+    use async_std::{sync::Arc, task::block_on};
+
+    #[test]
+    fn oversized_messages_are_dropped() {
+        let addr: SocketAddr = "127.0.0.1:16000".parse().unwrap();
+        let ep = Endpoint::new(Address::from(addr), Protocol::Udp);
+        let (udp, mut instructions) = instruction_channel();
+        let (notifier, mut events) = event_channel();
+
+        block_on(async {
+            connect(&ep, udp, &Shaping::new(&NetworkConfig::build().finish()), notifier)
+                .await
+                .unwrap();
+
+            let mut sender = match events.next().await {
+                Some(Event::NewConnection { sender, origin, .. }) => {
+                    assert!(matches!(origin, Origin::Outbound));
+                    sender
+                }
+                _ => panic!("Expected a new connection."),
+            };
+
+            sender.send(Arc::new(vec![0u8; MAX_MESSAGE_SIZE + 1])).await.unwrap();
+            sender.send(Arc::new(vec![1u8; MAX_MESSAGE_SIZE])).await.unwrap();
+            drop(sender);
+
+            match instructions.next().await {
+                Some(Instruction::Register(registered, registered_epid)) => {
+                    assert_eq!(registered, addr);
+                    assert_eq!(registered_epid, ep.id);
+                }
+                i => panic!("Unexpected instruction {:?}.", i),
+            }
+
+            let mut reassembler = Reassembler::default();
+            let mut message = None;
+
+            while message.is_none() {
+                match instructions.next().await {
+                    Some(Instruction::Send(to, datagram)) => {
+                        assert_eq!(to, addr);
+                        assert!(datagram.len() <= MAX_DATAGRAM_SIZE);
+                        message = reassembler.add(&datagram);
+                    }
+                    i => panic!("Unexpected instruction {:?}.", i),
+                }
+            }
+
+            assert_eq!(message.unwrap(), vec![1u8; MAX_MESSAGE_SIZE]);
+
+            match instructions.next().await {
+                Some(Instruction::Unregister(unregistered)) => assert_eq!(unregistered, addr),
+                i => panic!("Unexpected instruction {:?}.", i),
+            }
+        });
+    }
+}
@@ -9,28 +9,185 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::address::Address;
-use crate::events::EventPublisher as EventPub;
-use crate::shutdown::ShutdownListener;
+use crate::{
+    address::Address,
+    constants::MAX_DATAGRAM_SIZE,
+    endpoint::EndpointId as EpId,
+    events::{Event, EventPublisher as Notifier},
+};
 
-pub struct UdpWorker {
+use super::{fragment::Reassembler, Instruction, InstructionReceiver as Instructions};
+
+use bee_common::{shutdown::ShutdownListener as Shutdown, worker::Error as WorkerError};
+
+use async_std::net::{SocketAddr, UdpSocket};
+use futures::{prelude::*, select};
+use log::*;
+
+use std::collections::HashMap;
+
+// NOTE: Larger than the maximum datagram size, so that oversized datagrams can be detected instead of truncated.
+const RECV_BUFFER_SIZE: usize = 2 * MAX_DATAGRAM_SIZE;
+
+pub(crate) struct UdpWorker {
     binding_addr: Address,
-    notifier: EventPub,
-    publisher: EventPub,
-    shutdown: ShutdownListener,
+    instructions: Instructions,
+    notifier: Notifier,
+    shutdown: Shutdown,
 }
 
 impl UdpWorker {
-    pub fn new(binding_addr: Address, notifier: EventPub, publisher: EventPub, shutdown: ShutdownListener) -> Self {
+    pub fn new(binding_addr: Address, instructions: Instructions, notifier: Notifier, shutdown: Shutdown) -> Self {
         Self {
             binding_addr,
+            instructions,
             notifier,
-            publisher,
             shutdown,
         }
     }
 
-    pub async fn run(self) {
-        //
+    pub async fn run(mut self) -> Result<(), WorkerError> {
+        debug!("Starting UDP worker...");
+
+        let socket = UdpSocket::bind(*self.binding_addr).await?;
+
+        info!("Exchanging datagrams on {}.", socket.local_addr()?);
+
+        // Endpoint and number of connection writers per registered address.
+        let mut registered: HashMap<SocketAddr, (EpId, usize)> = HashMap::new();
+        // Number of bytes received per registered address.
+        let mut received: HashMap<SocketAddr, u64> = HashMap::new();
+        let mut reassemblers: HashMap<SocketAddr, Reassembler> = HashMap::new();
+        let mut buffer = vec![0u8; RECV_BUFFER_SIZE];
+
+        let instructions = &mut self.instructions;
+        let shutdown = &mut self.shutdown;
+
+        loop {
+            select! {
+                received = socket.recv_from(&mut buffer).fuse() => {
+                    match received {
+                        Ok((num_read, from)) => {
+                            // NOTE: Datagrams are attributed to the endpoint registered for their source address, whose
+                            // id is kept when its host name resolves to a new address.
+                            let epid = match registered.get(&from) {
+                                Some((epid, _)) => *epid,
+                                None => {
+                                    trace!("Dropping datagram from unknown address '{}'.", from);
+                                    continue;
+                                }
+                            };
+                            if num_read > MAX_DATAGRAM_SIZE {
+                                warn!("Dropping datagram of {} bytes from '{}'.", num_read, from);
+                                continue;
+                            }
+
+                            let total_bytes = received.entry(from).or_insert(0);
+                            *total_bytes += num_read as u64;
+                            let total_bytes = *total_bytes;
+
+                            let bytes = match reassemblers.entry(from).or_default().add(&buffer[0..num_read]) {
+                                Some(bytes) => bytes,
+                                None => continue,
+                            };
+
+                            if self.notifier.send(Event::MessageReceived { epid, bytes, total_bytes }).await.is_err() {
+                                warn!("Failed to send 'MessageReceived' notification.");
+                            }
+                        }
+                        Err(e) => {
+                            error!("Receiving datagram failed: {:?}.", e);
+                        }
+                    }
+                },
+                instruction = instructions.next() => {
+                    match instruction {
+                        Some(Instruction::Register(addr, epid)) => {
+                            let (registered_epid, count) = registered.entry(addr).or_insert((epid, 0));
+                            *registered_epid = epid;
+                            *count += 1;
+                        }
+                        Some(Instruction::Unregister(addr)) => {
+                            if let Some((_, count)) = registered.get_mut(&addr) {
+                                *count -= 1;
+                                if *count == 0 {
+                                    registered.remove(&addr);
+                                    received.remove(&addr);
+                                    reassemblers.remove(&addr);
+                                }
+                            }
+                        }
+                        Some(Instruction::Send(addr, bytes)) => {
+                            if let Err(e) = socket.send_to(&bytes, addr).await {
+                                error!("Sending datagram to '{}' failed: {:?}.", addr, e);
+                            }
+                        }
+                        None => break,
+                    }
+                },
+                _ = shutdown.fuse() => {
+                    break;
+                }
+            }
+        }
+
+        debug!("Stopped UDP worker.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        endpoint::EndpointId,
+        events::event_channel,
+        udp::{fragment::split, instruction_channel},
+    };
+
+    use async_std::task::{block_on, sleep, spawn};
+    use futures::channel::oneshot;
+
+    use std::time::Duration;
+
+    #[test]
+    fn exchange_datagrams_on_loopback() {
+        let addr_a: SocketAddr = "127.0.0.1:16101".parse().unwrap();
+        let addr_b: SocketAddr = "127.0.0.1:16102".parse().unwrap();
+
+        let (mut udp_a, instructions_a) = instruction_channel();
+        let (mut udp_b, instructions_b) = instruction_channel();
+        let (notifier_a, _events_a) = event_channel();
+        let (notifier_b, mut events_b) = event_channel();
+        let (_shutdown_a, shutdown_a) = oneshot::channel();
+        let (_shutdown_b, shutdown_b) = oneshot::channel();
+
+        spawn(UdpWorker::new(addr_a.into(), instructions_a, notifier_a, shutdown_a).run());
+        spawn(UdpWorker::new(addr_b.into(), instructions_b, notifier_b, shutdown_b).run());
+
+        block_on(async {
+            // The endpoint on `addr_a` keeps the id of its original address, like after a host name re-resolution.
+            let epid_a = EndpointId::from(Address::from("127.0.0.1:16100".parse::<SocketAddr>().unwrap()));
+            let epid_b = EndpointId::from(Address::from(addr_b));
+
+            udp_a.send(Instruction::Register(addr_b, epid_b)).await.unwrap();
+            udp_b.send(Instruction::Register(addr_a, epid_a)).await.unwrap();
+
+            // Both workers have to bind and register before anything can be received.
+            sleep(Duration::from_millis(100)).await;
+
+            for datagram in split(0, &[7u8; 2000]) {
+                udp_a.send(Instruction::Send(addr_b, datagram)).await.unwrap();
+            }
+
+            match events_b.next().await {
+                Some(Event::MessageReceived { epid, bytes, .. }) => {
+                    assert_eq!(epid, epid_a);
+                    assert_eq!(bytes, vec![7u8; 2000]);
+                }
+                _ => panic!("Expected a received message."),
+            }
+        });
     }
 }
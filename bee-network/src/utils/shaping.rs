@@ -0,0 +1,176 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Traffic shaping with token buckets, for a single connection as well as for all of them.
+
+use crate::config::NetworkConfig;
+
+use async_std::{sync::Arc, task::sleep};
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A token bucket that refills at `rate` bytes per second and holds at most one second worth of bytes.
+///
+/// NOTE: The bucket can go into debt, so that a large message is let through, but delays the following ones.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Takes `num_bytes` out of the bucket, and returns how long to wait until they are covered.
+    fn take(&mut self, num_bytes: usize) -> Duration {
+        let now = Instant::now();
+
+        self.tokens = (self.tokens + now.duration_since(self.updated).as_secs_f64() * self.rate).min(self.rate);
+        self.updated = now;
+        self.tokens -= num_bytes as f64;
+
+        if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+}
+
+/// The rate limits in one direction, a rate of 0 meaning unlimited.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimit {
+    global: Option<Arc<Mutex<TokenBucket>>>,
+    per_peer: u64,
+}
+
+impl RateLimit {
+    pub fn new(global: u64, per_peer: u64) -> Self {
+        Self {
+            global: if global > 0 {
+                Some(Arc::new(Mutex::new(TokenBucket::new(global))))
+            } else {
+                None
+            },
+            per_peer,
+        }
+    }
+
+    /// Returns a limiter for a new connection, which shares the global limit with all other connections.
+    pub fn limiter(&self) -> Limiter {
+        Limiter {
+            global: self.global.clone(),
+            peer: if self.per_peer > 0 {
+                Some(TokenBucket::new(self.per_peer))
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Limits the rate bytes are transferred at over a single connection.
+#[derive(Debug)]
+pub(crate) struct Limiter {
+    global: Option<Arc<Mutex<TokenBucket>>>,
+    peer: Option<TokenBucket>,
+}
+
+impl Limiter {
+    /// Waits until transferring `num_bytes` respects the limits.
+    pub async fn throttle(&mut self, num_bytes: usize) {
+        let peer_delay = self
+            .peer
+            .as_mut()
+            .map_or(Duration::from_secs(0), |peer| peer.take(num_bytes));
+        let global_delay = self.global.as_ref().map_or(Duration::from_secs(0), |global| {
+            global.lock().expect("poisoned token bucket").take(num_bytes)
+        });
+
+        let delay = peer_delay.max(global_delay);
+
+        if delay > Duration::from_secs(0) {
+            sleep(delay).await;
+        }
+    }
+}
+
+/// Everything needed to shape the traffic of new connections.
+#[derive(Clone, Debug)]
+pub(crate) struct Shaping {
+    pub outbox_capacity: usize,
+    pub upload: RateLimit,
+    pub download: RateLimit,
+}
+
+impl Shaping {
+    pub fn new(config: &NetworkConfig) -> Self {
+        Self {
+            outbox_capacity: config.outbox_capacity,
+            upload: RateLimit::new(config.max_upload_rate, config.max_peer_upload_rate),
+            download: RateLimit::new(config.max_download_rate, config.max_peer_download_rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_lets_burst_through() {
+        let mut bucket = TokenBucket::new(1000);
+
+        assert_eq!(bucket.take(600), Duration::from_secs(0));
+        assert_eq!(bucket.take(400), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn bucket_delays_when_in_debt() {
+        let mut bucket = TokenBucket::new(1000);
+
+        assert_eq!(bucket.take(1000), Duration::from_secs(0));
+
+        let delay = bucket.take(500);
+        assert!(delay <= Duration::from_millis(500));
+        assert!(delay > Duration::from_millis(400));
+    }
+
+    #[test]
+    fn unlimited_rate_has_no_buckets() {
+        let limiter = RateLimit::new(0, 0).limiter();
+
+        assert!(limiter.global.is_none());
+        assert!(limiter.peer.is_none());
+    }
+
+    #[test]
+    fn limiters_share_global_bucket() {
+        let rate_limit = RateLimit::new(1000, 0);
+        let mut first = rate_limit.limiter();
+        let mut second = rate_limit.limiter();
+
+        async_std::task::block_on(first.throttle(1000));
+
+        let delay = second.global.as_ref().unwrap().lock().unwrap().take(500);
+        assert!(delay > Duration::from_millis(400));
+    }
+}
@@ -0,0 +1,301 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_common::shutdown::Shutdown;
+use bee_network::{Command, EndpointId, Event, EventSubscriber, MemoryNetwork, Network, NetworkConfig, Url};
+
+use async_std::task::block_on;
+use futures::StreamExt;
+use serial_test::serial;
+
+use std::time::{Duration, Instant};
+
+fn config(port: u16) -> NetworkConfig {
+    NetworkConfig::build()
+        .binding_addr("127.0.0.1")
+        .binding_port(port)
+        .finish()
+}
+
+async fn connect(network: &mut Network, events: &mut EventSubscriber, to: &NetworkConfig) -> EndpointId {
+    let url = Url::from_url_str(&format!("tcp://{}", to.socket_addr())).await.unwrap();
+    let epid = EndpointId::from(&url);
+
+    network
+        .send(Command::AddEndpoint {
+            url,
+            public_key: None,
+            responder: None,
+        })
+        .await
+        .unwrap();
+    network.send(Command::Connect { epid, responder: None }).await.unwrap();
+
+    while let Some(event) = events.next().await {
+        if let Event::EndpointConnected { epid: connected, .. } = event {
+            if connected == epid {
+                break;
+            }
+        }
+    }
+
+    epid
+}
+
+// NOTE: Waits until the link has decided whether the message arrives.
+async fn send(network: &mut Network, events: &mut EventSubscriber, epid: EndpointId, bytes: Vec<u8>) {
+    network
+        .send(Command::SendMessage {
+            epid,
+            bytes,
+            responder: None,
+        })
+        .await
+        .unwrap();
+
+    while let Some(event) = events.next().await {
+        if let Event::MessageSent { .. } = event {
+            break;
+        }
+    }
+}
+
+// NOTE: Waits until the links to all `peers` have decided whether the message arrives.
+async fn broadcast(network: &mut Network, events: &mut EventSubscriber, peers: usize, bytes: Vec<u8>) {
+    network
+        .send(Command::BroadcastMessage { bytes, responder: None })
+        .await
+        .unwrap();
+
+    let mut sent = 0;
+    while let Some(event) = events.next().await {
+        if let Event::MessageSent { .. } = event {
+            sent += 1;
+            if sent == peers {
+                break;
+            }
+        }
+    }
+}
+
+async fn next_message(events: &mut EventSubscriber) -> (EndpointId, Vec<u8>) {
+    while let Some(event) = events.next().await {
+        if let Event::MessageReceived { epid, bytes, .. } = event {
+            return (epid, bytes);
+        }
+    }
+    panic!("Event channel closed.");
+}
+
+#[test]
+#[serial]
+fn exchange_messages() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b) = (config(17001), config(17002));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+
+    block_on(async {
+        let epid_b = connect(&mut network_a, &mut events_a, &config_b).await;
+
+        send(&mut network_a, &mut events_a, epid_b, vec![1, 2, 3]).await;
+
+        let (epid, bytes) = next_message(&mut events_b).await;
+        assert_eq!(epid, EndpointId::from(config_a.socket_addr()));
+        assert_eq!(bytes, vec![1, 2, 3]);
+
+        shutdown.execute().await.unwrap();
+    });
+}
+
+#[test]
+#[serial]
+fn messages_are_delayed_by_latency() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b) = (config(17003), config(17004));
+
+    memory.set_latency(Duration::from_millis(100));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+
+    block_on(async {
+        let epid_b = connect(&mut network_a, &mut events_a, &config_b).await;
+
+        let sent = Instant::now();
+        send(&mut network_a, &mut events_a, epid_b, vec![1]).await;
+        next_message(&mut events_b).await;
+        assert!(sent.elapsed() >= Duration::from_millis(100));
+
+        shutdown.execute().await.unwrap();
+    });
+}
+
+#[test]
+#[serial]
+fn full_packet_loss_drops_everything() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b) = (config(17007), config(17008));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+
+    block_on(async {
+        let epid_b = connect(&mut network_a, &mut events_a, &config_b).await;
+
+        memory.set_packet_loss(1.0);
+        send(&mut network_a, &mut events_a, epid_b, vec![1]).await;
+
+        memory.set_packet_loss(0.0);
+        send(&mut network_a, &mut events_a, epid_b, vec![2]).await;
+
+        assert_eq!(next_message(&mut events_b).await.1, vec![2]);
+
+        shutdown.execute().await.unwrap();
+    });
+}
+
+// Broadcasts messages over two lossy links and returns those that arrived at either end, in order.
+fn lossy_broadcast(seed: u64) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let memory = MemoryNetwork::new(seed);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b, config_c) = (config(17011), config(17012), config(17013));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+    let (_network_c, mut events_c) = memory.init(config_c, &mut shutdown);
+
+    block_on(async {
+        connect(&mut network_a, &mut events_a, &config_b).await;
+        connect(&mut network_a, &mut events_a, &config_c).await;
+
+        memory.set_packet_loss(0.5);
+        for i in 0..32 {
+            broadcast(&mut network_a, &mut events_a, 2, vec![i]).await;
+        }
+
+        // NOTE: The last message always arrives and marks the end of the received ones.
+        memory.set_packet_loss(0.0);
+        broadcast(&mut network_a, &mut events_a, 2, vec![u8::MAX]).await;
+
+        let mut received = Vec::new();
+        for events in &mut [&mut events_b, &mut events_c] {
+            let mut messages = Vec::new();
+            loop {
+                let (_, bytes) = next_message(events).await;
+                if bytes == vec![u8::MAX] {
+                    break;
+                }
+                messages.push(bytes);
+            }
+            received.push(messages);
+        }
+
+        shutdown.execute().await.unwrap();
+
+        (received.remove(0), received.remove(0))
+    })
+}
+
+#[test]
+#[serial]
+fn packet_loss_is_reproducible() {
+    let (received_b, received_c) = lossy_broadcast(42);
+
+    assert!(received_b.len() < 32);
+    assert_ne!(received_b, received_c);
+    assert_eq!(lossy_broadcast(42), (received_b, received_c));
+}
+
+#[test]
+#[serial]
+fn broadcast_reaches_all_connected_nodes() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b, config_c) = (config(17101), config(17102), config(17103));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+    let (_network_c, mut events_c) = memory.init(config_c, &mut shutdown);
+
+    block_on(async {
+        connect(&mut network_a, &mut events_a, &config_b).await;
+        connect(&mut network_a, &mut events_a, &config_c).await;
+
+        broadcast(&mut network_a, &mut events_a, 2, vec![1, 2, 3]).await;
+
+        for events in &mut [&mut events_b, &mut events_c] {
+            let (epid, bytes) = next_message(events).await;
+            assert_eq!(epid, EndpointId::from(config_a.socket_addr()));
+            assert_eq!(bytes, vec![1, 2, 3]);
+        }
+
+        shutdown.execute().await.unwrap();
+    });
+}
+
+#[test]
+#[serial]
+fn partitions_drop_messages_until_healed() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b) = (config(17005), config(17006));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+
+    block_on(async {
+        let epid_b = connect(&mut network_a, &mut events_a, &config_b).await;
+
+        memory.partition(config_a.socket_addr(), config_b.socket_addr());
+        send(&mut network_a, &mut events_a, epid_b, vec![1]).await;
+
+        memory.heal(config_a.socket_addr(), config_b.socket_addr());
+        send(&mut network_a, &mut events_a, epid_b, vec![2]).await;
+
+        assert_eq!(next_message(&mut events_b).await.1, vec![2]);
+
+        shutdown.execute().await.unwrap();
+    });
+}
+
+#[test]
+#[serial]
+fn partition_only_separates_the_given_nodes() {
+    let memory = MemoryNetwork::new(0);
+    let mut shutdown = Shutdown::new();
+    let (config_a, config_b, config_c) = (config(17104), config(17105), config(17106));
+
+    let (mut network_a, mut events_a) = memory.init(config_a, &mut shutdown);
+    let (_network_b, mut events_b) = memory.init(config_b, &mut shutdown);
+    let (_network_c, mut events_c) = memory.init(config_c, &mut shutdown);
+
+    block_on(async {
+        connect(&mut network_a, &mut events_a, &config_b).await;
+        connect(&mut network_a, &mut events_a, &config_c).await;
+
+        memory.partition(config_a.socket_addr(), config_c.socket_addr());
+        broadcast(&mut network_a, &mut events_a, 2, vec![1]).await;
+
+        memory.heal(config_a.socket_addr(), config_c.socket_addr());
+        broadcast(&mut network_a, &mut events_a, 2, vec![2]).await;
+
+        assert_eq!(next_message(&mut events_b).await.1, vec![1]);
+        assert_eq!(next_message(&mut events_b).await.1, vec![2]);
+        assert_eq!(next_message(&mut events_c).await.1, vec![2]);
+
+        shutdown.execute().await.unwrap();
+    });
+}
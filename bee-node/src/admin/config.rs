@@ -0,0 +1,75 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use serde::Deserialize;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const DEFAULT_BINDING_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+const DEFAULT_BINDING_PORT: u16 = 14266;
+
+/// Admin API configuration builder.
+#[derive(Default, Deserialize)]
+pub struct AdminConfigBuilder {
+    binding_addr: Option<IpAddr>,
+    binding_port: Option<u16>,
+}
+
+impl AdminConfigBuilder {
+    /// Creates a new config builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the binding address of the admin API server.
+    pub fn binding_addr(mut self, addr: &str) -> Self {
+        match addr.parse() {
+            Ok(addr) => {
+                self.binding_addr.replace(addr);
+            }
+            Err(e) => panic!("Error parsing address: {:?}", e),
+        }
+        self
+    }
+
+    /// Sets the binding port of the admin API server.
+    pub fn binding_port(mut self, port: u16) -> Self {
+        self.binding_port.replace(port);
+        self
+    }
+
+    /// Builds the admin API config.
+    pub fn finish(self) -> AdminConfig {
+        AdminConfig {
+            binding_addr: self.binding_addr.unwrap_or(DEFAULT_BINDING_ADDR),
+            binding_port: self.binding_port.unwrap_or(DEFAULT_BINDING_PORT),
+        }
+    }
+}
+
+/// Admin API configuration.
+#[derive(Clone)]
+pub struct AdminConfig {
+    pub(crate) binding_addr: IpAddr,
+    pub(crate) binding_port: u16,
+}
+
+impl AdminConfig {
+    /// Returns a builder for this config.
+    pub fn build() -> AdminConfigBuilder {
+        AdminConfigBuilder::new()
+    }
+
+    /// Returns the listening address of the admin API server.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.binding_addr, self.binding_port)
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+mod config;
+mod peers;
+
+pub use config::{AdminConfig, AdminConfigBuilder};
+
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+use bee_network::Network;
+
+use async_std::task::spawn;
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+};
+use log::{error, info};
+use tide::Server;
+
+fn server(network: Network) -> Server<Network> {
+    let mut server = tide::with_state(network);
+
+    server.at("/peers").get(peers::list);
+    server.at("/peers/add").post(peers::add);
+    server.at("/peers/remove").post(peers::remove);
+    server.at("/peers/connect").post(peers::connect);
+    server.at("/peers/disconnect").post(peers::disconnect);
+
+    server
+}
+
+async fn run(server: Server<Network>, address: String, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    if let Either::Left((Err(e), _)) = select(Box::pin(server.listen(address.clone())), shutdown).await {
+        error!("Listening on {} failed: {}.", address, e);
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) fn init(config: &AdminConfig, network: Network, shutdown: &mut Shutdown) {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+    info!("Serving admin API on {}.", config.socket_addr());
+
+    shutdown.add_worker_shutdown(
+        shutdown_tx,
+        spawn(run(server(network), config.socket_addr().to_string(), shutdown_rx)),
+    );
+}
@@ -0,0 +1,439 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Peer administration, commands being forwarded to the network layer and acknowledged by it.
+
+use bee_network::{
+    response_channel, Address, Command, ConnectionState, Endpoint, EndpointId, Network, Origin, PublicKey, Requester,
+    Responder, Url,
+};
+use bee_protocol::{HandshakeState, PeerInfo, PeerMetrics, Protocol};
+
+use async_std::future::timeout;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tide::{Body, Request, Response, StatusCode};
+
+use std::{collections::HashMap, time::Duration};
+
+// Connection attempts are acknowledged once one succeeds, which may take a whole backoff or ban, so waiting for the
+// acknowledgement is bounded.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Invalid request: {0}.")]
+    InvalidRequest(String),
+
+    #[error("Invalid URL: {0}.")]
+    InvalidUrl(String),
+
+    #[error("Invalid address: {0}.")]
+    InvalidAddress(String),
+
+    #[error("Network layer unavailable.")]
+    NetworkUnavailable,
+
+    #[error("Rejected by the network layer.")]
+    Rejected,
+
+    #[error("Not acknowledged by the network layer in time, the command may still complete.")]
+    Unacknowledged,
+}
+
+impl Error {
+    fn status(&self) -> StatusCode {
+        match self {
+            Error::InvalidRequest(_) | Error::InvalidUrl(_) | Error::InvalidAddress(_) => StatusCode::BadRequest,
+            Error::NetworkUnavailable => StatusCode::ServiceUnavailable,
+            Error::Rejected => StatusCode::Conflict,
+            Error::Unacknowledged => StatusCode::GatewayTimeout,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UrlRequest {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct AddressRequest {
+    address: String,
+}
+
+fn origin(origin: &Origin) -> &'static str {
+    match origin {
+        Origin::Inbound => "inbound",
+        Origin::Outbound => "outbound",
+        Origin::Unbound => "unbound",
+    }
+}
+
+fn connection_state(state: &ConnectionState) -> Value {
+    match state {
+        ConnectionState::Disconnected => json!({ "state": "disconnected" }),
+        ConnectionState::Connecting => json!({ "state": "connecting" }),
+        ConnectionState::Connected => json!({ "state": "connected" }),
+        ConnectionState::Backoff { attempts, delay } => json!({
+            "state": "backoff",
+            "failedAttempts": attempts,
+            "retryDelay": delay.as_secs(),
+        }),
+        ConnectionState::Parked => json!({ "state": "parked" }),
+    }
+}
+
+fn metrics(metrics: &PeerMetrics) -> Value {
+    json!({
+        "invalidTransactions": metrics.invalid_transactions(),
+        "staleTransactions": metrics.stale_transactions(),
+        "newTransactions": metrics.new_transactions(),
+        "knownTransactions": metrics.known_transactions(),
+        "invalidMessages": metrics.invalid_messages(),
+        "milestoneRequestsReceived": metrics.milestone_requests_received(),
+        "transactionsReceived": metrics.transactions_received(),
+        "transactionRequestsReceived": metrics.transaction_requests_received(),
+        "heartbeatsReceived": metrics.heartbeats_received(),
+        "milestoneRequestsSent": metrics.milestone_requests_sent(),
+        "transactionsSent": metrics.transactions_sent(),
+        "transactionRequestsSent": metrics.transaction_requests_sent(),
+        "heartbeatsSent": metrics.heartbeats_sent(),
+    })
+}
+
+fn peer(peer: &PeerInfo, state: Option<&ConnectionState>) -> Value {
+    let heartbeat = match (
+        peer.last_solid_milestone_index(),
+        peer.snapshot_milestone_index(),
+        peer.last_milestone_index(),
+    ) {
+        (Some(last_solid), Some(snapshot), Some(last)) => json!({
+            "lastSolidMilestoneIndex": *last_solid,
+            "snapshotMilestoneIndex": *snapshot,
+            "lastMilestoneIndex": *last,
+            "connectedPeers": peer.connected_peers(),
+            "syncedPeers": peer.synced_peers(),
+        }),
+        _ => Value::Null,
+    };
+
+    json!({
+        "epid": peer.epid().to_string(),
+        "address": peer.address().to_string(),
+        "publicKey": peer.public_key().map(ToString::to_string),
+        "origin": peer.origin().map(origin),
+        "connectionState": state.map(connection_state),
+        "handshakeState": match peer.handshake_state() {
+            HandshakeState::Pending => "pending",
+            HandshakeState::Completed => "completed",
+        },
+        "heartbeat": heartbeat,
+        "metrics": peer.metrics().map(metrics).unwrap_or(Value::Null),
+    })
+}
+
+fn respond(result: Result<Value, Error>) -> tide::Result {
+    let (status, body) = match result {
+        Ok(body) => (StatusCode::Ok, body),
+        Err(e) => (e.status(), json!({ "error": e.to_string() })),
+    };
+
+    let mut response = Response::new(status);
+    response.set_body(Body::from_json(&body)?);
+
+    Ok(response)
+}
+
+async fn send(mut network: Network, command: Command, requester: Requester<bool>) -> Result<(), Error> {
+    network.send(command).await.map_err(|_| Error::NetworkUnavailable)?;
+
+    match timeout(RESPONSE_TIMEOUT, requester).await {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err(Error::Rejected),
+        Ok(Err(_)) => Err(Error::NetworkUnavailable),
+        Err(_) => Err(Error::Unacknowledged),
+    }
+}
+
+async fn add_endpoint(request: &mut Request<Network>) -> Result<Value, Error> {
+    let body = request
+        .body_json::<UrlRequest>()
+        .await
+        .map_err(|e| Error::InvalidRequest(e.to_string()))?;
+    let public_key = PublicKey::from_url_str(&body.url).map_err(|_| Error::InvalidUrl(body.url.clone()))?;
+    let url = Url::from_url_str(&body.url)
+        .await
+        .map_err(|_| Error::InvalidUrl(body.url))?;
+    let epid = EndpointId::from(&url);
+    let (responder, requester) = response_channel();
+
+    send(
+        request.state().clone(),
+        Command::AddEndpoint {
+            url,
+            public_key,
+            responder: Some(responder),
+        },
+        requester,
+    )
+    .await?;
+
+    Ok(json!({ "epid": epid.to_string() }))
+}
+
+async fn endpoint_command(
+    request: &mut Request<Network>,
+    command: fn(EndpointId, Option<Responder<bool>>) -> Command,
+) -> Result<Value, Error> {
+    let body = request
+        .body_json::<AddressRequest>()
+        .await
+        .map_err(|e| Error::InvalidRequest(e.to_string()))?;
+    let epid = Address::from_addr_str(&body.address)
+        .await
+        .map(EndpointId::from)
+        .map_err(|_| Error::InvalidAddress(body.address))?;
+    let (responder, requester) = response_channel();
+
+    send(request.state().clone(), command(epid, Some(responder)), requester).await?;
+
+    Ok(json!({ "epid": epid.to_string() }))
+}
+
+// Contacts of the network layer that are not peers of the protocol, i.e. neither handshaking nor handshaked, are
+// listed after the peers.
+fn peers(peers: &[PeerInfo], contacts: Vec<(Endpoint, ConnectionState)>) -> Value {
+    let mut states = contacts
+        .into_iter()
+        .map(|(endpoint, state)| (endpoint.id, (endpoint, state)))
+        .collect::<HashMap<_, _>>();
+    let mut list = peers
+        .iter()
+        .map(|info| peer(info, states.remove(&info.epid()).as_ref().map(|(_, state)| state)))
+        .collect::<Vec<_>>();
+
+    list.extend(states.values().map(|(endpoint, state)| {
+        json!({
+            "epid": endpoint.id.to_string(),
+            "address": endpoint.address.to_string(),
+            "publicKey": Value::Null,
+            "origin": Value::Null,
+            "connectionState": connection_state(state),
+            "handshakeState": Value::Null,
+            "heartbeat": Value::Null,
+            "metrics": Value::Null,
+        })
+    }));
+
+    Value::Array(list)
+}
+
+async fn list_endpoints(mut network: Network) -> Result<Vec<(Endpoint, ConnectionState)>, Error> {
+    let (responder, requester) = response_channel();
+
+    network
+        .send(Command::ListEndpoints { responder })
+        .await
+        .map_err(|_| Error::NetworkUnavailable)?;
+
+    requester.await.map_err(|_| Error::NetworkUnavailable)
+}
+
+pub(crate) async fn list(request: Request<Network>) -> tide::Result {
+    respond(
+        list_endpoints(request.state().clone())
+            .await
+            .map(|contacts| peers(&Protocol::peers(), contacts)),
+    )
+}
+
+pub(crate) async fn add(mut request: Request<Network>) -> tide::Result {
+    respond(add_endpoint(&mut request).await)
+}
+
+pub(crate) async fn remove(mut request: Request<Network>) -> tide::Result {
+    respond(
+        endpoint_command(&mut request, |epid, responder| Command::RemoveEndpoint {
+            epid,
+            responder,
+        })
+        .await,
+    )
+}
+
+pub(crate) async fn connect(mut request: Request<Network>) -> tide::Result {
+    respond(endpoint_command(&mut request, |epid, responder| Command::Connect { epid, responder }).await)
+}
+
+pub(crate) async fn disconnect(mut request: Request<Network>) -> tide::Result {
+    respond(endpoint_command(&mut request, |epid, responder| Command::Disconnect { epid, responder }).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::admin::server;
+
+    use bee_network::NetworkConfig;
+
+    use async_std::task::{block_on, spawn};
+    use futures::{channel::mpsc, StreamExt};
+    use tide::http::{self, Method, Url as HttpUrl};
+
+    use std::time::Duration;
+
+    // Spawns a network layer stand-in acknowledging every command with `accept`, or never if `None`.
+    fn network(accept: Option<bool>) -> Network {
+        let (sender, mut receiver) = mpsc::channel(8);
+
+        spawn(async move {
+            while let Some(command) = receiver.next().await {
+                let responder = match command {
+                    Command::AddEndpoint { responder, .. }
+                    | Command::RemoveEndpoint { responder, .. }
+                    | Command::Connect { responder, .. }
+                    | Command::Disconnect { responder, .. } => responder,
+                    _ => None,
+                };
+
+                match (responder, accept) {
+                    (Some(responder), Some(accept)) => {
+                        let _ = responder.send(accept);
+                    }
+                    // Keeps the responder alive like a connection attempt that is retried.
+                    (Some(responder), None) => std::mem::forget(responder),
+                    _ => (),
+                }
+            }
+        });
+
+        Network::new(NetworkConfig::build().finish(), sender)
+    }
+
+    fn post(network: Network, path: &str, body: Value) -> (StatusCode, Value) {
+        let mut request = http::Request::new(
+            Method::Post,
+            HttpUrl::parse(&format!("http://localhost{}", path)).unwrap(),
+        );
+        request.set_body(body.to_string());
+
+        let mut response = block_on(server(network).respond(request)).unwrap();
+        let body = block_on(response.body_string()).unwrap();
+
+        (response.status(), serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn list_unconnected_contacts() {
+        let url = block_on(Url::from_url_str("tcp://127.0.0.1:15600")).unwrap();
+        let list = peers(
+            &[],
+            vec![(
+                Endpoint::from_url(url),
+                ConnectionState::Backoff {
+                    attempts: 2,
+                    delay: Duration::from_secs(10),
+                },
+            )],
+        );
+
+        assert_eq!(list.as_array().unwrap().len(), 1);
+        assert_eq!(list[0]["epid"], "127.0.0.1:15600");
+        assert_eq!(list[0]["address"], "127.0.0.1:15600");
+        assert_eq!(
+            list[0]["connectionState"],
+            json!({ "state": "backoff", "failedAttempts": 2, "retryDelay": 10 })
+        );
+        assert_eq!(list[0]["handshakeState"], Value::Null);
+    }
+
+    #[test]
+    fn add_peer() {
+        let (status, body) = post(
+            network(Some(true)),
+            "/peers/add",
+            json!({ "url": "tcp://127.0.0.1:15600" }),
+        );
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body["epid"], "127.0.0.1:15600");
+    }
+
+    #[test]
+    fn add_peer_invalid_url() {
+        let (status, body) = post(network(Some(true)), "/peers/add", json!({ "url": "127.0.0.1:15600" }));
+
+        assert_eq!(status, StatusCode::BadRequest);
+        assert_eq!(body["error"], "Invalid URL: 127.0.0.1:15600.");
+    }
+
+    #[test]
+    fn connect_peer() {
+        let (status, body) = post(
+            network(Some(true)),
+            "/peers/connect",
+            json!({ "address": "127.0.0.1:15600" }),
+        );
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body["epid"], "127.0.0.1:15600");
+    }
+
+    #[test]
+    fn connect_peer_unacknowledged() {
+        let (status, body) = post(network(None), "/peers/connect", json!({ "address": "127.0.0.1:15600" }));
+
+        assert_eq!(status, StatusCode::GatewayTimeout);
+        assert_eq!(
+            body["error"],
+            "Not acknowledged by the network layer in time, the command may still complete."
+        );
+    }
+
+    #[test]
+    fn remove_peer_rejected() {
+        let (status, body) = post(
+            network(Some(false)),
+            "/peers/remove",
+            json!({ "address": "127.0.0.1:15600" }),
+        );
+
+        assert_eq!(status, StatusCode::Conflict);
+        assert_eq!(body["error"], "Rejected by the network layer.");
+    }
+
+    #[test]
+    fn disconnect_peer_invalid_request() {
+        let (status, _) = post(
+            network(Some(true)),
+            "/peers/disconnect",
+            json!({ "url": "tcp://127.0.0.1:15600" }),
+        );
+
+        assert_eq!(status, StatusCode::BadRequest);
+    }
+
+    #[test]
+    fn network_unavailable() {
+        let (sender, _) = mpsc::channel(8);
+        let (status, body) = post(
+            Network::new(NetworkConfig::build().finish(), sender),
+            "/peers/connect",
+            json!({ "address": "127.0.0.1:15600" }),
+        );
+
+        assert_eq!(status, StatusCode::ServiceUnavailable);
+        assert_eq!(body["error"], "Network layer unavailable.");
+    }
+}
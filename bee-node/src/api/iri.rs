@@ -0,0 +1,463 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Commands of the IRI JSON API, all posted on `/` and dispatched by their `command` field.
+
+use bee_api::{
+    trytes::{address_from_trytes, hash_from_trytes, tag_from_trytes, transaction_from_trytes},
+    ApiService, Error as ApiError,
+};
+use bee_transaction::bundled::TRANSACTION_TRYT_LEN;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tide::{Body, Request, Response, StatusCode};
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Invalid JSON.")]
+    InvalidJson,
+
+    #[error("Invalid request: {0}.")]
+    InvalidRequest(#[from] serde_json::Error),
+
+    #[error("Unknown command: {0}.")]
+    UnknownCommand(String),
+
+    #[error("{0}")]
+    Api(#[from] ApiError),
+}
+
+#[derive(Deserialize)]
+struct CommandRequest {
+    command: String,
+}
+
+#[derive(Deserialize)]
+struct HashesRequest {
+    hashes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AddressesRequest {
+    addresses: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TransactionsRequest {
+    transactions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TrytesRequest {
+    trytes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TailsRequest {
+    tails: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FindTransactionsRequest {
+    bundles: Vec<String>,
+    addresses: Vec<String>,
+    tags: Vec<String>,
+    approvees: Vec<String>,
+}
+
+fn decode<T>(trytes: &[String], from_trytes: fn(&str) -> Result<T, ApiError>) -> Result<Vec<T>, Error> {
+    trytes
+        .iter()
+        .map(|trytes| from_trytes(trytes))
+        .collect::<Result<Vec<T>, ApiError>>()
+        .map_err(Error::from)
+}
+
+async fn get_node_info(service: &ApiService) -> Result<Value, Error> {
+    let info = service.node_info().await;
+
+    Ok(json!({
+        "appName": info.app_name,
+        "appVersion": info.app_version,
+        "latestMilestone": info.last_milestone,
+        "latestMilestoneIndex": info.last_milestone_index,
+        "latestSolidSubtangleMilestone": info.last_solid_milestone,
+        "latestSolidSubtangleMilestoneIndex": info.last_solid_milestone_index,
+        "milestoneStartIndex": info.snapshot_milestone_index,
+        "isSynced": info.is_synced,
+        "neighbors": info.neighbors,
+        "tips": info.tips,
+        "transactionsToRequest": info.transactions_to_request,
+        "transactions": info.transactions,
+        "coordinatorAddress": info.coordinator_address,
+        "time": info.time,
+    }))
+}
+
+async fn get_tips(service: &ApiService) -> Result<Value, Error> {
+    Ok(json!({ "hashes": service.tips().await.hashes }))
+}
+
+async fn get_trytes(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: HashesRequest = serde_json::from_value(request)?;
+    let mut trytes = Vec::with_capacity(request.hashes.len());
+
+    for hash in decode(&request.hashes, hash_from_trytes)? {
+        trytes.push(match service.transaction(&hash).await {
+            Some(transaction) => transaction.trytes,
+            None => "9".repeat(TRANSACTION_TRYT_LEN),
+        });
+    }
+
+    Ok(json!({ "trytes": trytes }))
+}
+
+async fn get_balances(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: AddressesRequest = serde_json::from_value(request)?;
+    let response = service
+        .balances(&decode(&request.addresses, address_from_trytes)?)
+        .await?;
+    let references = service
+        .milestone(response.milestone_index.into())
+        .await
+        .map_or_else(Vec::new, |milestone| vec![milestone.hash]);
+
+    Ok(json!({
+        "balances": response.balances.iter().map(u64::to_string).collect::<Vec<String>>(),
+        "references": references,
+        "milestoneIndex": response.milestone_index,
+    }))
+}
+
+async fn get_inclusion_states(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: TransactionsRequest = serde_json::from_value(request)?;
+    let response = service
+        .inclusion_states(&decode(&request.transactions, hash_from_trytes)?)
+        .await;
+
+    Ok(json!({ "states": response.states }))
+}
+
+async fn find_transactions(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: FindTransactionsRequest = serde_json::from_value(request)?;
+    let response = service
+        .find_transactions(
+            &decode(&request.bundles, hash_from_trytes)?,
+            &decode(&request.addresses, address_from_trytes)?,
+            &decode(&request.tags, tag_from_trytes)?,
+            &decode(&request.approvees, hash_from_trytes)?,
+        )
+        .await;
+
+    Ok(json!({ "hashes": response.hashes }))
+}
+
+async fn broadcast_transactions(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: TrytesRequest = serde_json::from_value(request)?;
+
+    for transaction in decode(&request.trytes, transaction_from_trytes)? {
+        service.submit_transaction(&transaction).await;
+    }
+
+    Ok(json!({}))
+}
+
+async fn store_transactions(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: TrytesRequest = serde_json::from_value(request)?;
+
+    for transaction in decode(&request.trytes, transaction_from_trytes)? {
+        service.store_transaction(&transaction).await;
+    }
+
+    Ok(json!({}))
+}
+
+async fn check_consistency(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: TailsRequest = serde_json::from_value(request)?;
+
+    Ok(serde_json::to_value(
+        service
+            .check_consistency(&decode(&request.tails, hash_from_trytes)?)
+            .await?,
+    )?)
+}
+
+async fn were_addresses_spent_from(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let request: AddressesRequest = serde_json::from_value(request)?;
+    let response = service
+        .were_addresses_spent_from(&decode(&request.addresses, address_from_trytes)?)
+        .await;
+
+    Ok(json!({ "states": response.states }))
+}
+
+async fn dispatch(service: &ApiService, request: Value) -> Result<Value, Error> {
+    let command: CommandRequest = serde_json::from_value(request.clone())?;
+
+    match command.command.as_str() {
+        "getNodeInfo" => get_node_info(service).await,
+        "getTips" => get_tips(service).await,
+        "getTrytes" => get_trytes(service, request).await,
+        "getBalances" => get_balances(service, request).await,
+        "getInclusionStates" => get_inclusion_states(service, request).await,
+        "findTransactions" => find_transactions(service, request).await,
+        "broadcastTransactions" => broadcast_transactions(service, request).await,
+        "storeTransactions" => store_transactions(service, request).await,
+        "checkConsistency" => check_consistency(service, request).await,
+        "wereAddressesSpentFrom" => were_addresses_spent_from(service, request).await,
+        _ => Err(Error::UnknownCommand(command.command)),
+    }
+}
+
+pub(crate) async fn handle(mut request: Request<ApiService>) -> tide::Result {
+    let result = match request.body_json::<Value>().await {
+        Ok(body) => dispatch(request.state(), body).await,
+        Err(_) => Err(Error::InvalidJson),
+    };
+
+    let (status, body) = match result {
+        Ok(body) => (StatusCode::Ok, body),
+        Err(e) => (StatusCode::BadRequest, json!({ "error": e.to_string() })),
+    };
+
+    let mut response = Response::new(status);
+    response.set_body(Body::from_json(&body)?);
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::api::{server, ApiConfig};
+
+    use bee_api::trytes::{hash_to_trytes, transaction_to_trytes};
+    use bee_common::shutdown::Shutdown;
+    use bee_common_ext::event::Bus;
+    use bee_crypto::ternary::Hash;
+    use bee_network::{Identity, NetworkConfig};
+    use bee_protocol::{
+        config::ProtocolConfig,
+        tangle::{self, tangle, TransactionMetadata},
+        Protocol,
+    };
+    use bee_test::transaction::{create_random_attached_tx, create_random_tx, hash_tx};
+
+    use async_std::task::block_on;
+    use futures::channel::mpsc;
+    use tide::http::{self, Method, Url};
+
+    use std::{
+        sync::{Arc, Once},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    static INIT: Once = Once::new();
+
+    // Initializes the network, the tangle and the protocol once for all the tests, since they are singletons.
+    fn init() {
+        INIT.call_once(|| {
+            // Dropping the shutdown would stop the workers.
+            let shutdown = Box::leak(Box::new(Shutdown::new()));
+            let identity = Identity::generate();
+            let public_key = *identity.public_key();
+            let (network, _) = bee_network::init(NetworkConfig::build().finish(), identity, shutdown);
+
+            tangle::init();
+
+            // Random transactions have no PoW.
+            block_on(Protocol::init(
+                ProtocolConfig::build().mwm(0).finish(),
+                network,
+                public_key,
+                0,
+                Arc::new(Bus::default()),
+                shutdown,
+            ));
+        });
+    }
+
+    fn post(config: &ApiConfig, origin: Option<&str>, body: &str) -> http::Response {
+        init();
+
+        let (ledger_worker, _) = mpsc::unbounded();
+        let server = server(
+            config,
+            ApiService::new("Bee".to_string(), "test".to_string(), ledger_worker),
+        );
+        let mut request = http::Request::new(Method::Post, Url::parse("http://localhost/").unwrap());
+
+        if let Some(origin) = origin {
+            request.insert_header("Origin", origin);
+        }
+        request.set_body(body);
+
+        block_on(server.respond(request)).unwrap()
+    }
+
+    fn command(body: Value) -> (StatusCode, Value) {
+        let mut response = post(&ApiConfig::build().finish(), None, &body.to_string());
+        let body = block_on(response.body_string()).unwrap();
+
+        (response.status(), serde_json::from_str(&body).unwrap())
+    }
+
+    fn insert(hash: Hash, transaction: bee_transaction::bundled::BundledTransaction) {
+        tangle().insert(transaction, hash, TransactionMetadata::new());
+    }
+
+    #[test]
+    fn invalid_json() {
+        let mut response = post(&ApiConfig::build().finish(), None, "{");
+        let body: Value = serde_json::from_str(&block_on(response.body_string()).unwrap()).unwrap();
+
+        assert_eq!(response.status(), StatusCode::BadRequest);
+        assert_eq!(body["error"], "Invalid JSON.");
+    }
+
+    #[test]
+    fn unknown_command() {
+        let (status, body) = command(json!({ "command": "attachToTangle" }));
+
+        assert_eq!(status, StatusCode::BadRequest);
+        assert_eq!(body["error"], "Unknown command: attachToTangle.");
+    }
+
+    #[test]
+    fn store_transactions() {
+        let (_, transaction) = create_random_attached_tx(Hash::zeros(), Hash::zeros());
+        let hash = hash_tx(&transaction);
+        let (status, body) = command(json!({
+            "command": "storeTransactions",
+            "trytes": [transaction_to_trytes(&transaction)],
+        }));
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body, json!({}));
+
+        // Transactions are stored asynchronously by the processing pipeline.
+        let start = Instant::now();
+        while !tangle().contains(&hash) {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn invalid_hash() {
+        let (status, body) = command(json!({ "command": "getTrytes", "hashes": ["ABC"] }));
+
+        assert_eq!(status, StatusCode::BadRequest);
+        assert_eq!(body["error"], "Invalid hash.");
+    }
+
+    #[test]
+    fn get_trytes() {
+        let (hash, transaction) = create_random_tx();
+        let (unknown, _) = create_random_tx();
+        insert(hash, transaction.clone());
+
+        let (status, body) = command(json!({
+            "command": "getTrytes",
+            "hashes": [hash_to_trytes(&hash), hash_to_trytes(&unknown)],
+        }));
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body["trytes"][0], transaction_to_trytes(&transaction));
+        assert_eq!(body["trytes"][1], "9".repeat(TRANSACTION_TRYT_LEN));
+    }
+
+    #[test]
+    fn get_tips() {
+        let (hash, transaction) = create_random_tx();
+        insert(hash, transaction);
+
+        let (status, body) = command(json!({ "command": "getTips" }));
+
+        assert_eq!(status, StatusCode::Ok);
+        assert!(body["hashes"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from(hash_to_trytes(&hash))));
+    }
+
+    #[test]
+    fn get_inclusion_states() {
+        let (confirmed, transaction) = create_random_tx();
+        insert(confirmed, transaction);
+        tangle().update_metadata(&confirmed, |metadata| metadata.flags_mut().set_confirmed());
+        let (unconfirmed, transaction) = create_random_tx();
+        insert(unconfirmed, transaction);
+
+        let (status, body) = command(json!({
+            "command": "getInclusionStates",
+            "transactions": [hash_to_trytes(&confirmed), hash_to_trytes(&unconfirmed)],
+        }));
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body["states"], json!([true, false]));
+    }
+
+    #[test]
+    fn find_transactions_by_approvee() {
+        let (parent, transaction) = create_random_tx();
+        insert(parent, transaction);
+        let (child, transaction) = create_random_attached_tx(parent, parent);
+        insert(child, transaction);
+
+        let (status, body) = command(json!({
+            "command": "findTransactions",
+            "approvees": [hash_to_trytes(&parent)],
+        }));
+
+        assert_eq!(status, StatusCode::Ok);
+        assert_eq!(body["hashes"], json!([hash_to_trytes(&child)]));
+    }
+
+    #[test]
+    fn check_consistency_unknown_tail() {
+        let (hash, _) = create_random_tx();
+
+        let (status, body) = command(json!({ "command": "checkConsistency", "tails": [hash_to_trytes(&hash)] }));
+
+        assert_eq!(status, StatusCode::BadRequest);
+        assert_eq!(body["error"], "Unknown transaction.");
+    }
+
+    #[test]
+    fn cors_allowed_origin() {
+        let config = ApiConfig::build()
+            .cors_allowed_origins(vec!["https://example.com".to_string()])
+            .finish();
+
+        let response = post(&config, Some("https://example.com"), r#"{"command": "getTips"}"#);
+
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert_eq!(response["access-control-allow-origin"].as_str(), "https://example.com");
+    }
+
+    #[test]
+    fn cors_disabled_by_default() {
+        let response = post(
+            &ApiConfig::build().finish(),
+            Some("https://example.com"),
+            r#"{"command": "getTips"}"#,
+        );
+
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert!(response.header("access-control-allow-origin").is_none());
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+mod config;
+mod iri;
+
+pub use config::{ApiConfig, ApiConfigBuilder};
+
+use bee_api::ApiService;
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+
+use async_std::task::spawn;
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+};
+use log::{error, info};
+use tide::{
+    security::{CorsMiddleware, Origin},
+    Server,
+};
+
+fn server(config: &ApiConfig, service: ApiService) -> Server<ApiService> {
+    let mut server = tide::with_state(service);
+
+    if !config.cors_allowed_origins.is_empty() {
+        let origin = if config.cors_allowed_origins.iter().any(|origin| origin == "*") {
+            Origin::Any
+        } else {
+            Origin::List(config.cors_allowed_origins.clone())
+        };
+
+        server.middleware(CorsMiddleware::new().allow_origin(origin));
+    }
+    server.at("/").post(iri::handle);
+
+    server
+}
+
+async fn run(server: Server<ApiService>, address: String, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    if let Either::Left((Err(e), _)) = select(Box::pin(server.listen(address.clone())), shutdown).await {
+        error!("Listening on {} failed: {}.", address, e);
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) fn init(config: &ApiConfig, service: ApiService, shutdown: &mut Shutdown) {
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+    info!("Serving API on {}.", config.socket_addr());
+
+    shutdown.add_worker_shutdown(
+        shutdown_tx,
+        spawn(run(
+            server(config, service),
+            config.socket_addr().to_string(),
+            shutdown_rx,
+        )),
+    );
+}
@@ -9,6 +9,12 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+use crate::{
+    admin::{AdminConfig, AdminConfigBuilder},
+    api::{ApiConfig, ApiConfigBuilder},
+    plugin::{PluginsConfig, PluginsConfigBuilder},
+};
+
 use bee_common::logger::{LoggerConfig, LoggerConfigBuilder};
 use bee_network::{NetworkConfig, NetworkConfigBuilder};
 use bee_peering::{PeeringConfig, PeeringConfigBuilder};
@@ -31,9 +37,15 @@ pub enum Error {
 
 #[derive(Default, Deserialize)]
 pub struct NodeConfigBuilder {
+    #[serde(default)]
+    pub(crate) admin: AdminConfigBuilder,
+    #[serde(default)]
+    pub(crate) api: ApiConfigBuilder,
     pub(crate) logger: LoggerConfigBuilder,
     pub(crate) network: NetworkConfigBuilder,
     pub(crate) peering: PeeringConfigBuilder,
+    #[serde(default)]
+    pub(crate) plugins: PluginsConfigBuilder,
     pub(crate) protocol: ProtocolConfigBuilder,
     pub(crate) snapshot: SnapshotConfigBuilder,
 }
@@ -49,9 +61,12 @@ impl NodeConfigBuilder {
 
     pub fn finish(self) -> NodeConfig {
         NodeConfig {
+            admin: self.admin.finish(),
+            api: self.api.finish(),
             logger: self.logger.finish(),
             network: self.network.finish(),
             peering: self.peering.finish(),
+            plugins: self.plugins.finish(),
             protocol: self.protocol.finish(),
             snapshot: self.snapshot.finish(),
         }
@@ -60,9 +75,69 @@ impl NodeConfigBuilder {
 
 #[derive(Clone)]
 pub struct NodeConfig {
+    pub admin: AdminConfig,
+    pub api: ApiConfig,
     pub logger: LoggerConfig,
     pub network: NetworkConfig,
     pub peering: PeeringConfig,
+    pub plugins: PluginsConfig,
     pub protocol: ProtocolConfig,
     pub snapshot: SnapshotConfig,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example config as shipped before the admin API, the API and the plugins were added.
+    const LEGACY_CONFIG: &str = r#"
+[logger]
+color_enabled = true
+[[logger.outputs]]
+name  = "stdout"
+level = "info"
+
+[network]
+binding_addr        = "0.0.0.0"
+binding_port        = 15600
+reconnect_interval  = 60
+
+[peering]
+[peering.static]
+limit     = 5
+peers     = [ ]
+
+[protocol]
+mwm = 14
+[protocol.coordinator]
+depth           = 25
+public_key      = "UDYXTZBE9GZGPM9SSQV9LTZNDLJIZMPUVVXYXFYVBLIEUHLSEWFTKZZLXYRHHWVQV9MNNX9KZC9D9UZWZ"
+security_level  = 2
+sponge_type     = "kerl"
+[protocol.workers]
+status_interval = 10
+
+[snapshot.local]
+file_path     = "./snapshots/mainnet/export.bin"
+download_urls = [
+  "https://ls.manapotion.io/export.bin",
+  "https://x-vps.com/export.bin",
+  "https://dbfiles.iota.org/mainnet/hornet/latest-export.bin"
+]
+"#;
+
+    #[test]
+    fn legacy_config_loads() {
+        let config = toml::from_str::<NodeConfigBuilder>(LEGACY_CONFIG).unwrap().finish();
+
+        assert_eq!(config.admin.socket_addr(), AdminConfig::build().finish().socket_addr());
+        assert_eq!(config.api.socket_addr(), ApiConfig::build().finish().socket_addr());
+    }
+
+    #[test]
+    fn example_config_loads() {
+        toml::from_str::<NodeConfigBuilder>(include_str!("../config.example.toml"))
+            .unwrap()
+            .finish();
+    }
+}
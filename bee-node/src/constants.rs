@@ -11,3 +11,7 @@
 
 pub(crate) const BEE_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub(crate) const BEE_GIT_COMMIT: &str = env!("GIT_COMMIT");
+/// Path of the node config file.
+pub const CONFIG_PATH: &str = "./config.toml";
+/// Path of the file storing the private key of the node identity.
+pub(crate) const IDENTITY_PATH: &str = "./identity.key";
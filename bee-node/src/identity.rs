@@ -0,0 +1,91 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Persistent identity of the node.
+
+use bee_network::{Identity, IdentityError};
+
+use log::info;
+use thiserror::Error;
+
+use std::{fs, io, path::Path};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Reading the identity file failed: {0}.")]
+    IdentityFileReadFailure(io::Error),
+
+    #[error("Writing the identity file failed: {0}.")]
+    IdentityFileWriteFailure(io::Error),
+
+    #[error("Invalid identity file: {0}.")]
+    InvalidIdentity(IdentityError),
+}
+
+/// Loads the identity of the node from a file containing its hex encoded private key, or generates and stores a new
+/// one if there is no such file.
+pub(crate) fn load_or_generate<P: AsRef<Path>>(path: P) -> Result<Identity, Error> {
+    let path = path.as_ref();
+
+    match fs::read_to_string(path) {
+        Ok(hex) => Identity::from_private_key_hex(hex.trim()).map_err(Error::InvalidIdentity),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let identity = Identity::generate();
+
+            write(path, &identity).map_err(Error::IdentityFileWriteFailure)?;
+
+            info!("Generated new identity stored in \"{}\".", path.display());
+
+            Ok(identity)
+        }
+        Err(e) => Err(Error::IdentityFileReadFailure(e)),
+    }
+}
+
+fn write(path: &Path, identity: &Identity) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+
+    options.write(true).create_new(true);
+
+    // NOTE: The private key must only be readable by the owner of the node.
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    io::Write::write_all(&mut options.open(path)?, identity.private_key_hex().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_then_load() {
+        let path = std::env::temp_dir().join(format!("bee-identity-{}.key", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let generated = load_or_generate(&path).unwrap();
+        let loaded = load_or_generate(&path).unwrap();
+
+        assert_eq!(generated.public_key(), loaded.public_key());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_identity_file() {
+        let path = std::env::temp_dir().join(format!("bee-invalid-identity-{}.key", std::process::id()));
+        fs::write(&path, "not a key").unwrap();
+
+        assert!(matches!(load_or_generate(&path), Err(Error::InvalidIdentity(_))));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
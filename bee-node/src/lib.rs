@@ -9,12 +9,16 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+mod admin;
+mod api;
 mod cli;
 mod config;
 mod constants;
+mod identity;
 mod node;
 mod plugin;
 
 pub use cli::CliArgs;
 pub use config::NodeConfigBuilder;
+pub use constants::CONFIG_PATH;
 pub use node::{Error, Node};
@@ -10,9 +10,7 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use bee_common::logger::logger_init;
-use bee_node::{CliArgs, Node, NodeConfigBuilder};
-
-const CONFIG_PATH: &str = "./config.toml";
+use bee_node::{CliArgs, Node, NodeConfigBuilder, CONFIG_PATH};
 
 fn main() {
     match NodeConfigBuilder::from_file(CONFIG_PATH) {
@@ -12,16 +12,18 @@
 #![warn(missing_docs)]
 
 use crate::{
+    admin, api,
     config::NodeConfig,
-    constants::{BEE_GIT_COMMIT, BEE_VERSION},
-    plugin,
+    constants::{BEE_GIT_COMMIT, BEE_VERSION, CONFIG_PATH, IDENTITY_PATH},
+    identity, plugin,
 };
 
+use bee_api::ApiService;
 use bee_common::{shutdown::Shutdown, shutdown_stream::ShutdownStream};
 use bee_common_ext::event::Bus;
 use bee_crypto::ternary::Hash;
-use bee_network::{self, Address, Command::Connect, EndpointId, Event, EventSubscriber, Network, Origin};
-use bee_peering::{PeerManager, StaticPeerManager};
+use bee_network::{self, Address, Command::Connect, EndpointId, Event, EventSubscriber, Network, Origin, PublicKey};
+use bee_peering::{AutopeeringManager, PeerManager, StaticPeerManager};
 use bee_protocol::{tangle, MilestoneIndex, Protocol};
 use bee_snapshot::local::{download_local_snapshot, Error as LocalSnapshotReadError, LocalSnapshot};
 
@@ -46,6 +48,10 @@ pub enum Error {
     #[error("Reading the snapshot file failed.")]
     LocalSnapshotReadError(LocalSnapshotReadError),
 
+    /// Occurs, when the identity of the node can neither be loaded nor generated.
+    #[error("Loading the node identity failed.")]
+    IdentityError(#[from] identity::Error),
+
     /// Occurs, when there is an error while shutting down the node.
     #[error("Shutting down failed.")]
     ShutdownError(#[from] bee_common::shutdown::Error),
@@ -74,14 +80,15 @@ impl NodeBuilder {
         let local_snapshot = match LocalSnapshot::from_file(self.config.snapshot.local().file_path()) {
             Ok(local_snapshot) => {
                 info!(
-                    "Read snapshot file from {} with index {}, {} solid entry points, {} seen milestones and \
-                    {} balances.",
+                    "Read snapshot file from {} with index {}, {} solid entry points, {} seen milestones, \
+                    {} balances and {} spent addresses.",
                     Utc.timestamp(local_snapshot.metadata().timestamp() as i64, 0)
                         .to_rfc2822(),
                     local_snapshot.metadata().index(),
                     local_snapshot.metadata().solid_entry_points().len(),
                     local_snapshot.metadata().seen_milestones().len(),
-                    local_snapshot.state().balances().len()
+                    local_snapshot.state().balances().len(),
+                    local_snapshot.state().spent_addresses().len()
                 );
 
                 tangle::tangle().update_last_solid_milestone_index(local_snapshot.metadata().index().into());
@@ -97,6 +104,11 @@ impl NodeBuilder {
                     tangle::tangle().add_solid_entry_point(*hash, MilestoneIndex(*index));
                 }
 
+                // Addresses spent from before the snapshot, those spent from afterwards are added by the ledger.
+                for address in local_snapshot.state().spent_addresses() {
+                    tangle::tangle().add_spent_address(address.clone());
+                }
+
                 for _seen_milestone in local_snapshot.metadata().seen_milestones() {
                     // TODO request ?
                 }
@@ -117,14 +129,28 @@ impl NodeBuilder {
         let snapshot_index = local_snapshot.metadata().index();
         let snapshot_timestamp = local_snapshot.metadata().timestamp();
 
+        info!("Loading identity...");
+        let identity = identity::load_or_generate(IDENTITY_PATH)?;
+        let public_key = *identity.public_key();
+        info!("Public key: {}.", public_key);
+
         info!("Initializing network...");
-        let (network, events) = bee_network::init(self.config.network, &mut shutdown);
+        let (network, events) = bee_network::init(self.config.network, identity.clone(), &mut shutdown);
 
         info!("Starting static peer manager...");
-        spawn(StaticPeerManager::new(self.config.peering.r#static.clone(), network.clone()).run());
+        spawn(
+            StaticPeerManager::new(self.config.peering.r#static.clone(), network.clone())
+                .watch(CONFIG_PATH)
+                .run(),
+        );
+
+        if self.config.peering.autopeering.enabled() {
+            info!("Starting autopeering manager...");
+            spawn(AutopeeringManager::new(self.config.peering.autopeering.clone(), network.clone(), &identity).run());
+        }
 
         info!("Initializing ledger...");
-        bee_ledger::whiteflag::init(
+        let ledger_worker = bee_ledger::whiteflag::init(
             snapshot_index,
             local_snapshot.into_state().into_balances(),
             self.config.protocol.coordinator().clone(),
@@ -135,14 +161,25 @@ impl NodeBuilder {
         block_on(Protocol::init(
             self.config.protocol.clone(),
             network.clone(),
+            public_key,
             snapshot_timestamp,
             bus.clone(),
             &mut shutdown,
         ));
 
+        info!("Initializing API...");
+        api::init(
+            &self.config.api,
+            ApiService::new("Bee".to_owned(), BEE_VERSION.to_owned(), ledger_worker),
+            &mut shutdown,
+        );
+
+        info!("Initializing admin API...");
+        admin::init(&self.config.admin, network.clone(), &mut shutdown);
+
         info!("Initializing plugins...");
 
-        plugin::init(bus, &mut shutdown);
+        plugin::init(&self.config.plugins, bus, &mut shutdown);
 
         info!("Initialized.");
 
@@ -192,9 +229,22 @@ impl Node {
             Event::EndpointAdded { epid, .. } => self.endpoint_added_handler(epid).await,
             Event::EndpointRemoved { epid, .. } => self.endpoint_removed_handler(epid),
             Event::EndpointConnected {
-                epid, origin, address, ..
-            } => self.endpoint_connected_handler(epid, address, origin),
+                epid,
+                origin,
+                address,
+                public_key,
+                ..
+            } => self.endpoint_connected_handler(epid, address, origin, public_key),
             Event::EndpointDisconnected { epid, .. } => self.endpoint_disconnected_handler(epid),
+            Event::EndpointAddressChanged { epid, new_address, .. } => {
+                info!("Endpoint {} is now reachable at {}.", epid, new_address)
+            }
+            Event::EndpointBanned { epid, duration, .. } => {
+                info!("Endpoint {} has been banned for {}s.", epid, duration.as_secs())
+            }
+            Event::EndpointUnbanned { epid, .. } => info!("Endpoint {} is no longer banned.", epid),
+            Event::ConnectionRefused { address, reason } => debug!("Refused connection with {}: {}.", address, reason),
+            Event::MessageSent { .. } => (),
             Event::MessageReceived { epid, bytes, .. } => self.endpoint_bytes_received_handler(epid, bytes).await,
             _ => warn!("Unsupported event {}.", event),
         }
@@ -228,8 +278,14 @@ impl Node {
         info!("Endpoint {} has been removed.", epid);
     }
 
-    fn endpoint_connected_handler(&mut self, epid: EndpointId, address: Address, origin: Origin) {
-        let (receiver_tx, receiver_shutdown_tx) = Protocol::register(epid, address, origin);
+    fn endpoint_connected_handler(
+        &mut self,
+        epid: EndpointId,
+        address: Address,
+        origin: Origin,
+        public_key: Option<PublicKey>,
+    ) {
+        let (receiver_tx, receiver_shutdown_tx) = Protocol::register(epid, address, origin, public_key);
 
         self.peers.insert(epid, (receiver_tx, receiver_shutdown_tx));
     }
@@ -0,0 +1,153 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::plugin::{event_stream, mqtt, prometheus, publisher};
+
+use serde::Deserialize;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+const DEFAULT_ENABLED: bool = false;
+const DEFAULT_BINDING_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+
+/// Configuration builder of a plugin serving clients, e.g. the event stream.
+#[derive(Default, Deserialize)]
+pub struct PluginServerConfigBuilder {
+    enabled: Option<bool>,
+    binding_addr: Option<IpAddr>,
+    binding_port: Option<u16>,
+}
+
+impl PluginServerConfigBuilder {
+    /// Creates a new config builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the plugin.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled.replace(enabled);
+        self
+    }
+
+    /// Sets the binding address of the plugin server.
+    pub fn binding_addr(mut self, addr: &str) -> Self {
+        match addr.parse() {
+            Ok(addr) => {
+                self.binding_addr.replace(addr);
+            }
+            Err(e) => panic!("Error parsing address: {:?}", e),
+        }
+        self
+    }
+
+    /// Sets the binding port of the plugin server.
+    pub fn binding_port(mut self, port: u16) -> Self {
+        self.binding_port.replace(port);
+        self
+    }
+
+    /// Builds the plugin server config, with the default port of the plugin.
+    pub fn finish(self, default_binding_port: u16) -> PluginServerConfig {
+        PluginServerConfig {
+            enabled: self.enabled.unwrap_or(DEFAULT_ENABLED),
+            binding_addr: self.binding_addr.unwrap_or(DEFAULT_BINDING_ADDR),
+            binding_port: self.binding_port.unwrap_or(default_binding_port),
+        }
+    }
+}
+
+/// Configuration of a plugin serving clients.
+#[derive(Clone)]
+pub struct PluginServerConfig {
+    pub(crate) enabled: bool,
+    pub(crate) binding_addr: IpAddr,
+    pub(crate) binding_port: u16,
+}
+
+impl PluginServerConfig {
+    /// Returns a builder for this config.
+    pub fn build() -> PluginServerConfigBuilder {
+        PluginServerConfigBuilder::new()
+    }
+
+    /// Returns whether the plugin is enabled.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the listening address of the plugin server.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.binding_addr, self.binding_port)
+    }
+}
+
+/// Plugins configuration builder.
+#[derive(Default, Deserialize)]
+pub struct PluginsConfigBuilder {
+    #[serde(default)]
+    event_stream: PluginServerConfigBuilder,
+    #[serde(default)]
+    mqtt: PluginServerConfigBuilder,
+    #[serde(default)]
+    prometheus: PluginServerConfigBuilder,
+    #[serde(default)]
+    publisher: PluginServerConfigBuilder,
+}
+
+impl PluginsConfigBuilder {
+    /// Creates a new config builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the plugins config.
+    pub fn finish(self) -> PluginsConfig {
+        PluginsConfig {
+            event_stream: self.event_stream.finish(event_stream::DEFAULT_BINDING_PORT),
+            mqtt: self.mqtt.finish(mqtt::DEFAULT_BINDING_PORT),
+            prometheus: self.prometheus.finish(prometheus::DEFAULT_BINDING_PORT),
+            publisher: self.publisher.finish(publisher::DEFAULT_BINDING_PORT),
+        }
+    }
+}
+
+/// Plugins configuration.
+#[derive(Clone)]
+pub struct PluginsConfig {
+    pub(crate) event_stream: PluginServerConfig,
+    pub(crate) mqtt: PluginServerConfig,
+    pub(crate) prometheus: PluginServerConfig,
+    pub(crate) publisher: PluginServerConfig,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_servers_default_to_localhost() {
+        let config = toml::from_str::<PluginsConfigBuilder>(
+            r#"
+            [event_stream]
+            enabled = true
+            "#,
+        )
+        .unwrap()
+        .finish();
+
+        assert!(config.event_stream.enabled());
+        assert_eq!(
+            config.event_stream.socket_addr(),
+            SocketAddr::new(DEFAULT_BINDING_ADDR, event_stream::DEFAULT_BINDING_PORT)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Streams bus events as JSON to clients over Server-Sent Events.
+//!
+//! Clients connect to `/events`, optionally restricting the stream to some topics with e.g. `/events?topics=tps,peers`.
+//! Each SSE event is named after its topic.
+
+mod subscribers;
+
+use crate::plugin::{Plugin, PluginServerConfig};
+
+use subscribers::{Subscribers, Topic, UnknownTopic};
+
+use bee_api::trytes::hash_to_trytes;
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+use bee_common_ext::event::Bus;
+use bee_ledger::event::MilestoneConfirmed;
+use bee_protocol::{
+    event::{HandshakeCompleted, LastMilestoneChanged, LastSolidMilestoneChanged, NewTransaction, TpsMetricsUpdated},
+    Milestone,
+};
+
+use async_std::task::spawn;
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+    stream::StreamExt,
+};
+use log::{error, info};
+use serde_json::{json, Value};
+use tide::{sse, Request, Response, Server, StatusCode};
+
+use std::{collections::HashSet, convert::Infallible, sync::Arc};
+
+pub(crate) const DEFAULT_BINDING_PORT: u16 = 8081;
+
+fn milestone(milestone: &Milestone) -> Value {
+    json!({
+        "index": *milestone.index(),
+        "hash": hash_to_trytes(milestone.hash()),
+    })
+}
+
+fn add_listeners(bus: &Bus, subscribers: &Subscribers) {
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &NewTransaction| {
+        s.publish(Topic::Transactions, || {
+            json!({
+                "hash": hash_to_trytes(&event.hash),
+                "arrivalTimestamp": event.metadata.arrival_timestamp(),
+            })
+        })
+    });
+
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &LastMilestoneChanged| s.publish(Topic::LastMilestone, || milestone(&event.0)));
+
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &LastSolidMilestoneChanged| {
+        s.publish(Topic::LastSolidMilestone, || milestone(&event.0))
+    });
+
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &MilestoneConfirmed| {
+        s.publish(Topic::ConfirmedMilestone, || {
+            json!({
+                "index": *event.milestone.index(),
+                "hash": hash_to_trytes(event.milestone.hash()),
+                "timestamp": event.timestamp,
+                "tailsReferenced": event.tails_referenced,
+                "tailsZeroValue": event.tails_zero_value,
+                "tailsConflicting": event.tails_conflicting,
+                "tailsIncluded": event.tails_included,
+            })
+        })
+    });
+
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &TpsMetricsUpdated| {
+        s.publish(Topic::Tps, || {
+            json!({
+                "incoming": event.incoming,
+                "new": event.new,
+                "known": event.known,
+                "stale": event.stale,
+                "invalid": event.invalid,
+                "outgoing": event.outgoing,
+            })
+        })
+    });
+
+    let s = subscribers.clone();
+    bus.add_listener(move |event: &HandshakeCompleted| {
+        s.publish(Topic::Peers, || json!({ "address": event.0.to_string() }))
+    });
+}
+
+// Topics are given as a comma separated list, all topics being streamed if there is none.
+fn topics(query: Option<&str>) -> Result<HashSet<Topic>, UnknownTopic> {
+    let topics = query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("topics="))
+        .flat_map(|topics| topics.split(','))
+        .filter(|topic| !topic.is_empty())
+        .map(str::parse)
+        .collect::<Result<HashSet<Topic>, UnknownTopic>>()?;
+
+    if topics.is_empty() {
+        Ok(Topic::ALL.iter().copied().collect())
+    } else {
+        Ok(topics)
+    }
+}
+
+async fn events(request: Request<Subscribers>) -> tide::Result {
+    let topics = match topics(request.url().query()) {
+        Ok(topics) => topics,
+        Err(e) => {
+            let mut response = Response::new(StatusCode::BadRequest);
+            response.set_body(e.to_string());
+            return Ok(response);
+        }
+    };
+
+    Ok(sse::upgrade(request, move |request: Request<Subscribers>, sender| {
+        let mut receiver = request.state().subscribe(topics.clone());
+
+        async move {
+            // Stops when the client disconnects, as sending then fails.
+            while let Some(event) = receiver.next().await {
+                sender.send(event.topic.as_str(), &event.data, None).await?;
+            }
+
+            Ok(())
+        }
+    }))
+}
+
+fn server(subscribers: Subscribers) -> Server<Subscribers> {
+    let mut server = tide::with_state(subscribers);
+
+    server.at("/events").get(events);
+
+    server
+}
+
+async fn run(server: Server<Subscribers>, address: String, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    if let Either::Left((Err(e), _)) = select(Box::pin(server.listen(address.clone())), shutdown).await {
+        error!("Listening on {} failed: {}.", address, e);
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) struct EventStreamPlugin {
+    config: PluginServerConfig,
+}
+
+impl EventStreamPlugin {
+    pub(crate) fn new(config: PluginServerConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for EventStreamPlugin {
+    type Error = Infallible;
+
+    fn name(&self) -> &str {
+        "event_stream"
+    }
+
+    fn init(&mut self, bus: Arc<Bus>, shutdown: &mut Shutdown) -> Result<(), Self::Error> {
+        let subscribers = Subscribers::default();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        add_listeners(&bus, &subscribers);
+
+        info!("Streaming events on {}.", self.config.socket_addr());
+
+        shutdown.add_worker_shutdown(
+            shutdown_tx,
+            spawn(run(
+                server(subscribers),
+                self.config.socket_addr().to_string(),
+                shutdown_rx,
+            )),
+        );
+
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_topics_by_default() {
+        assert_eq!(topics(None).unwrap().len(), Topic::ALL.len());
+        assert_eq!(topics(Some("foo=bar")).unwrap().len(), Topic::ALL.len());
+    }
+
+    #[test]
+    fn some_topics() {
+        let topics = topics(Some("topics=tps,peers")).unwrap();
+
+        assert_eq!(topics.len(), 2);
+        assert!(topics.contains(&Topic::Tps));
+        assert!(topics.contains(&Topic::Peers));
+    }
+
+    #[test]
+    fn invalid_topic() {
+        assert!(topics(Some("topics=tps,foo")).is_err());
+    }
+
+    #[test]
+    fn bus_events_are_published() {
+        let bus = Bus::default();
+        let subscribers = Subscribers::default();
+        let mut receiver = subscribers.subscribe(vec![Topic::Tps].into_iter().collect());
+
+        add_listeners(&bus, &subscribers);
+        bus.dispatch(TpsMetricsUpdated {
+            incoming: 1,
+            new: 2,
+            known: 3,
+            stale: 4,
+            invalid: 5,
+            outgoing: 6,
+        });
+
+        let event = receiver.try_next().unwrap().unwrap();
+        let data: Value = serde_json::from_str(&event.data).unwrap();
+
+        assert_eq!(event.topic, Topic::Tps);
+        assert_eq!(data["new"], 2);
+        assert_eq!(data["outgoing"], 6);
+    }
+}
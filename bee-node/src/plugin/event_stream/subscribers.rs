@@ -0,0 +1,209 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use futures::channel::mpsc;
+use serde_json::Value;
+use thiserror::Error;
+
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+// Number of events a client can lag behind before it is disconnected.
+const SUBSCRIBER_CAPACITY: usize = 256;
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown topic: {0}.")]
+pub(crate) struct UnknownTopic(String);
+
+/// Kinds of events clients can subscribe to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Topic {
+    Transactions,
+    LastMilestone,
+    LastSolidMilestone,
+    ConfirmedMilestone,
+    Tps,
+    Peers,
+}
+
+impl Topic {
+    pub(crate) const ALL: [Topic; 6] = [
+        Topic::Transactions,
+        Topic::LastMilestone,
+        Topic::LastSolidMilestone,
+        Topic::ConfirmedMilestone,
+        Topic::Tps,
+        Topic::Peers,
+    ];
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Topic::Transactions => "transactions",
+            Topic::LastMilestone => "lastMilestone",
+            Topic::LastSolidMilestone => "lastSolidMilestone",
+            Topic::ConfirmedMilestone => "confirmedMilestone",
+            Topic::Tps => "tps",
+            Topic::Peers => "peers",
+        }
+    }
+}
+
+impl FromStr for Topic {
+    type Err = UnknownTopic;
+
+    fn from_str(topic: &str) -> Result<Self, Self::Err> {
+        Topic::ALL
+            .iter()
+            .find(|t| t.as_str() == topic)
+            .copied()
+            .ok_or_else(|| UnknownTopic(topic.to_owned()))
+    }
+}
+
+/// An event, as sent to the subscribers of its topic.
+#[derive(Debug)]
+pub(crate) struct StreamEvent {
+    pub(crate) topic: Topic,
+    pub(crate) data: String,
+}
+
+struct Subscriber {
+    topics: HashSet<Topic>,
+    sender: mpsc::Sender<Arc<StreamEvent>>,
+}
+
+/// Clients currently subscribed to the event stream.
+///
+/// Publishing happens synchronously in the bus listeners, it only serializes an event when at least one client is
+/// subscribed to its topic and never waits on a client: a client too slow to keep up with its topics is disconnected.
+#[derive(Clone, Default)]
+pub(crate) struct Subscribers(Arc<Mutex<Vec<Subscriber>>>);
+
+impl Subscribers {
+    pub(crate) fn subscribe(&self, topics: HashSet<Topic>) -> mpsc::Receiver<Arc<StreamEvent>> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_CAPACITY);
+
+        self.0
+            .lock()
+            .expect("Subscribers lock poisoned")
+            .push(Subscriber { topics, sender });
+
+        receiver
+    }
+
+    pub(crate) fn publish<F: FnOnce() -> Value>(&self, topic: Topic, data: F) {
+        let mut subscribers = self.0.lock().expect("Subscribers lock poisoned");
+
+        // Subscribers whose receiver was dropped, i.e. disconnected clients, are removed.
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+
+        if subscribers.iter().all(|subscriber| !subscriber.topics.contains(&topic)) {
+            return;
+        }
+
+        let event = Arc::new(StreamEvent {
+            topic,
+            data: data().to_string(),
+        });
+
+        for subscriber in subscribers
+            .iter_mut()
+            .filter(|subscriber| subscriber.topics.contains(&topic))
+        {
+            // Fails if the client does not keep up or disconnected in the meantime, closing the channel then ends the
+            // stream of the client once it received the queued events.
+            if subscriber.sender.try_send(event.clone()).is_err() {
+                subscriber.sender.close_channel();
+            }
+        }
+
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.0.lock().expect("Subscribers lock poisoned").len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::stream::StreamExt;
+    use serde_json::json;
+
+    use std::iter::FromIterator;
+
+    #[test]
+    fn topic_roundtrip() {
+        for topic in Topic::ALL.iter() {
+            assert_eq!(topic.as_str().parse::<Topic>().unwrap(), *topic);
+        }
+    }
+
+    #[test]
+    fn unknown_topic() {
+        assert_eq!("foo".parse::<Topic>().unwrap_err(), UnknownTopic("foo".to_owned()));
+    }
+
+    #[test]
+    fn publish_to_subscribed_topics_only() {
+        let subscribers = Subscribers::default();
+        let mut tps = subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps]));
+        let mut peers = subscribers.subscribe(HashSet::from_iter(vec![Topic::Peers]));
+
+        subscribers.publish(Topic::Tps, || json!({ "incoming": 1 }));
+
+        let event = tps.try_next().unwrap().unwrap();
+        assert_eq!(event.topic, Topic::Tps);
+        assert_eq!(event.data, r#"{"incoming":1}"#);
+        assert!(peers.try_next().is_err());
+    }
+
+    #[test]
+    fn no_serialization_without_subscriber() {
+        let subscribers = Subscribers::default();
+        let _tps = subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps]));
+
+        subscribers.publish(Topic::Peers, || panic!("Serialized an event nobody subscribed to"));
+    }
+
+    #[test]
+    fn remove_disconnected_subscribers() {
+        let subscribers = Subscribers::default();
+        let mut connected = subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps]));
+        drop(subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps])));
+
+        subscribers.publish(Topic::Tps, || json!({}));
+
+        assert_eq!(subscribers.len(), 1);
+        assert!(futures::executor::block_on(connected.next()).is_some());
+    }
+
+    #[test]
+    fn remove_slow_subscribers() {
+        let subscribers = Subscribers::default();
+        let slow = subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps]));
+        let mut fast = subscribers.subscribe(HashSet::from_iter(vec![Topic::Tps]));
+
+        for _ in 0..2 * SUBSCRIBER_CAPACITY {
+            subscribers.publish(Topic::Tps, || json!({}));
+            assert!(fast.try_next().unwrap().is_some());
+        }
+
+        assert_eq!(subscribers.len(), 1);
+        assert!(futures::executor::block_on(slow.collect::<Vec<_>>()).len() < 2 * SUBSCRIBER_CAPACITY);
+    }
+}
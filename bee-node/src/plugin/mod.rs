@@ -9,12 +9,21 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+mod config;
+mod event_stream;
+mod mqtt;
+mod prometheus;
+mod publisher;
+mod tps;
+
+pub use config::{PluginServerConfig, PluginServerConfigBuilder, PluginsConfig, PluginsConfigBuilder};
+
 use bee_common::shutdown::Shutdown;
 use bee_common_ext::event::Bus;
 
-use std::sync::Arc;
+use log::error;
 
-mod tps;
+use std::sync::Arc;
 
 pub trait Plugin {
     type Error;
@@ -24,6 +33,34 @@ pub trait Plugin {
     fn start(&mut self) -> Result<(), Self::Error>;
 }
 
-pub(crate) fn init(bus: Arc<Bus>, shutdown: &mut Shutdown) {
-    tps::TpsPlugin::new().init(bus, shutdown);
+pub(crate) fn init(config: &PluginsConfig, bus: Arc<Bus>, shutdown: &mut Shutdown) {
+    tps::TpsPlugin::new().init(bus.clone(), shutdown);
+
+    if config.event_stream.enabled() {
+        let mut event_stream = event_stream::EventStreamPlugin::new(config.event_stream.clone());
+        if let Err(e) = event_stream.init(bus.clone(), shutdown) {
+            error!("Initializing plugin {} failed: {:?}.", event_stream.name(), e);
+        }
+    }
+
+    if config.mqtt.enabled() {
+        let mut mqtt = mqtt::MqttPlugin::new(config.mqtt.clone());
+        if let Err(e) = mqtt.init(bus.clone(), shutdown) {
+            error!("Initializing plugin {} failed: {:?}.", mqtt.name(), e);
+        }
+    }
+
+    if config.prometheus.enabled() {
+        let mut prometheus = prometheus::PrometheusPlugin::new(config.prometheus.clone());
+        if let Err(e) = prometheus.init(bus.clone(), shutdown) {
+            error!("Initializing plugin {} failed: {:?}.", prometheus.name(), e);
+        }
+    }
+
+    if config.publisher.enabled() {
+        let mut publisher = publisher::PublisherPlugin::new(config.publisher.clone());
+        if let Err(e) = publisher.init(bus, shutdown) {
+            error!("Initializing plugin {} failed: {:?}.", publisher.name(), e);
+        }
+    }
 }
@@ -0,0 +1,185 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::plugin::mqtt::packet::{matches, Packet};
+
+use futures::channel::mpsc;
+use serde_json::Value;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+// Number of packets a session can lag behind before it is disconnected.
+const SESSION_CAPACITY: usize = 256;
+
+struct Session {
+    filters: HashSet<String>,
+    sender: mpsc::Sender<Arc<Vec<u8>>>,
+}
+
+/// Connected sessions and their subscriptions.
+///
+/// Publishing happens synchronously in the bus listeners, a payload is only built when at least one session is
+/// subscribed to its topic and messages are queued to the sessions without waiting. A session too slow to keep up with
+/// its subscriptions is disconnected.
+#[derive(Clone, Default)]
+pub(crate) struct Broker(Arc<Mutex<(usize, HashMap<usize, Session>)>>);
+
+impl Broker {
+    /// Registers a new session, without any subscription, and returns its id and the receiver of its encoded
+    /// packets.
+    pub(crate) fn connect(&self) -> (usize, mpsc::Receiver<Arc<Vec<u8>>>) {
+        let (sender, receiver) = mpsc::channel(SESSION_CAPACITY);
+        let mut inner = self.0.lock().expect("Broker lock poisoned");
+        let id = inner.0;
+
+        inner.0 += 1;
+        inner.1.insert(
+            id,
+            Session {
+                filters: HashSet::new(),
+                sender,
+            },
+        );
+
+        (id, receiver)
+    }
+
+    pub(crate) fn disconnect(&self, id: usize) {
+        self.0.lock().expect("Broker lock poisoned").1.remove(&id);
+    }
+
+    /// Disconnects all sessions, which closes their receivers.
+    pub(crate) fn clear(&self) {
+        self.0.lock().expect("Broker lock poisoned").1.clear();
+    }
+
+    pub(crate) fn subscribe(&self, id: usize, filter: &str) {
+        if let Some(session) = self.0.lock().expect("Broker lock poisoned").1.get_mut(&id) {
+            session.filters.insert(filter.to_owned());
+        }
+    }
+
+    pub(crate) fn unsubscribe(&self, id: usize, filter: &str) {
+        if let Some(session) = self.0.lock().expect("Broker lock poisoned").1.get_mut(&id) {
+            session.filters.remove(filter);
+        }
+    }
+
+    /// Whether at least one session is subscribed to the topic.
+    pub(crate) fn is_subscribed(&self, topic: &str) -> bool {
+        self.0
+            .lock()
+            .expect("Broker lock poisoned")
+            .1
+            .values()
+            .any(|session| session.filters.iter().any(|filter| matches(filter, topic)))
+    }
+
+    /// Publishes, with QoS 0, the JSON payload to all the sessions subscribed to the topic.
+    pub(crate) fn publish<F: FnOnce() -> Value>(&self, topic: &str, payload: F) {
+        let mut inner = self.0.lock().expect("Broker lock poisoned");
+        let subscribed = |session: &Session| session.filters.iter().any(|filter| matches(filter, topic));
+
+        if !inner.1.values().any(subscribed) {
+            return;
+        }
+
+        let packet = Arc::new(
+            Packet::Publish {
+                topic: topic.to_owned(),
+                qos: 0,
+                packet_id: None,
+                payload: payload().to_string().into_bytes(),
+            }
+            .encode(),
+        );
+
+        // A session whose queue is full, or that is disconnecting, is removed; its receiver then closes once it
+        // received the queued packets.
+        inner
+            .1
+            .retain(|_, session| !subscribed(session) || session.sender.try_send(packet.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::stream::StreamExt;
+    use serde_json::json;
+
+    #[test]
+    fn publish_to_matching_sessions_only() {
+        let broker = Broker::default();
+        let (milestones, mut milestones_receiver) = broker.connect();
+        let (tags, mut tags_receiver) = broker.connect();
+
+        broker.subscribe(milestones, "milestones/+");
+        broker.subscribe(tags, "tags/+/transactions");
+
+        assert!(broker.is_subscribed("milestones/latest"));
+        assert!(!broker.is_subscribed("addresses/ABC/transactions"));
+
+        broker.publish("milestones/latest", || json!({ "index": 1 }));
+        broker.publish("addresses/ABC/transactions", || {
+            panic!("Built a payload nobody subscribed to")
+        });
+
+        let packet = milestones_receiver.try_next().unwrap().unwrap();
+        assert_eq!(
+            *packet,
+            Packet::Publish {
+                topic: "milestones/latest".to_owned(),
+                qos: 0,
+                packet_id: None,
+                payload: br#"{"index":1}"#.to_vec(),
+            }
+            .encode()
+        );
+        assert!(tags_receiver.try_next().is_err());
+    }
+
+    #[test]
+    fn unsubscribe_and_disconnect() {
+        let broker = Broker::default();
+        let (id, mut receiver) = broker.connect();
+
+        broker.subscribe(id, "#");
+        broker.unsubscribe(id, "#");
+        assert!(!broker.is_subscribed("milestones/latest"));
+
+        broker.disconnect(id);
+        assert_eq!(receiver.try_next().unwrap(), None);
+    }
+
+    #[test]
+    fn disconnect_slow_sessions() {
+        let broker = Broker::default();
+        let (slow, slow_receiver) = broker.connect();
+        let (fast, mut fast_receiver) = broker.connect();
+
+        broker.subscribe(slow, "milestones/#");
+        broker.subscribe(fast, "milestones/#");
+
+        for _ in 0..2 * SESSION_CAPACITY {
+            broker.publish("milestones/latest", || json!({ "index": 1 }));
+            assert!(fast_receiver.try_next().unwrap().is_some());
+        }
+
+        assert!(broker.is_subscribed("milestones/latest"));
+        assert_eq!(broker.0.lock().unwrap().1.len(), 1);
+        assert!(futures::executor::block_on(slow_receiver.collect::<Vec<_>>()).len() < 2 * SESSION_CAPACITY);
+    }
+}
@@ -0,0 +1,449 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! An embedded, publish-only, MQTT 3.1.1 broker.
+//!
+//! Clients can subscribe, with QoS 0, to the following topics:
+//! - `milestones/latest`, `milestones/solid` and `milestones/confirmed`;
+//! - `transactions/confirmed`, for each transaction of a confirmed bundle;
+//! - `transactions/<hash>/metadata`, each time the metadata of a transaction changes;
+//! - `addresses/<address>/transactions` and `tags/<tag>/transactions`, for each new transaction.
+//!
+//! Payloads are JSON encoded, hashes, addresses and tags being tryte strings.
+
+mod broker;
+mod packet;
+
+use crate::plugin::{Plugin, PluginServerConfig};
+
+use broker::Broker;
+use packet::{is_valid_filter, Error as PacketError, Packet, PROTOCOL_LEVEL};
+
+use bee_api::{
+    trytes::{hash_to_trytes, trits_to_trytes},
+    types::{TransactionMetadataResponse, TransactionResponse},
+};
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+use bee_common_ext::event::Bus;
+use bee_crypto::ternary::Hash;
+use bee_ledger::event::MilestoneConfirmed;
+use bee_protocol::{
+    event::{
+        LastMilestoneChanged, LastSolidMilestoneChanged, NewTransaction, TransactionConfirmed, TransactionConflicting,
+        TransactionSolidified,
+    },
+    tangle::{tangle, TransactionMetadata},
+    Milestone,
+};
+use bee_tangle::traversal;
+use bee_transaction::bundled::BundledTransactionField;
+
+use async_std::{
+    future::timeout,
+    io::prelude::*,
+    net::{TcpListener, TcpStream},
+    task::spawn,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    select,
+    sink::SinkExt,
+    stream::StreamExt,
+};
+use log::{debug, error, info, warn};
+use serde_json::{json, Value};
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+
+pub(crate) const DEFAULT_BINDING_PORT: u16 = 1883;
+
+// Time a client has to send its CONNECT packet after opening the connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Number of packets read from a client ahead of them being handled; reading stops while it is reached.
+const PACKETS_CAPACITY: usize = 16;
+
+fn milestone(milestone: &Milestone) -> Value {
+    json!({
+        "index": *milestone.index(),
+        "hash": hash_to_trytes(milestone.hash()),
+    })
+}
+
+fn publish_metadata(broker: &Broker, hash: &Hash, metadata: &TransactionMetadata) {
+    broker.publish(&format!("transactions/{}/metadata", hash_to_trytes(hash)), || {
+        serde_json::to_value(TransactionMetadataResponse::new(hash, metadata)).unwrap_or_default()
+    });
+}
+
+fn add_listeners(bus: &Bus, broker: &Broker) {
+    let b = broker.clone();
+    bus.add_listener(move |event: &LastMilestoneChanged| b.publish("milestones/latest", || milestone(&event.0)));
+
+    let b = broker.clone();
+    bus.add_listener(move |event: &LastSolidMilestoneChanged| b.publish("milestones/solid", || milestone(&event.0)));
+
+    let b = broker.clone();
+    bus.add_listener(move |event: &MilestoneConfirmed| {
+        b.publish("milestones/confirmed", || {
+            json!({
+                "index": *event.milestone.index(),
+                "hash": hash_to_trytes(event.milestone.hash()),
+                "timestamp": event.timestamp,
+                "tailsReferenced": event.tails_referenced,
+                "tailsZeroValue": event.tails_zero_value,
+                "tailsConflicting": event.tails_conflicting,
+                "tailsIncluded": event.tails_included,
+            })
+        })
+    });
+
+    let b = broker.clone();
+    bus.add_listener(move |event: &NewTransaction| {
+        publish_metadata(&b, &event.hash, &event.metadata);
+
+        if let Some(transaction) = tangle().get(&event.hash) {
+            let response =
+                || serde_json::to_value(TransactionResponse::new(&event.hash, &transaction)).unwrap_or_default();
+
+            b.publish(
+                &format!(
+                    "addresses/{}/transactions",
+                    trits_to_trytes(transaction.address().to_inner())
+                ),
+                response,
+            );
+            b.publish(
+                &format!("tags/{}/transactions", trits_to_trytes(transaction.tag().to_inner())),
+                response,
+            );
+        }
+    });
+
+    let b = broker.clone();
+    bus.add_listener(move |event: &TransactionSolidified| publish_metadata(&b, &event.hash, &event.metadata));
+
+    let b = broker.clone();
+    bus.add_listener(move |event: &TransactionConflicting| publish_metadata(&b, &event.hash, &event.metadata));
+
+    // Only tails are confirmed, the rest of the bundle is reached by following the trunks.
+    let b = broker.clone();
+    bus.add_listener(move |event: &TransactionConfirmed| {
+        publish_metadata(&b, &event.hash, &event.metadata);
+
+        if !b.is_subscribed("transactions/confirmed") {
+            return;
+        }
+
+        let index = event.metadata.milestone_index();
+        let bundle = match tangle().get(&event.hash) {
+            Some(tail) => *tail.bundle(),
+            None => return,
+        };
+
+        traversal::visit_parents_follow_trunk(
+            tangle(),
+            event.hash,
+            |transaction, _| *transaction.bundle() == bundle,
+            |hash, _, _| {
+                b.publish("transactions/confirmed", || {
+                    json!({
+                        "hash": hash_to_trytes(hash),
+                        "milestoneIndex": *index,
+                    })
+                })
+            },
+        );
+    });
+}
+
+// A client is disconnected if it sends nothing for one and a half times its keep alive period, `0` disabling it.
+async fn read_packets(mut stream: TcpStream, keep_alive: u16, mut packets: mpsc::Sender<Packet>) {
+    let period = Duration::from_millis(u64::from(keep_alive) * 1500);
+
+    loop {
+        let packet = if keep_alive == 0 {
+            Packet::read(&mut stream).await
+        } else {
+            match timeout(period, Packet::read(&mut stream)).await {
+                Ok(packet) => packet,
+                Err(_) => {
+                    debug!("Keep alive of {:?} expired.", stream.peer_addr());
+                    break;
+                }
+            }
+        };
+
+        match packet {
+            Ok(packet) => {
+                if packets.send(packet).await.is_err() {
+                    break;
+                }
+            }
+            Err(e) => {
+                debug!("Reading packet from {:?} failed: {}", stream.peer_addr(), e);
+                break;
+            }
+        }
+    }
+}
+
+async fn serve_session(
+    stream: &mut TcpStream,
+    id: usize,
+    broker: &Broker,
+    mut packets: mpsc::Receiver<Packet>,
+    mut messages: mpsc::Receiver<Arc<Vec<u8>>>,
+) -> Result<(), PacketError> {
+    loop {
+        select! {
+            packet = packets.next() => match packet {
+                Some(Packet::Subscribe { packet_id, filters }) => {
+                    // Only QoS 0 is granted.
+                    let return_codes = filters
+                        .iter()
+                        .map(|(filter, _)| {
+                            if is_valid_filter(filter) {
+                                broker.subscribe(id, filter);
+                                0x00
+                            } else {
+                                0x80
+                            }
+                        })
+                        .collect();
+
+                    stream.write_all(&Packet::Suback { packet_id, return_codes }.encode()).await?;
+                }
+                Some(Packet::Unsubscribe { packet_id, filters }) => {
+                    for filter in filters {
+                        broker.unsubscribe(id, &filter);
+                    }
+
+                    stream.write_all(&Packet::Unsuback { packet_id }.encode()).await?;
+                }
+                Some(Packet::Pingreq) => stream.write_all(&Packet::Pingresp.encode()).await?,
+                // The broker is publish-only, messages published by clients are dropped.
+                Some(Packet::Publish { qos: 0, .. }) => (),
+                Some(Packet::Publish { qos: 1, packet_id: Some(packet_id), .. }) => {
+                    stream.write_all(&Packet::Puback { packet_id }.encode()).await?
+                }
+                Some(Packet::Disconnect) | None => return Ok(()),
+                Some(packet) => {
+                    debug!("Unexpected packet {:?}, disconnecting.", packet);
+                    return Ok(());
+                }
+            },
+            message = messages.next() => match message {
+                Some(message) => stream.write_all(&message).await?,
+                // Broker shutting down or session too slow.
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+async fn handle_session(mut stream: TcpStream, broker: Broker) -> Result<(), PacketError> {
+    let keep_alive = match timeout(CONNECT_TIMEOUT, Packet::read(&mut stream)).await {
+        Ok(Ok(Packet::Connect { level, keep_alive, .. })) => {
+            if level != PROTOCOL_LEVEL {
+                // Unacceptable protocol version.
+                stream
+                    .write_all(&Packet::Connack { return_code: 0x01 }.encode())
+                    .await?;
+                return Ok(());
+            }
+            keep_alive
+        }
+        // The first packet has to be a CONNECT.
+        _ => return Ok(()),
+    };
+
+    stream
+        .write_all(&Packet::Connack { return_code: 0x00 }.encode())
+        .await?;
+
+    let (id, messages) = broker.connect();
+    let (packets_tx, packets_rx) = mpsc::channel(PACKETS_CAPACITY);
+
+    spawn(read_packets(stream.clone(), keep_alive, packets_tx));
+
+    let result = serve_session(&mut stream, id, &broker, packets_rx, messages).await;
+
+    broker.disconnect(id);
+    // Also stops the reading task.
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+
+    result
+}
+
+async fn serve(listener: TcpListener, broker: Broker, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    let mut incoming = Box::pin(listener.incoming()).fuse();
+    let mut shutdown = shutdown.fuse();
+
+    loop {
+        select! {
+            stream = incoming.next() => match stream {
+                Some(Ok(stream)) => {
+                    let broker = broker.clone();
+
+                    spawn(async move {
+                        let address = stream.peer_addr();
+
+                        if let Err(e) = handle_session(stream, broker).await {
+                            debug!("Session of {:?} failed: {}.", address, e);
+                        }
+                    });
+                }
+                Some(Err(e)) => warn!("Accepting client failed: {}.", e),
+                None => break,
+            },
+            _ = shutdown => break,
+        }
+    }
+
+    broker.clear();
+
+    Ok(())
+}
+
+async fn run(address: SocketAddr, broker: Broker, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    match TcpListener::bind(address).await {
+        Ok(listener) => serve(listener, broker, shutdown).await?,
+        Err(e) => error!("Binding to {} failed: {}.", address, e),
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) struct MqttPlugin {
+    config: PluginServerConfig,
+}
+
+impl MqttPlugin {
+    pub(crate) fn new(config: PluginServerConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for MqttPlugin {
+    type Error = Infallible;
+
+    fn name(&self) -> &str {
+        "mqtt"
+    }
+
+    fn init(&mut self, bus: Arc<Bus>, shutdown: &mut Shutdown) -> Result<(), Self::Error> {
+        let broker = Broker::default();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        add_listeners(&bus, &broker);
+
+        info!("MQTT broker listening on {}.", self.config.socket_addr());
+
+        shutdown.add_worker_shutdown(shutdown_tx, spawn(run(self.config.socket_addr(), broker, shutdown_rx)));
+
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_protocol::MilestoneIndex;
+
+    use async_std::task::block_on;
+
+    async fn read(client: &mut TcpStream) -> Packet {
+        timeout(Duration::from_secs(5), Packet::read(client))
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn connect(address: SocketAddr, level: u8) -> (TcpStream, Packet) {
+        let mut client = TcpStream::connect(address).await.unwrap();
+        let connect = Packet::Connect {
+            level,
+            client_id: "test".to_owned(),
+            keep_alive: 60,
+        };
+
+        client.write_all(&connect.encode()).await.unwrap();
+        let connack = read(&mut client).await;
+
+        (client, connack)
+    }
+
+    #[test]
+    fn loopback() {
+        block_on(async {
+            let bus = Bus::default();
+            let broker = Broker::default();
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+            add_listeners(&bus, &broker);
+            let server = spawn(serve(listener, broker, shutdown_rx));
+
+            let (_, connack) = connect(address, 3).await;
+            assert_eq!(connack, Packet::Connack { return_code: 0x01 });
+
+            let (mut client, connack) = connect(address, PROTOCOL_LEVEL).await;
+            assert_eq!(connack, Packet::Connack { return_code: 0x00 });
+
+            let subscribe = Packet::Subscribe {
+                packet_id: 1,
+                filters: vec![("milestones/+".to_owned(), 1), ("milestones/#/x".to_owned(), 0)],
+            };
+            client.write_all(&subscribe.encode()).await.unwrap();
+            assert_eq!(
+                read(&mut client).await,
+                Packet::Suback {
+                    packet_id: 1,
+                    return_codes: vec![0x00, 0x80],
+                }
+            );
+
+            bus.dispatch(LastSolidMilestoneChanged(Milestone::new(
+                Hash::zeros(),
+                MilestoneIndex(42),
+            )));
+
+            match read(&mut client).await {
+                Packet::Publish {
+                    topic, qos, payload, ..
+                } => {
+                    let payload: Value = serde_json::from_slice(&payload).unwrap();
+
+                    assert_eq!(topic, "milestones/solid");
+                    assert_eq!(qos, 0);
+                    assert_eq!(payload["index"], 42);
+                }
+                packet => panic!("Unexpected packet {:?}.", packet),
+            }
+
+            client.write_all(&Packet::Pingreq.encode()).await.unwrap();
+            assert_eq!(read(&mut client).await, Packet::Pingresp);
+
+            shutdown_tx.send(()).unwrap();
+            server.await.unwrap();
+        });
+    }
+}
@@ -0,0 +1,482 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! The subset of MQTT 3.1.1 packets a publish-only broker needs, and their encoding.
+
+use async_std::io::{prelude::*, Read};
+use thiserror::Error;
+
+use std::io;
+
+const PROTOCOL_NAME: &str = "MQTT";
+pub(crate) const PROTOCOL_LEVEL: u8 = 4;
+// Clients only subscribe, their packets are small.
+const MAX_PACKET_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error("I/O error: {0}.")]
+    Io(#[from] io::Error),
+
+    #[error("Malformed remaining length.")]
+    MalformedLength,
+
+    #[error("Packet too large: {0} bytes.")]
+    TooLarge(usize),
+
+    #[error("Malformed packet.")]
+    Malformed,
+
+    #[error("Unsupported protocol: {0}.")]
+    UnsupportedProtocol(String),
+
+    #[error("Unsupported packet type: {0}.")]
+    UnsupportedPacket(u8),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Packet {
+    Connect {
+        level: u8,
+        client_id: String,
+        keep_alive: u16,
+    },
+    Connack {
+        return_code: u8,
+    },
+    Publish {
+        topic: String,
+        qos: u8,
+        packet_id: Option<u16>,
+        payload: Vec<u8>,
+    },
+    Puback {
+        packet_id: u16,
+    },
+    Subscribe {
+        packet_id: u16,
+        filters: Vec<(String, u8)>,
+    },
+    Suback {
+        packet_id: u16,
+        return_codes: Vec<u8>,
+    },
+    Unsubscribe {
+        packet_id: u16,
+        filters: Vec<String>,
+    },
+    Unsuback {
+        packet_id: u16,
+    },
+    Pingreq,
+    Pingresp,
+    Disconnect,
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::Malformed);
+        }
+
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes(2)?;
+
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let len = self.u16()? as usize;
+
+        String::from_utf8(self.bytes(len)?.to_vec()).map_err(|_| Error::Malformed)
+    }
+
+    fn rest(&mut self) -> Vec<u8> {
+        let rest = self.bytes.to_vec();
+        self.bytes = &[];
+
+        rest
+    }
+}
+
+fn encode_string(buffer: &mut Vec<u8>, string: &str) {
+    buffer.extend_from_slice(&(string.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(string.as_bytes());
+}
+
+impl Packet {
+    fn header(&self) -> u8 {
+        match self {
+            Packet::Connect { .. } => 0x10,
+            Packet::Connack { .. } => 0x20,
+            Packet::Publish { qos, .. } => 0x30 | (qos << 1),
+            Packet::Puback { .. } => 0x40,
+            Packet::Subscribe { .. } => 0x82,
+            Packet::Suback { .. } => 0x90,
+            Packet::Unsubscribe { .. } => 0xA2,
+            Packet::Unsuback { .. } => 0xB0,
+            Packet::Pingreq => 0xC0,
+            Packet::Pingresp => 0xD0,
+            Packet::Disconnect => 0xE0,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+
+        match self {
+            Packet::Connect {
+                level,
+                client_id,
+                keep_alive,
+            } => {
+                encode_string(&mut body, PROTOCOL_NAME);
+                body.push(*level);
+                // Clean session.
+                body.push(0x02);
+                body.extend_from_slice(&keep_alive.to_be_bytes());
+                encode_string(&mut body, client_id);
+            }
+            Packet::Connack { return_code } => {
+                // No session is ever present.
+                body.push(0x00);
+                body.push(*return_code);
+            }
+            Packet::Publish {
+                topic,
+                packet_id,
+                payload,
+                ..
+            } => {
+                encode_string(&mut body, topic);
+                if let Some(packet_id) = packet_id {
+                    body.extend_from_slice(&packet_id.to_be_bytes());
+                }
+                body.extend_from_slice(payload);
+            }
+            Packet::Puback { packet_id } | Packet::Unsuback { packet_id } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+            }
+            Packet::Subscribe { packet_id, filters } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                for (filter, qos) in filters {
+                    encode_string(&mut body, filter);
+                    body.push(*qos);
+                }
+            }
+            Packet::Suback {
+                packet_id,
+                return_codes,
+            } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                body.extend_from_slice(return_codes);
+            }
+            Packet::Unsubscribe { packet_id, filters } => {
+                body.extend_from_slice(&packet_id.to_be_bytes());
+                for filter in filters {
+                    encode_string(&mut body, filter);
+                }
+            }
+            Packet::Pingreq | Packet::Pingresp | Packet::Disconnect => (),
+        }
+
+        let mut packet = vec![self.header()];
+        let mut len = body.len();
+
+        loop {
+            let mut byte = (len % 128) as u8;
+            len /= 128;
+            if len > 0 {
+                byte |= 0x80;
+            }
+            packet.push(byte);
+            if len == 0 {
+                break;
+            }
+        }
+
+        packet.extend_from_slice(&body);
+        packet
+    }
+
+    pub(crate) fn decode(header: u8, body: &[u8]) -> Result<Self, Error> {
+        let mut decoder = Decoder { bytes: body };
+
+        let packet = match header >> 4 {
+            1 => {
+                let protocol = decoder.string()?;
+                if protocol != PROTOCOL_NAME {
+                    return Err(Error::UnsupportedProtocol(protocol));
+                }
+                let level = decoder.u8()?;
+                let _flags = decoder.u8()?;
+                let keep_alive = decoder.u16()?;
+                let client_id = decoder.string()?;
+                // Will, username and password are not supported and ignored.
+                decoder.rest();
+
+                Packet::Connect {
+                    level,
+                    client_id,
+                    keep_alive,
+                }
+            }
+            2 => {
+                let _session_present = decoder.u8()?;
+
+                Packet::Connack {
+                    return_code: decoder.u8()?,
+                }
+            }
+            3 => {
+                let qos = (header >> 1) & 0x03;
+                let topic = decoder.string()?;
+                let packet_id = if qos > 0 { Some(decoder.u16()?) } else { None };
+
+                Packet::Publish {
+                    topic,
+                    qos,
+                    packet_id,
+                    payload: decoder.rest(),
+                }
+            }
+            4 => Packet::Puback {
+                packet_id: decoder.u16()?,
+            },
+            8 => {
+                let packet_id = decoder.u16()?;
+                let mut filters = Vec::new();
+
+                while !decoder.is_empty() {
+                    filters.push((decoder.string()?, decoder.u8()?));
+                }
+                if filters.is_empty() {
+                    return Err(Error::Malformed);
+                }
+
+                Packet::Subscribe { packet_id, filters }
+            }
+            9 => Packet::Suback {
+                packet_id: decoder.u16()?,
+                return_codes: decoder.rest(),
+            },
+            10 => {
+                let packet_id = decoder.u16()?;
+                let mut filters = Vec::new();
+
+                while !decoder.is_empty() {
+                    filters.push(decoder.string()?);
+                }
+                if filters.is_empty() {
+                    return Err(Error::Malformed);
+                }
+
+                Packet::Unsubscribe { packet_id, filters }
+            }
+            11 => Packet::Unsuback {
+                packet_id: decoder.u16()?,
+            },
+            12 => Packet::Pingreq,
+            13 => Packet::Pingresp,
+            14 => Packet::Disconnect,
+            packet_type => return Err(Error::UnsupportedPacket(packet_type)),
+        };
+
+        if !decoder.is_empty() {
+            return Err(Error::Malformed);
+        }
+
+        Ok(packet)
+    }
+
+    pub(crate) async fn read<R: Read + Unpin>(reader: &mut R) -> Result<Self, Error> {
+        let mut byte = [0u8; 1];
+
+        reader.read_exact(&mut byte).await?;
+        let header = byte[0];
+
+        let mut len = 0usize;
+        let mut shift = 0;
+
+        loop {
+            reader.read_exact(&mut byte).await?;
+            len |= usize::from(byte[0] & 0x7F) << shift;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+            if shift == 28 {
+                return Err(Error::MalformedLength);
+            }
+        }
+
+        if len > MAX_PACKET_SIZE {
+            return Err(Error::TooLarge(len));
+        }
+
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body).await?;
+
+        Packet::decode(header, &body)
+    }
+}
+
+/// Whether a topic filter is valid: `+` must occupy a whole level and `#` must be the whole last level.
+pub(crate) fn is_valid_filter(filter: &str) -> bool {
+    let levels = filter.split('/').collect::<Vec<&str>>();
+
+    !filter.is_empty()
+        && levels.iter().enumerate().all(|(i, level)| {
+            (*level == "#" && i == levels.len() - 1) || *level == "+" || (!level.contains('#') && !level.contains('+'))
+        })
+}
+
+/// Whether a topic matches a valid topic filter.
+pub(crate) fn matches(filter: &str, topic: &str) -> bool {
+    let mut topic_levels = topic.split('/');
+
+    for filter_level in filter.split('/') {
+        match (filter_level, topic_levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => (),
+            (filter_level, Some(topic_level)) if filter_level == topic_level => (),
+            _ => return false,
+        }
+    }
+
+    topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_std::task::block_on;
+
+    fn decode(bytes: &[u8]) -> Result<Packet, Error> {
+        block_on(Packet::read(&mut &bytes[..]))
+    }
+
+    fn roundtrip(packet: Packet) {
+        assert_eq!(decode(&packet.encode()).unwrap(), packet);
+    }
+
+    #[test]
+    fn roundtrip_all() {
+        roundtrip(Packet::Connect {
+            level: PROTOCOL_LEVEL,
+            client_id: "bee".to_owned(),
+            keep_alive: 60,
+        });
+        roundtrip(Packet::Connack { return_code: 0 });
+        roundtrip(Packet::Publish {
+            topic: "milestones/latest".to_owned(),
+            qos: 0,
+            packet_id: None,
+            payload: vec![1, 2, 3],
+        });
+        roundtrip(Packet::Publish {
+            topic: "a/b".to_owned(),
+            qos: 1,
+            packet_id: Some(7),
+            payload: vec![0; 300],
+        });
+        roundtrip(Packet::Puback { packet_id: 7 });
+        roundtrip(Packet::Subscribe {
+            packet_id: 1,
+            filters: vec![("milestones/+".to_owned(), 0), ("#".to_owned(), 1)],
+        });
+        roundtrip(Packet::Suback {
+            packet_id: 1,
+            return_codes: vec![0, 0x80],
+        });
+        roundtrip(Packet::Unsubscribe {
+            packet_id: 2,
+            filters: vec!["milestones/+".to_owned()],
+        });
+        roundtrip(Packet::Unsuback { packet_id: 2 });
+        roundtrip(Packet::Pingreq);
+        roundtrip(Packet::Pingresp);
+        roundtrip(Packet::Disconnect);
+    }
+
+    #[test]
+    fn remaining_length() {
+        let packet = Packet::Publish {
+            topic: "t".to_owned(),
+            qos: 0,
+            packet_id: None,
+            payload: vec![0; 200],
+        };
+
+        // 3 bytes of topic and 200 of payload, 203 = 0x4B + 1 * 128.
+        assert_eq!(packet.encode()[1..3], [0xCB, 0x01]);
+    }
+
+    #[test]
+    fn too_large() {
+        assert!(matches!(decode(&[0x30, 0xFF, 0xFF, 0x7F]), Err(Error::TooLarge(_))));
+    }
+
+    #[test]
+    fn unsupported_protocol() {
+        let mut bytes = Packet::Connect {
+            level: PROTOCOL_LEVEL,
+            client_id: "bee".to_owned(),
+            keep_alive: 60,
+        }
+        .encode();
+        bytes[4..8].copy_from_slice(b"MQTX");
+
+        assert!(matches!(decode(&bytes), Err(Error::UnsupportedProtocol(_))));
+    }
+
+    #[test]
+    fn filters() {
+        assert!(is_valid_filter("milestones/latest"));
+        assert!(is_valid_filter("addresses/+/transactions"));
+        assert!(is_valid_filter("#"));
+        assert!(is_valid_filter("transactions/#"));
+        assert!(!is_valid_filter(""));
+        assert!(!is_valid_filter("transactions/#/metadata"));
+        assert!(!is_valid_filter("milestones/lat+"));
+    }
+
+    #[test]
+    fn matching() {
+        assert!(matches("milestones/latest", "milestones/latest"));
+        assert!(matches("milestones/+", "milestones/solid"));
+        assert!(matches("addresses/+/transactions", "addresses/ABC/transactions"));
+        assert!(matches("transactions/#", "transactions/ABC/metadata"));
+        assert!(matches("#", "milestones/latest"));
+        assert!(!matches("milestones/+", "milestones/latest/extra"));
+        assert!(!matches("milestones/latest/+", "milestones/latest"));
+        assert!(!matches("tags/+/transactions", "addresses/ABC/transactions"));
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Encoding of the metrics in the Prometheus text exposition format.
+
+use bee_protocol::{PeerMetrics, ProtocolMetrics};
+
+use std::fmt::Write;
+
+const NAMESPACE: &str = "bee";
+
+type Counter<M> = (&'static str, &'static str, fn(&M) -> u64);
+
+// Counters both collected globally and per peer.
+macro_rules! shared_counters {
+    ($metrics:ty) => {
+        [
+            (
+                "invalid_transactions",
+                "Number of invalid transactions received.",
+                <$metrics>::invalid_transactions,
+            ),
+            (
+                "stale_transactions",
+                "Number of stale transactions received.",
+                <$metrics>::stale_transactions,
+            ),
+            (
+                "new_transactions",
+                "Number of new transactions received.",
+                <$metrics>::new_transactions,
+            ),
+            (
+                "known_transactions",
+                "Number of already known transactions received.",
+                <$metrics>::known_transactions,
+            ),
+            (
+                "invalid_messages",
+                "Number of invalid messages received.",
+                <$metrics>::invalid_messages,
+            ),
+            (
+                "milestone_requests_received",
+                "Number of milestone requests received.",
+                <$metrics>::milestone_requests_received,
+            ),
+            (
+                "transactions_received",
+                "Number of transactions received.",
+                <$metrics>::transactions_received,
+            ),
+            (
+                "transaction_requests_received",
+                "Number of transaction requests received.",
+                <$metrics>::transaction_requests_received,
+            ),
+            (
+                "heartbeats_received",
+                "Number of heartbeats received.",
+                <$metrics>::heartbeats_received,
+            ),
+            (
+                "milestone_requests_sent",
+                "Number of milestone requests sent.",
+                <$metrics>::milestone_requests_sent,
+            ),
+            (
+                "transactions_sent",
+                "Number of transactions sent.",
+                <$metrics>::transactions_sent,
+            ),
+            (
+                "transaction_requests_sent",
+                "Number of transaction requests sent.",
+                <$metrics>::transaction_requests_sent,
+            ),
+            (
+                "heartbeats_sent",
+                "Number of heartbeats sent.",
+                <$metrics>::heartbeats_sent,
+            ),
+        ]
+    };
+}
+
+const PROTOCOL_COUNTERS: [Counter<ProtocolMetrics>; 8] = [
+    (
+        "value_transactions",
+        "Number of value transactions.",
+        ProtocolMetrics::value_transactions,
+    ),
+    (
+        "non_value_transactions",
+        "Number of non value transactions.",
+        ProtocolMetrics::non_value_transactions,
+    ),
+    (
+        "confirmed_transactions",
+        "Number of confirmed transactions.",
+        ProtocolMetrics::confirmed_transactions,
+    ),
+    (
+        "conflicting_transactions",
+        "Number of conflicting transactions.",
+        ProtocolMetrics::conflicting_transactions,
+    ),
+    (
+        "transaction_requests_answered",
+        "Number of transaction requests answered.",
+        ProtocolMetrics::transaction_requests_answered,
+    ),
+    (
+        "transaction_requests_retried",
+        "Number of transaction requests retried after a timeout.",
+        ProtocolMetrics::transaction_requests_retried,
+    ),
+    (
+        "transaction_requests_abandoned",
+        "Number of transaction requests abandoned after too many retries.",
+        ProtocolMetrics::transaction_requests_abandoned,
+    ),
+    (
+        "transaction_request_latency_milliseconds",
+        "Cumulated latency of the answered transaction requests, in milliseconds.",
+        ProtocolMetrics::transaction_request_latency,
+    ),
+];
+
+const SHARED_PROTOCOL_COUNTERS: [Counter<ProtocolMetrics>; 13] = shared_counters!(ProtocolMetrics);
+const SHARED_PEER_COUNTERS: [Counter<PeerMetrics>; 13] = shared_counters!(PeerMetrics);
+
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', r#"\""#).replace('\n', r"\n")
+}
+
+/// Reads the counters of a peer, as `Exposition::peers` expects them.
+pub(crate) fn peer_counters(metrics: &PeerMetrics) -> Vec<u64> {
+    SHARED_PEER_COUNTERS
+        .iter()
+        .map(|(_, _, counter)| counter(metrics))
+        .collect()
+}
+
+/// A Prometheus text exposition being built, metric families having to be written in one go.
+#[derive(Default)]
+pub(crate) struct Exposition(String);
+
+impl Exposition {
+    fn family(&mut self, name: &str, help: &str, kind: &str) {
+        // Writing to a `String` can not fail.
+        let _ = writeln!(self.0, "# HELP {}_{} {}", NAMESPACE, name, help);
+        let _ = writeln!(self.0, "# TYPE {}_{} {}", NAMESPACE, name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        let _ = write!(self.0, "{}_{}", NAMESPACE, name);
+
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape(value)))
+                .collect::<Vec<String>>()
+                .join(",");
+            let _ = write!(self.0, "{{{}}}", labels);
+        }
+
+        let _ = writeln!(self.0, " {}", value);
+    }
+
+    pub(crate) fn gauge(&mut self, name: &str, help: &str, value: u64) {
+        self.family(name, help, "gauge");
+        self.sample(name, &[], value);
+    }
+
+    pub(crate) fn protocol(&mut self, metrics: &ProtocolMetrics) {
+        for (name, help, counter) in SHARED_PROTOCOL_COUNTERS.iter().chain(PROTOCOL_COUNTERS.iter()) {
+            let name = format!("{}_total", name);
+
+            self.family(&name, help, "counter");
+            self.sample(&name, &[], counter(metrics));
+        }
+    }
+
+    /// Peers are labelled by their address, their counters being read with `peer_counters`.
+    pub(crate) fn peers(&mut self, peers: &[(String, Vec<u64>)]) {
+        for (i, (name, help, _)) in SHARED_PEER_COUNTERS.iter().enumerate() {
+            let name = format!("peer_{}_total", name);
+
+            self.family(&name, help, "counter");
+            for (address, counters) in peers {
+                self.sample(&name, &[("address", address)], counters[i]);
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gauge() {
+        let mut exposition = Exposition::default();
+
+        exposition.gauge("tips", "Number of tips.", 42);
+
+        assert_eq!(
+            exposition.finish(),
+            "# HELP bee_tips Number of tips.\n# TYPE bee_tips gauge\nbee_tips 42\n"
+        );
+    }
+
+    #[test]
+    fn protocol_counters() {
+        let mut exposition = Exposition::default();
+
+        exposition.protocol(&ProtocolMetrics::new());
+        let exposition = exposition.finish();
+
+        assert!(exposition.contains("# TYPE bee_new_transactions_total counter\nbee_new_transactions_total 0\n"));
+        assert!(exposition.contains("bee_conflicting_transactions_total 0\n"));
+        assert_eq!(exposition.matches("# TYPE").count(), 21);
+    }
+
+    #[test]
+    fn peer_counters() {
+        let counters = peer_counters(&PeerMetrics::new());
+        let mut exposition = Exposition::default();
+
+        exposition.peers(&[
+            ("127.0.0.1:15600".to_owned(), counters.clone()),
+            ("weird\"address".to_owned(), counters),
+        ]);
+        let exposition = exposition.finish();
+
+        assert!(exposition.contains("bee_peer_heartbeats_sent_total{address=\"127.0.0.1:15600\"} 0\n"));
+        assert!(exposition.contains("bee_peer_heartbeats_sent_total{address=\"weird\\\"address\"} 0\n"));
+        assert_eq!(exposition.matches("# TYPE").count(), 13);
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Exports the protocol, peer and tangle metrics on `/metrics`, in the Prometheus text exposition format.
+
+mod exposition;
+
+use crate::plugin::{Plugin, PluginServerConfig};
+
+use exposition::{peer_counters, Exposition};
+
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+use bee_common_ext::event::Bus;
+use bee_protocol::{tangle::tangle, Protocol};
+
+use async_std::task::spawn;
+use futures::{
+    channel::oneshot,
+    future::{select, Either},
+};
+use log::{error, info};
+use tide::{Request, Response, Server, StatusCode};
+
+use std::{convert::Infallible, sync::Arc};
+
+pub(crate) const DEFAULT_BINDING_PORT: u16 = 9311;
+
+fn metrics() -> String {
+    let mut exposition = Exposition::default();
+    let mut peers = Vec::new();
+
+    exposition.protocol(Protocol::metrics());
+
+    Protocol::for_each_handshaked_peer(|address, metrics| {
+        peers.push((address.to_string(), peer_counters(metrics)));
+    });
+    exposition.peers(&peers);
+
+    exposition.gauge(
+        "tangle_transactions",
+        "Number of transactions in the tangle.",
+        tangle().len() as u64,
+    );
+    exposition.gauge(
+        "tangle_tips",
+        "Number of tips of the tangle.",
+        tangle().num_tips() as u64,
+    );
+    exposition.gauge(
+        "requested_transactions",
+        "Number of transactions requested and not yet received.",
+        Protocol::num_requested_transactions() as u64,
+    );
+    exposition.gauge(
+        "handshaked_peers",
+        "Number of handshaked peers.",
+        Protocol::num_handshaked_peers() as u64,
+    );
+    exposition.gauge(
+        "snapshot_milestone_index",
+        "Index of the milestone of the snapshot the node started from.",
+        u64::from(*tangle().get_snapshot_milestone_index()),
+    );
+    exposition.gauge(
+        "last_solid_milestone_index",
+        "Index of the last solid milestone.",
+        u64::from(*tangle().get_last_solid_milestone_index()),
+    );
+    exposition.gauge(
+        "last_milestone_index",
+        "Index of the last known milestone.",
+        u64::from(*tangle().get_last_milestone_index()),
+    );
+
+    exposition.finish()
+}
+
+async fn handle(_: Request<()>) -> tide::Result {
+    let mut response = Response::new(StatusCode::Ok);
+
+    response.set_body(metrics());
+    response.insert_header("Content-Type", "text/plain; version=0.0.4");
+
+    Ok(response)
+}
+
+async fn run(server: Server<()>, address: String, shutdown: oneshot::Receiver<()>) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    if let Either::Left((Err(e), _)) = select(Box::pin(server.listen(address.clone())), shutdown).await {
+        error!("Listening on {} failed: {}.", address, e);
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) struct PrometheusPlugin {
+    config: PluginServerConfig,
+}
+
+impl PrometheusPlugin {
+    pub(crate) fn new(config: PluginServerConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for PrometheusPlugin {
+    type Error = Infallible;
+
+    fn name(&self) -> &str {
+        "prometheus"
+    }
+
+    fn init(&mut self, _: Arc<Bus>, shutdown: &mut Shutdown) -> Result<(), Self::Error> {
+        let mut server = tide::new();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        server.at("/metrics").get(handle);
+
+        info!("Exporting metrics on {}.", self.config.socket_addr());
+
+        shutdown.add_worker_shutdown(
+            shutdown_tx,
+            spawn(run(server, self.config.socket_addr().to_string(), shutdown_rx)),
+        );
+
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Messages of the IRI ZMQ topics, fields being separated by spaces.
+
+use bee_api::trytes::{hash_to_trytes, transaction_to_trytes, trits_to_trytes};
+use bee_crypto::ternary::Hash;
+use bee_protocol::MilestoneIndex;
+use bee_transaction::{
+    bundled::{BundledTransaction as Transaction, BundledTransactionField},
+    Vertex,
+};
+
+/// `tx <hash> <address> <value> <obsolete tag> <timestamp> <index> <last index> <bundle> <trunk> <branch> <arrival
+/// time> <tag>`, for each new transaction, the arrival time being in seconds.
+pub(crate) fn tx(hash: &Hash, transaction: &Transaction, arrival_timestamp: u64) -> String {
+    format!(
+        "tx {} {} {} {} {} {} {} {} {} {} {} {}",
+        hash_to_trytes(hash),
+        trits_to_trytes(transaction.address().to_inner()),
+        transaction.value().to_inner(),
+        trits_to_trytes(transaction.obsolete_tag().to_inner()),
+        transaction.timestamp().to_inner(),
+        transaction.index().to_inner(),
+        transaction.last_index().to_inner(),
+        hash_to_trytes(transaction.bundle()),
+        hash_to_trytes(transaction.trunk()),
+        hash_to_trytes(transaction.branch()),
+        arrival_timestamp / 1000,
+        trits_to_trytes(transaction.tag().to_inner()),
+    )
+}
+
+/// `tx_trytes <trytes> <hash>`, for each new transaction.
+pub(crate) fn tx_trytes(hash: &Hash, transaction: &Transaction) -> String {
+    format!(
+        "tx_trytes {} {}",
+        transaction_to_trytes(transaction),
+        hash_to_trytes(hash)
+    )
+}
+
+/// `sn <milestone index> <hash> <address> <trunk> <branch> <bundle>`, for each confirmed transaction.
+pub(crate) fn sn(index: MilestoneIndex, hash: &Hash, transaction: &Transaction) -> String {
+    format!(
+        "sn {} {} {} {} {} {}",
+        *index,
+        hash_to_trytes(hash),
+        trits_to_trytes(transaction.address().to_inner()),
+        hash_to_trytes(transaction.trunk()),
+        hash_to_trytes(transaction.branch()),
+        hash_to_trytes(transaction.bundle()),
+    )
+}
+
+/// `<address> <hash> <milestone index>`, for each confirmed transaction.
+pub(crate) fn address(index: MilestoneIndex, hash: &Hash, transaction: &Transaction) -> String {
+    format!(
+        "{} {} {}",
+        trits_to_trytes(transaction.address().to_inner()),
+        hash_to_trytes(hash),
+        *index
+    )
+}
+
+/// `lmi <previous index> <index>`, for each change of the last milestone.
+pub(crate) fn lmi(previous: MilestoneIndex, index: MilestoneIndex) -> String {
+    format!("lmi {} {}", *previous, *index)
+}
+
+/// `lmsi <previous index> <index>`, for each change of the last solid milestone.
+pub(crate) fn lmsi(previous: MilestoneIndex, index: MilestoneIndex) -> String {
+    format!("lmsi {} {}", *previous, *index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_test::transaction::create_random_tx;
+
+    #[test]
+    fn tx_fields() {
+        let (hash, transaction) = create_random_tx();
+        let message = tx(&hash, &transaction, 1_500_000);
+        let fields = message.split(' ').collect::<Vec<&str>>();
+
+        assert_eq!(fields.len(), 13);
+        assert_eq!(fields[0], "tx");
+        assert_eq!(fields[1], hash_to_trytes(&hash));
+        assert_eq!(fields[8], hash_to_trytes(transaction.bundle()));
+        assert_eq!(fields[11], "1500");
+    }
+
+    #[test]
+    fn tx_trytes_fields() {
+        let (hash, transaction) = create_random_tx();
+
+        assert_eq!(
+            tx_trytes(&hash, &transaction),
+            format!(
+                "tx_trytes {} {}",
+                transaction_to_trytes(&transaction),
+                hash_to_trytes(&hash)
+            )
+        );
+    }
+
+    #[test]
+    fn sn_fields() {
+        let (hash, transaction) = create_random_tx();
+        let message = sn(MilestoneIndex(42), &hash, &transaction);
+        let fields = message.split(' ').collect::<Vec<&str>>();
+
+        assert_eq!(fields.len(), 7);
+        assert_eq!(fields[..3], ["sn", "42", hash_to_trytes(&hash).as_str()]);
+    }
+
+    #[test]
+    fn address_topic() {
+        let (hash, transaction) = create_random_tx();
+        let message = address(MilestoneIndex(42), &hash, &transaction);
+
+        assert!(message.starts_with(&trits_to_trytes(transaction.address().to_inner())));
+        assert!(message.ends_with(" 42"));
+    }
+
+    #[test]
+    fn milestone_indexes() {
+        assert_eq!(lmi(MilestoneIndex(1), MilestoneIndex(2)), "lmi 1 2");
+        assert_eq!(lmsi(MilestoneIndex(1), MilestoneIndex(2)), "lmsi 1 2");
+    }
+}
@@ -0,0 +1,352 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Publishes the classic IRI ZMQ topics over a plain TCP line protocol.
+//!
+//! Clients send `sub <prefix>` and `unsub <prefix>` lines and receive, one per line, every message starting with one
+//! of their subscriptions. Topics are `tx`, `tx_trytes`, `sn`, `lmi`, `lmsi` and the 81 trytes of an address, see the
+//! `message` module for their formats.
+
+mod message;
+mod subscriptions;
+
+use crate::plugin::{Plugin, PluginServerConfig};
+
+use subscriptions::Subscriptions;
+
+use bee_api::trytes::trits_to_trytes;
+use bee_common::{shutdown::Shutdown, worker::Error as WorkerError};
+use bee_common_ext::event::Bus;
+use bee_protocol::{
+    event::{LastMilestoneChanged, LastSolidMilestoneChanged, NewTransaction, TransactionConfirmed},
+    tangle::tangle,
+    MilestoneIndex,
+};
+use bee_tangle::traversal;
+use bee_transaction::bundled::BundledTransactionField;
+
+use async_std::{
+    io::{prelude::*, BufReader},
+    net::{TcpListener, TcpStream},
+    task::spawn,
+};
+use futures::{
+    channel::oneshot,
+    select,
+    stream::{self, Stream, StreamExt},
+};
+use log::{debug, error, info, warn};
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+pub(crate) const DEFAULT_BINDING_PORT: u16 = 5556;
+
+// Longest line accepted from a client, enough for `unsub ` followed by the trytes of an address.
+const MAX_LINE_LENGTH: usize = 128;
+
+fn add_listeners(
+    bus: &Bus,
+    subscriptions: &Subscriptions,
+    last_milestone_index: MilestoneIndex,
+    last_solid_milestone_index: MilestoneIndex,
+) {
+    let s = subscriptions.clone();
+    bus.add_listener(move |event: &NewTransaction| {
+        let (tx, tx_trytes) = (s.is_subscribed("tx"), s.is_subscribed("tx_trytes"));
+
+        if !tx && !tx_trytes {
+            return;
+        }
+
+        if let Some(transaction) = tangle().get(&event.hash) {
+            if tx {
+                s.publish(message::tx(
+                    &event.hash,
+                    &transaction,
+                    event.metadata.arrival_timestamp(),
+                ));
+            }
+            if tx_trytes {
+                s.publish(message::tx_trytes(&event.hash, &transaction));
+            }
+        }
+    });
+
+    // Only tails are confirmed, the rest of the bundle is reached by following the trunks.
+    let s = subscriptions.clone();
+    bus.add_listener(move |event: &TransactionConfirmed| {
+        let index = event.metadata.milestone_index();
+        let bundle = match tangle().get(&event.hash) {
+            Some(tail) => *tail.bundle(),
+            None => return,
+        };
+
+        traversal::visit_parents_follow_trunk(
+            tangle(),
+            event.hash,
+            |transaction, _| *transaction.bundle() == bundle,
+            |hash, transaction, _| {
+                if s.is_subscribed("sn") {
+                    s.publish(message::sn(index, hash, transaction));
+                }
+                if s.is_subscribed(&trits_to_trytes(transaction.address().to_inner())) {
+                    s.publish(message::address(index, hash, transaction));
+                }
+            },
+        );
+    });
+
+    let s = subscriptions.clone();
+    let previous = AtomicU32::new(*last_milestone_index);
+    bus.add_listener(move |event: &LastMilestoneChanged| {
+        let previous = previous.swap(*event.0.index(), Ordering::Relaxed);
+        s.publish(message::lmi(MilestoneIndex(previous), event.0.index()));
+    });
+
+    let s = subscriptions.clone();
+    let previous = AtomicU32::new(*last_solid_milestone_index);
+    bus.add_listener(move |event: &LastSolidMilestoneChanged| {
+        let previous = previous.swap(*event.0.index(), Ordering::Relaxed);
+        s.publish(message::lmsi(MilestoneIndex(previous), event.0.index()));
+    });
+}
+
+/// Reads the lines sent by a client, ending on the first line longer than `MAX_LINE_LENGTH` bytes or not valid UTF-8.
+fn lines(stream: TcpStream) -> impl Stream<Item = String> {
+    stream::unfold(BufReader::new(stream), |mut reader| async move {
+        let mut line = Vec::new();
+
+        match (&mut reader)
+            .take(MAX_LINE_LENGTH as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .await
+        {
+            Ok(0) | Err(_) => None,
+            Ok(_) if line.len() > MAX_LINE_LENGTH && line.last() != Some(&b'\n') => {
+                warn!(
+                    "Disconnecting client sending lines longer than {} bytes.",
+                    MAX_LINE_LENGTH
+                );
+                None
+            }
+            Ok(_) => String::from_utf8(line).ok().map(|line| (line, reader)),
+        }
+    })
+}
+
+async fn handle_client(stream: TcpStream, subscriptions: Subscriptions) {
+    let (id, mut messages) = subscriptions.connect();
+    let mut lines = Box::pin(lines(stream.clone())).fuse();
+    let mut writer = stream;
+
+    loop {
+        select! {
+            line = lines.next() => match line {
+                Some(line) => {
+                    let line = line.trim_end();
+                    let mut parts = line.splitn(2, ' ');
+
+                    match (parts.next(), parts.next().unwrap_or_default()) {
+                        (Some("sub"), prefix) => {
+                            if !subscriptions.subscribe(id, prefix) {
+                                warn!("Ignoring subscription \"{}\", too many subscriptions.", prefix);
+                            }
+                        }
+                        (Some("unsub"), prefix) => subscriptions.unsubscribe(id, prefix),
+                        _ => warn!("Ignoring invalid command \"{}\".", line),
+                    }
+                }
+                None => break,
+            },
+            message = messages.next() => match message {
+                Some(message) => {
+                    if writer.write_all(format!("{}\n", message).as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+                // Publisher shutting down or client too slow.
+                None => break,
+            },
+        }
+    }
+
+    subscriptions.disconnect(id);
+}
+
+async fn serve(
+    listener: TcpListener,
+    subscriptions: Subscriptions,
+    shutdown: oneshot::Receiver<()>,
+) -> Result<(), WorkerError> {
+    let mut incoming = Box::pin(listener.incoming()).fuse();
+    let mut shutdown = shutdown.fuse();
+
+    loop {
+        select! {
+            stream = incoming.next() => match stream {
+                Some(Ok(stream)) => {
+                    debug!("Client {:?} connected.", stream.peer_addr());
+                    spawn(handle_client(stream, subscriptions.clone()));
+                }
+                Some(Err(e)) => warn!("Accepting client failed: {}.", e),
+                None => break,
+            },
+            _ = shutdown => break,
+        }
+    }
+
+    subscriptions.clear();
+
+    Ok(())
+}
+
+async fn run(
+    address: SocketAddr,
+    subscriptions: Subscriptions,
+    shutdown: oneshot::Receiver<()>,
+) -> Result<(), WorkerError> {
+    info!("Running.");
+
+    match TcpListener::bind(address).await {
+        Ok(listener) => serve(listener, subscriptions, shutdown).await?,
+        Err(e) => error!("Binding to {} failed: {}.", address, e),
+    }
+
+    info!("Stopped.");
+
+    Ok(())
+}
+
+pub(crate) struct PublisherPlugin {
+    config: PluginServerConfig,
+}
+
+impl PublisherPlugin {
+    pub(crate) fn new(config: PluginServerConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for PublisherPlugin {
+    type Error = Infallible;
+
+    fn name(&self) -> &str {
+        "publisher"
+    }
+
+    fn init(&mut self, bus: Arc<Bus>, shutdown: &mut Shutdown) -> Result<(), Self::Error> {
+        let subscriptions = Subscriptions::default();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        add_listeners(
+            &bus,
+            &subscriptions,
+            tangle().get_last_milestone_index(),
+            tangle().get_last_solid_milestone_index(),
+        );
+
+        info!("Publishing on {}.", self.config.socket_addr());
+
+        shutdown.add_worker_shutdown(
+            shutdown_tx,
+            spawn(run(self.config.socket_addr(), subscriptions, shutdown_rx)),
+        );
+
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_crypto::ternary::Hash;
+    use bee_protocol::Milestone;
+
+    use async_std::{future::timeout, task::block_on};
+
+    use std::time::Duration;
+
+    #[test]
+    fn loopback() {
+        block_on(async {
+            let bus = Bus::default();
+            let subscriptions = Subscriptions::default();
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+            add_listeners(&bus, &subscriptions, MilestoneIndex(1), MilestoneIndex(1));
+            let server = spawn(serve(listener, subscriptions.clone(), shutdown_rx));
+
+            let mut client = TcpStream::connect(address).await.unwrap();
+            client.write_all(b"sub lmi\n").await.unwrap();
+
+            // Subscribing is asynchronous, there is no acknowledgement.
+            while !subscriptions.is_subscribed("lmi") {
+                async_std::task::sleep(Duration::from_millis(10)).await;
+            }
+
+            bus.dispatch(LastSolidMilestoneChanged(Milestone::new(
+                Hash::zeros(),
+                MilestoneIndex(2),
+            )));
+            bus.dispatch(LastMilestoneChanged(Milestone::new(Hash::zeros(), MilestoneIndex(2))));
+            bus.dispatch(LastMilestoneChanged(Milestone::new(Hash::zeros(), MilestoneIndex(3))));
+
+            let mut lines = BufReader::new(client).lines();
+
+            for expected in &["lmi 1 2", "lmi 2 3"] {
+                let line = timeout(Duration::from_secs(5), lines.next()).await.unwrap();
+                assert_eq!(line.unwrap().unwrap(), *expected);
+            }
+
+            shutdown_tx.send(()).unwrap();
+            server.await.unwrap();
+        });
+    }
+
+    #[test]
+    fn disconnect_long_lines() {
+        block_on(async {
+            let subscriptions = Subscriptions::default();
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+            let server = spawn(serve(listener, subscriptions.clone(), shutdown_rx));
+
+            // One byte too long, without a newline; the server reads it all so that closing doesn't reset the connection.
+            let prefix = "9".repeat(MAX_LINE_LENGTH - 3);
+            let mut client = TcpStream::connect(address).await.unwrap();
+            client.write_all(format!("sub {}", prefix).as_bytes()).await.unwrap();
+
+            let mut buffer = Vec::new();
+            let read = timeout(Duration::from_secs(5), client.read_to_end(&mut buffer)).await;
+            assert_eq!(read.unwrap().unwrap(), 0);
+            assert!(!subscriptions.is_subscribed(&prefix));
+
+            shutdown_tx.send(()).unwrap();
+            server.await.unwrap();
+        });
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use futures::channel::mpsc;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+// Number of messages a client can lag behind before it is disconnected.
+const CLIENT_CAPACITY: usize = 256;
+// Number of prefixes a client can be subscribed to at once.
+const MAX_CLIENT_PREFIXES: usize = 64;
+
+struct Client {
+    prefixes: HashSet<String>,
+    sender: mpsc::Sender<Arc<String>>,
+}
+
+/// Connected clients and their subscriptions.
+///
+/// As with ZMQ, a subscription is a prefix and a client receives every message starting with one of its
+/// subscriptions, the empty subscription matching all messages. A client too slow to keep up with its subscriptions
+/// is disconnected.
+#[derive(Clone, Default)]
+pub(crate) struct Subscriptions(Arc<Mutex<(usize, HashMap<usize, Client>)>>);
+
+impl Subscriptions {
+    /// Registers a new client, without any subscription, and returns its id and the receiver of its messages.
+    pub(crate) fn connect(&self) -> (usize, mpsc::Receiver<Arc<String>>) {
+        let (sender, receiver) = mpsc::channel(CLIENT_CAPACITY);
+        let mut inner = self.0.lock().expect("Subscriptions lock poisoned");
+        let id = inner.0;
+
+        inner.0 += 1;
+        inner.1.insert(
+            id,
+            Client {
+                prefixes: HashSet::new(),
+                sender,
+            },
+        );
+
+        (id, receiver)
+    }
+
+    pub(crate) fn disconnect(&self, id: usize) {
+        self.0.lock().expect("Subscriptions lock poisoned").1.remove(&id);
+    }
+
+    /// Disconnects all clients, which closes their receivers.
+    pub(crate) fn clear(&self) {
+        self.0.lock().expect("Subscriptions lock poisoned").1.clear();
+    }
+
+    /// Subscribes a client to a prefix, returning `false` if the client already has `MAX_CLIENT_PREFIXES` other
+    /// subscriptions.
+    pub(crate) fn subscribe(&self, id: usize, prefix: &str) -> bool {
+        if let Some(client) = self.0.lock().expect("Subscriptions lock poisoned").1.get_mut(&id) {
+            if client.prefixes.len() >= MAX_CLIENT_PREFIXES && !client.prefixes.contains(prefix) {
+                return false;
+            }
+            client.prefixes.insert(prefix.to_owned());
+        }
+
+        true
+    }
+
+    pub(crate) fn unsubscribe(&self, id: usize, prefix: &str) {
+        if let Some(client) = self.0.lock().expect("Subscriptions lock poisoned").1.get_mut(&id) {
+            client.prefixes.remove(prefix);
+        }
+    }
+
+    /// Whether a message of this topic would be sent to at least one client; allows skipping building messages.
+    pub(crate) fn is_subscribed(&self, topic: &str) -> bool {
+        self.0
+            .lock()
+            .expect("Subscriptions lock poisoned")
+            .1
+            .values()
+            .any(|client| client.prefixes.iter().any(|prefix| topic.starts_with(prefix.as_str())))
+    }
+
+    pub(crate) fn publish(&self, message: String) {
+        let message = Arc::new(message);
+
+        // A client whose queue is full, or that is disconnecting, is removed; its receiver then closes once it received
+        // the queued messages.
+        self.0
+            .lock()
+            .expect("Subscriptions lock poisoned")
+            .1
+            .retain(|_, client| {
+                !client
+                    .prefixes
+                    .iter()
+                    .any(|prefix| message.starts_with(prefix.as_str()))
+                    || client.sender.try_send(message.clone()).is_ok()
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::stream::StreamExt;
+
+    #[test]
+    fn prefix_matching() {
+        let subscriptions = Subscriptions::default();
+        let (tx, mut tx_receiver) = subscriptions.connect();
+        let (all, mut all_receiver) = subscriptions.connect();
+        let (_, mut none_receiver) = subscriptions.connect();
+
+        subscriptions.subscribe(tx, "tx");
+        subscriptions.subscribe(all, "");
+
+        subscriptions.publish("tx_trytes ABC".to_owned());
+        subscriptions.publish("lmi 1 2".to_owned());
+
+        assert_eq!(*tx_receiver.try_next().unwrap().unwrap(), "tx_trytes ABC");
+        assert!(tx_receiver.try_next().is_err());
+        assert_eq!(*all_receiver.try_next().unwrap().unwrap(), "tx_trytes ABC");
+        assert_eq!(*all_receiver.try_next().unwrap().unwrap(), "lmi 1 2");
+        assert!(none_receiver.try_next().is_err());
+    }
+
+    #[test]
+    fn unsubscribe_and_disconnect() {
+        let subscriptions = Subscriptions::default();
+        let (id, mut receiver) = subscriptions.connect();
+
+        subscriptions.subscribe(id, "lmi");
+        assert!(subscriptions.is_subscribed("lmi"));
+        assert!(!subscriptions.is_subscribed("lmsi"));
+
+        subscriptions.unsubscribe(id, "lmi");
+        assert!(!subscriptions.is_subscribed("lmi"));
+
+        subscriptions.disconnect(id);
+        assert_eq!(receiver.try_next().unwrap(), None);
+    }
+
+    #[test]
+    fn limit_prefixes() {
+        let subscriptions = Subscriptions::default();
+        let (id, _receiver) = subscriptions.connect();
+
+        for i in 0..MAX_CLIENT_PREFIXES {
+            assert!(subscriptions.subscribe(id, &i.to_string()));
+        }
+
+        assert!(!subscriptions.subscribe(id, "lmi"));
+        assert!(subscriptions.subscribe(id, "0"));
+        assert!(!subscriptions.is_subscribed("lmi"));
+
+        subscriptions.unsubscribe(id, "0");
+        assert!(subscriptions.subscribe(id, "lmi"));
+        assert!(subscriptions.is_subscribed("lmi"));
+    }
+
+    #[test]
+    fn disconnect_slow_clients() {
+        let subscriptions = Subscriptions::default();
+        let (slow, slow_receiver) = subscriptions.connect();
+        let (fast, mut fast_receiver) = subscriptions.connect();
+
+        subscriptions.subscribe(slow, "lmi");
+        subscriptions.subscribe(fast, "lmi");
+
+        for _ in 0..2 * CLIENT_CAPACITY {
+            subscriptions.publish("lmi 1 2".to_owned());
+            assert!(fast_receiver.try_next().unwrap().is_some());
+        }
+
+        assert_eq!(subscriptions.0.lock().unwrap().1.len(), 1);
+        assert!(futures::executor::block_on(slow_receiver.collect::<Vec<_>>()).len() < 2 * CLIENT_CAPACITY);
+    }
+}
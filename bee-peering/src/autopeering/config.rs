@@ -0,0 +1,133 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use serde::Deserialize;
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+const DEFAULT_ENABLED: bool = false;
+const DEFAULT_BINDING_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0));
+const DEFAULT_BINDING_PORT: u16 = 14626;
+const DEFAULT_ENTRY_NODES: Vec<String> = Vec::new();
+const DEFAULT_INBOUND_NEIGHBOURS: u8 = 4;
+const DEFAULT_OUTBOUND_NEIGHBOURS: u8 = 4;
+const DEFAULT_QUERY_INTERVAL: u64 = 30;
+const DEFAULT_SALT_LIFETIME: u64 = 7200;
+
+#[derive(Default, Deserialize)]
+pub struct AutopeeringConfigBuilder {
+    enabled: Option<bool>,
+    binding_addr: Option<IpAddr>,
+    binding_port: Option<u16>,
+    entry_nodes: Option<Vec<String>>,
+    inbound_neighbours: Option<u8>,
+    outbound_neighbours: Option<u8>,
+    query_interval: Option<u64>,
+    salt_lifetime: Option<u64>,
+}
+
+impl AutopeeringConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled.replace(enabled);
+        self
+    }
+
+    pub fn binding_addr(mut self, addr: &str) -> Self {
+        match addr.parse() {
+            Ok(addr) => {
+                self.binding_addr.replace(addr);
+            }
+            Err(e) => panic!("Error parsing address: {:?}", e),
+        }
+        self
+    }
+
+    pub fn binding_port(mut self, port: u16) -> Self {
+        self.binding_port.replace(port);
+        self
+    }
+
+    /// Adds an entry node, given by the "host:port" of its autopeering socket.
+    pub fn add_entry_node(mut self, entry_node: &str) -> Self {
+        self.entry_nodes
+            .get_or_insert_with(Vec::new)
+            .push(entry_node.to_owned());
+        self
+    }
+
+    pub fn inbound_neighbours(mut self, inbound_neighbours: u8) -> Self {
+        self.inbound_neighbours.replace(inbound_neighbours);
+        self
+    }
+
+    pub fn outbound_neighbours(mut self, outbound_neighbours: u8) -> Self {
+        self.outbound_neighbours.replace(outbound_neighbours);
+        self
+    }
+
+    /// Sets the interval, in seconds, at which peers are queried and neighbours selected.
+    pub fn query_interval(mut self, query_interval: u64) -> Self {
+        self.query_interval.replace(query_interval);
+        self
+    }
+
+    /// Sets the lifetime, in seconds, of the salt the distance to other peers is computed with.
+    pub fn salt_lifetime(mut self, salt_lifetime: u64) -> Self {
+        self.salt_lifetime.replace(salt_lifetime);
+        self
+    }
+
+    pub fn finish(self) -> AutopeeringConfig {
+        AutopeeringConfig {
+            enabled: self.enabled.unwrap_or(DEFAULT_ENABLED),
+            binding_addr: self.binding_addr.unwrap_or(DEFAULT_BINDING_ADDR),
+            binding_port: self.binding_port.unwrap_or(DEFAULT_BINDING_PORT),
+            entry_nodes: self.entry_nodes.unwrap_or(DEFAULT_ENTRY_NODES),
+            inbound_neighbours: self.inbound_neighbours.unwrap_or(DEFAULT_INBOUND_NEIGHBOURS),
+            outbound_neighbours: self.outbound_neighbours.unwrap_or(DEFAULT_OUTBOUND_NEIGHBOURS),
+            query_interval: Duration::from_secs(self.query_interval.unwrap_or(DEFAULT_QUERY_INTERVAL)),
+            salt_lifetime: Duration::from_secs(self.salt_lifetime.unwrap_or(DEFAULT_SALT_LIFETIME)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AutopeeringConfig {
+    pub(crate) enabled: bool,
+    pub(crate) binding_addr: IpAddr,
+    pub(crate) binding_port: u16,
+    pub(crate) entry_nodes: Vec<String>,
+    pub(crate) inbound_neighbours: u8,
+    pub(crate) outbound_neighbours: u8,
+    pub(crate) query_interval: Duration,
+    pub(crate) salt_lifetime: Duration,
+}
+
+impl AutopeeringConfig {
+    pub fn build() -> AutopeeringConfigBuilder {
+        AutopeeringConfigBuilder::new()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.binding_addr, self.binding_port)
+    }
+}
@@ -0,0 +1,742 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    autopeering::{
+        config::AutopeeringConfig,
+        message::{Message, Packet, PeerRecord, MAX_ADVERTISED_PEERS, MAX_PACKET_SIZE},
+        salt::{distance, Salt},
+    },
+    PeerManager,
+};
+
+use bee_network::{
+    Command::{AddEndpoint, RemoveEndpoint},
+    EndpointId, Identity, Network, PublicKey as GossipKey, Url,
+};
+
+use async_std::{
+    io,
+    net::{ToSocketAddrs, UdpSocket},
+};
+use async_trait::async_trait;
+use blake2::{Blake2b, Digest};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+use log::{debug, info, warn};
+use rand::{rngs::OsRng, seq::IteratorRandom, RngCore};
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+const MAX_KNOWN_PEERS: usize = 256;
+// Number of consecutive queries a known peer may leave unanswered before it is forgotten.
+const MAX_UNANSWERED_QUERIES: u8 = 3;
+const MAX_TIMESTAMP_SKEW: u64 = 60;
+const QUERIED_PEERS: usize = 3;
+
+type PeerKey = [u8; PUBLIC_KEY_LENGTH];
+
+struct KnownPeer {
+    public_key: PublicKey,
+    autopeering_addr: SocketAddr,
+    gossip_port: u16,
+    // The key the peer authenticates its gossip connections with, only known once it sent a packet itself.
+    gossip_key: Option<GossipKey>,
+    // Whether the peer answered a request sent to its address.
+    verified: bool,
+    // Number of consecutive queries the peer left unanswered.
+    unanswered: u8,
+}
+
+impl KnownPeer {
+    fn gossip_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.autopeering_addr.ip(), self.gossip_port)
+    }
+}
+
+struct Neighbour {
+    epid: EndpointId,
+    distance: Vec<u8>,
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Discovers peers by exchanging signed peer lists over UDP and selects the closest of them, according to a salted
+/// distance, as neighbours of the gossip network.
+pub struct AutopeeringManager {
+    config: AutopeeringConfig,
+    network: Network,
+    keypair: Keypair,
+    gossip_key: GossipKey,
+    salt: Salt,
+    known: HashMap<PeerKey, KnownPeer>,
+    inbound: HashMap<PeerKey, Neighbour>,
+    outbound: HashMap<PeerKey, Neighbour>,
+    requested: HashSet<PeerKey>,
+    // Nonces of the requests sent since the last query, and the addresses they were sent to.
+    pending: HashMap<u64, SocketAddr>,
+    // Nonces of the packets received within the accepted timestamp skew, and their timestamps.
+    received: HashMap<(PeerKey, u64), u64>,
+}
+
+// Derives the key signing autopeering packets from the node identity, so that the node keeps its place in the
+// autopeering network across restarts.
+fn keypair(identity: &Identity) -> Keypair {
+    let seed = Blake2b::new()
+        .chain(b"bee-autopeering")
+        .chain(identity.private_key())
+        .finalize();
+    let secret = SecretKey::from_bytes(&seed[..SECRET_KEY_LENGTH]).expect("Invalid secret key length");
+
+    Keypair {
+        public: (&secret).into(),
+        secret,
+    }
+}
+
+impl AutopeeringManager {
+    pub fn new(config: AutopeeringConfig, network: Network, identity: &Identity) -> Self {
+        let salt = Salt::new(config.salt_lifetime);
+
+        Self {
+            config,
+            network,
+            keypair: keypair(identity),
+            gossip_key: *identity.public_key(),
+            salt,
+            known: HashMap::new(),
+            inbound: HashMap::new(),
+            outbound: HashMap::new(),
+            requested: HashSet::new(),
+            pending: HashMap::new(),
+            received: HashMap::new(),
+        }
+    }
+
+    fn is_neighbour(&self, key: &PeerKey) -> bool {
+        self.inbound.contains_key(key) || self.outbound.contains_key(key)
+    }
+
+    async fn send(&self, socket: &UdpSocket, to: SocketAddr, message: &Message) -> u64 {
+        let nonce = OsRng.next_u64();
+        let bytes = Packet::sign(
+            &self.keypair,
+            &self.gossip_key,
+            *self.network.config().binding_port(),
+            timestamp(),
+            nonce,
+            &to,
+            message,
+        );
+
+        if let Err(e) = socket.send_to(&bytes, to).await {
+            warn!("Sending autopeering packet to {} failed: {}.", to, e);
+        }
+
+        nonce
+    }
+
+    async fn request(&mut self, socket: &UdpSocket, to: SocketAddr, message: &Message) {
+        let nonce = self.send(socket, to, message).await;
+
+        self.pending.insert(nonce, to);
+    }
+
+    // The endpoint is pinned to the gossip key the neighbour signed, if connections are secure.
+    async fn add_endpoint(&mut self, gossip_addr: SocketAddr, gossip_key: GossipKey) -> Option<EndpointId> {
+        match Url::from_url_str(&format!("tcp://{}", gossip_addr)).await {
+            Ok(url) => {
+                let epid = EndpointId::from(&url);
+                let public_key = if self.network.config().secure() {
+                    Some(gossip_key)
+                } else {
+                    None
+                };
+
+                if let Err(e) = self
+                    .network
+                    .send(AddEndpoint {
+                        url,
+                        public_key,
+                        responder: None,
+                    })
+                    .await
+                {
+                    warn!("Failed to add endpoint \"{}\": {}", epid, e);
+                    None
+                } else {
+                    Some(epid)
+                }
+            }
+            Err(e) => {
+                warn!("Failed to resolve URL \"tcp://{}\": {}", gossip_addr, e);
+                None
+            }
+        }
+    }
+
+    async fn remove_endpoint(&mut self, epid: EndpointId) {
+        if let Err(e) = self.network.send(RemoveEndpoint { epid, responder: None }).await {
+            warn!("Failed to remove endpoint \"{}\": {}", epid, e);
+        }
+    }
+
+    async fn drop_neighbour(&mut self, socket: &UdpSocket, key: &PeerKey) {
+        let neighbour = self.inbound.remove(key).or_else(|| self.outbound.remove(key));
+
+        if let Some(neighbour) = neighbour {
+            if let Some(peer) = self.known.get(key) {
+                self.send(socket, peer.autopeering_addr, &Message::PeeringDrop).await;
+            }
+            self.remove_endpoint(neighbour.epid).await;
+        }
+    }
+
+    // A verified address is only replaced by another verified one, whereas an unverified one, e.g. advertised by
+    // another peer, is replaced by the source of any packet of the peer.
+    fn learn(&mut self, key: PeerKey, peer: KnownPeer) {
+        if key == self.keypair.public.to_bytes() {
+            return;
+        }
+        match self.known.get_mut(&key) {
+            Some(known) => {
+                if known.autopeering_addr == peer.autopeering_addr && peer.gossip_key.is_some() {
+                    known.unanswered = 0;
+                }
+                if peer.verified || !known.verified {
+                    *known = peer;
+                }
+            }
+            None => {
+                // A verified peer takes the place of an unverified one, that may not even exist, if there is no room.
+                if self.known.len() >= MAX_KNOWN_PEERS && peer.verified {
+                    let (inbound, outbound) = (&self.inbound, &self.outbound);
+                    let unverified = self
+                        .known
+                        .iter()
+                        .find(|(key, known)| {
+                            !known.verified && !inbound.contains_key(*key) && !outbound.contains_key(*key)
+                        })
+                        .map(|(key, _)| *key);
+
+                    if let Some(unverified) = unverified {
+                        self.known.remove(&unverified);
+                    }
+                }
+                if self.known.len() < MAX_KNOWN_PEERS {
+                    self.known.insert(key, peer);
+                }
+            }
+        }
+    }
+
+    // Checks that the packet was sent to this node, recently, and that it is not a replay.
+    fn is_fresh(&mut self, packet: &Packet) -> bool {
+        let now = timestamp();
+        let local = self.config.socket_addr();
+
+        if now.saturating_sub(packet.timestamp) > MAX_TIMESTAMP_SKEW
+            || packet.timestamp.saturating_sub(now) > MAX_TIMESTAMP_SKEW
+        {
+            return false;
+        }
+        // The IP a packet is sent to can not be checked when listening on all interfaces, e.g. behind a NAT.
+        if packet.destination.port() != local.port()
+            || (!local.ip().is_unspecified() && packet.destination.ip() != local.ip())
+        {
+            return false;
+        }
+
+        self.received
+            .insert((packet.public_key.to_bytes(), packet.nonce), packet.timestamp)
+            .is_none()
+    }
+
+    async fn handle(&mut self, socket: &UdpSocket, packet: Packet, from: SocketAddr) {
+        let key = packet.public_key.to_bytes();
+
+        if !self.is_fresh(&packet) {
+            debug!(
+                "Dropping stale, replayed or misdirected autopeering packet from {}.",
+                from
+            );
+            return;
+        }
+
+        // A response proves that the peer is reachable at its source address only if it answers a request sent there.
+        let verified = match packet.message.request_nonce() {
+            Some(nonce) if self.pending.get(&nonce) == Some(&from) => {
+                self.pending.remove(&nonce);
+                true
+            }
+            Some(_) => {
+                debug!("Dropping unsolicited autopeering response from {}.", from);
+                return;
+            }
+            None => false,
+        };
+
+        self.learn(
+            key,
+            KnownPeer {
+                public_key: packet.public_key,
+                autopeering_addr: from,
+                gossip_port: packet.gossip_port,
+                gossip_key: Some(packet.gossip_key),
+                verified,
+                unanswered: 0,
+            },
+        );
+
+        // The peer is asked to prove it is reachable at the source address, which then becomes its address.
+        let unverified = self
+            .known
+            .get(&key)
+            .map_or(false, |peer| !peer.verified || peer.autopeering_addr != from);
+
+        if unverified && !self.pending.values().any(|addr| *addr == from) {
+            self.request(socket, from, &Message::DiscoveryRequest).await;
+        }
+
+        match packet.message {
+            Message::DiscoveryRequest => {
+                let peers = self
+                    .known
+                    .iter()
+                    .filter(|(known, peer)| **known != key && peer.verified)
+                    .map(|(_, peer)| PeerRecord {
+                        public_key: peer.public_key,
+                        autopeering_addr: peer.autopeering_addr,
+                        gossip_port: peer.gossip_port,
+                    })
+                    .choose_multiple(&mut OsRng, MAX_ADVERTISED_PEERS);
+
+                self.send(socket, from, &Message::DiscoveryResponse(packet.nonce, peers))
+                    .await;
+            }
+            Message::DiscoveryResponse(_, peers) => {
+                for peer in peers {
+                    let key = peer.public_key.to_bytes();
+
+                    if !self.known.contains_key(&key) {
+                        self.learn(
+                            key,
+                            KnownPeer {
+                                public_key: peer.public_key,
+                                autopeering_addr: peer.autopeering_addr,
+                                gossip_port: peer.gossip_port,
+                                gossip_key: None,
+                                verified: false,
+                                unanswered: 0,
+                            },
+                        );
+                    }
+                }
+            }
+            Message::PeeringRequest(salt) => {
+                let accepted = self.accept(socket, key, from, &salt).await;
+
+                self.send(socket, from, &Message::PeeringResponse(packet.nonce, accepted))
+                    .await;
+            }
+            Message::PeeringResponse(_, accepted) => {
+                if self.requested.remove(&key)
+                    && accepted
+                    && !self.is_neighbour(&key)
+                    && self.outbound.len() < self.config.outbound_neighbours as usize
+                {
+                    let gossip_addr = SocketAddr::new(from.ip(), packet.gossip_port);
+
+                    if let Some(epid) = self.add_endpoint(gossip_addr, packet.gossip_key).await {
+                        info!("Added outbound neighbour {}.", gossip_addr);
+                        self.outbound.insert(
+                            key,
+                            Neighbour {
+                                epid,
+                                distance: distance(&self.keypair.public, &self.salt.bytes, &packet.public_key),
+                            },
+                        );
+                    }
+                }
+            }
+            Message::PeeringDrop => {
+                let neighbour = self.inbound.remove(&key).or_else(|| self.outbound.remove(&key));
+
+                if let Some(neighbour) = neighbour {
+                    info!("Neighbour {} dropped us.", neighbour.epid);
+                    self.remove_endpoint(neighbour.epid).await;
+                }
+            }
+        }
+    }
+
+    // Accepts an inbound neighbour if there is room left or if it is closer, according to the salt of the
+    // requester, than the furthest inbound neighbour, which then gets dropped. The requester has to be verified at the
+    // address the request comes from, the request being declined until then.
+    async fn accept(&mut self, socket: &UdpSocket, key: PeerKey, from: SocketAddr, salt: &[u8]) -> bool {
+        if self.is_neighbour(&key) {
+            return true;
+        }
+
+        let (public_key, gossip_addr, gossip_key) = match self.known.get(&key) {
+            Some(peer) if peer.verified && peer.autopeering_addr == from => match peer.gossip_key {
+                Some(gossip_key) => (peer.public_key, peer.gossip_addr(), gossip_key),
+                None => return false,
+            },
+            _ => return false,
+        };
+        let distance = distance(&self.keypair.public, salt, &public_key);
+
+        if self.inbound.len() >= self.config.inbound_neighbours as usize {
+            let furthest = self
+                .inbound
+                .iter()
+                .max_by(|(_, a), (_, b)| a.distance.cmp(&b.distance))
+                .map(|(key, neighbour)| (*key, neighbour.distance.clone()));
+
+            match furthest {
+                Some((furthest, furthest_distance)) if distance < furthest_distance => {
+                    self.drop_neighbour(socket, &furthest).await;
+                }
+                _ => return false,
+            }
+        }
+
+        match self.add_endpoint(gossip_addr, gossip_key).await {
+            Some(epid) => {
+                info!("Added inbound neighbour {}.", gossip_addr);
+                self.inbound.insert(key, Neighbour { epid, distance });
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn query(&mut self, socket: &UdpSocket, entry_nodes: &[SocketAddr]) {
+        if self.salt.is_expired() {
+            info!("Salt expired, dropping neighbours.");
+            self.salt = Salt::new(self.config.salt_lifetime);
+
+            let neighbours = self
+                .inbound
+                .keys()
+                .chain(self.outbound.keys())
+                .copied()
+                .collect::<Vec<PeerKey>>();
+
+            for key in neighbours {
+                self.drop_neighbour(socket, &key).await;
+            }
+        }
+
+        let queried = self
+            .known
+            .values()
+            .map(|peer| peer.autopeering_addr)
+            .choose_multiple(&mut OsRng, QUERIED_PEERS);
+
+        // Requests that stayed unanswered since the last query are given up on, and peers that keep leaving them
+        // unanswered are forgotten so that there is room to learn new ones.
+        let unanswered = self.pending.values().collect::<HashSet<&SocketAddr>>();
+
+        for peer in self.known.values_mut() {
+            if unanswered.contains(&peer.autopeering_addr) {
+                peer.unanswered = peer.unanswered.saturating_add(1);
+            }
+        }
+
+        let (inbound, outbound) = (&self.inbound, &self.outbound);
+        self.known.retain(|key, peer| {
+            peer.unanswered < MAX_UNANSWERED_QUERIES || inbound.contains_key(key) || outbound.contains_key(key)
+        });
+
+        self.requested.clear();
+        self.pending.clear();
+
+        let now = timestamp();
+        self.received
+            .retain(|_, timestamp| now.saturating_sub(*timestamp) <= MAX_TIMESTAMP_SKEW);
+
+        for addr in entry_nodes.iter().chain(queried.iter()) {
+            self.request(socket, *addr, &Message::DiscoveryRequest).await;
+        }
+
+        let missing = (self.config.outbound_neighbours as usize).saturating_sub(self.outbound.len());
+        let mut candidates = self
+            .known
+            .iter()
+            .filter(|(key, _)| !self.is_neighbour(key))
+            .map(|(key, peer)| {
+                (
+                    distance(&self.keypair.public, &self.salt.bytes, &peer.public_key),
+                    *key,
+                    peer.autopeering_addr,
+                )
+            })
+            .collect::<Vec<(Vec<u8>, PeerKey, SocketAddr)>>();
+
+        candidates.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        for (_, key, addr) in candidates.into_iter().take(missing) {
+            self.requested.insert(key);
+            self.request(socket, addr, &Message::PeeringRequest(self.salt.bytes))
+                .await;
+        }
+    }
+}
+
+async fn resolve(entry_nodes: &[String]) -> Vec<SocketAddr> {
+    let mut addrs = Vec::new();
+
+    for entry_node in entry_nodes {
+        match entry_node.to_socket_addrs().await.map(|mut addrs| addrs.next()) {
+            Ok(Some(addr)) => addrs.push(addr),
+            Ok(None) => warn!("Failed to resolve entry node \"{}\".", entry_node),
+            Err(e) => warn!("Failed to resolve entry node \"{}\": {}", entry_node, e),
+        }
+    }
+
+    addrs
+}
+
+#[async_trait]
+impl PeerManager for AutopeeringManager {
+    async fn run(mut self) {
+        let socket = match UdpSocket::bind(self.config.socket_addr()).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                warn!(
+                    "Binding autopeering socket to {} failed: {}.",
+                    self.config.socket_addr(),
+                    e
+                );
+                return;
+            }
+        };
+        let entry_nodes = resolve(&self.config.entry_nodes).await;
+        let mut buffer = [0u8; MAX_PACKET_SIZE];
+        let mut last_query: Option<Instant> = None;
+
+        info!("Autopeering on {}.", self.config.socket_addr());
+
+        loop {
+            if last_query.map_or(true, |last_query| last_query.elapsed() >= self.config.query_interval) {
+                self.query(&socket, &entry_nodes).await;
+                last_query.replace(Instant::now());
+            }
+
+            match io::timeout(self.config.query_interval, socket.recv_from(&mut buffer)).await {
+                Ok((len, from)) => match Packet::verify(&buffer[..len]) {
+                    Ok(packet) => self.handle(&socket, packet, from).await,
+                    Err(e) => debug!("Dropping invalid autopeering packet from {}: {}", from, e),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => (),
+                Err(e) => warn!("Receiving autopeering packet failed: {}.", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_network::{Command, NetworkConfig};
+
+    use async_std::{
+        future::timeout,
+        task::{block_on, spawn},
+    };
+    use futures::{channel::mpsc, StreamExt};
+
+    use std::{net::Ipv4Addr, time::Duration};
+
+    fn manager() -> AutopeeringManager {
+        let (sender, _) = mpsc::channel(1);
+        let config = AutopeeringConfig::build()
+            .binding_addr("127.0.0.1")
+            .binding_port(14710)
+            .finish();
+
+        AutopeeringManager::new(
+            config,
+            Network::new(NetworkConfig::build().finish(), sender),
+            &Identity::generate(),
+        )
+    }
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)
+    }
+
+    fn packet(keypair: &Keypair, timestamp: u64, nonce: u64, destination: SocketAddr) -> Packet {
+        Packet::verify(&Packet::sign(
+            keypair,
+            15600,
+            timestamp,
+            nonce,
+            &destination,
+            &Message::DiscoveryRequest,
+        ))
+        .unwrap()
+    }
+
+    fn known(keypair: &Keypair, port: u16, verified: bool) -> KnownPeer {
+        KnownPeer {
+            public_key: keypair.public,
+            autopeering_addr: addr(port),
+            gossip_port: 15600,
+            gossip_key: Some(*Identity::generate().public_key()),
+            verified,
+            unanswered: 0,
+        }
+    }
+
+    #[test]
+    fn derive_keypair_from_identity() {
+        let identity = Identity::generate();
+
+        assert_eq!(keypair(&identity).public, keypair(&identity).public);
+        assert_ne!(keypair(&identity).public, keypair(&Identity::generate()).public);
+    }
+
+    #[test]
+    fn reject_stale_replayed_and_misdirected_packets() {
+        let mut manager = manager();
+        let keypair = Keypair::generate(&mut OsRng);
+
+        assert!(manager.is_fresh(&packet(&keypair, timestamp(), 1, addr(14710))));
+        assert!(!manager.is_fresh(&packet(&keypair, timestamp(), 1, addr(14710))));
+        assert!(manager.is_fresh(&packet(&keypair, timestamp(), 2, addr(14710))));
+        assert!(!manager.is_fresh(&packet(&keypair, timestamp() - 2 * MAX_TIMESTAMP_SKEW, 3, addr(14710))));
+        assert!(!manager.is_fresh(&packet(&keypair, timestamp(), 4, addr(14711))));
+        assert!(!manager.is_fresh(&packet(
+            &keypair,
+            timestamp(),
+            5,
+            SocketAddr::new(Ipv4Addr::new(10, 0, 0, 1).into(), 14710)
+        )));
+    }
+
+    #[test]
+    fn keep_verified_addresses() {
+        let mut manager = manager();
+        let keypair = Keypair::generate(&mut OsRng);
+        let key = keypair.public.to_bytes();
+        let port = |manager: &AutopeeringManager| manager.known[&key].autopeering_addr.port();
+
+        manager.learn(key, known(&keypair, 14720, false));
+        manager.learn(key, known(&keypair, 14721, false));
+        assert_eq!(port(&manager), 14721);
+
+        manager.learn(key, known(&keypair, 14722, true));
+        manager.learn(key, known(&keypair, 14723, false));
+        assert_eq!(port(&manager), 14722);
+
+        manager.learn(key, known(&keypair, 14724, true));
+        assert_eq!(port(&manager), 14724);
+    }
+
+    #[test]
+    fn forget_unanswering_peers() {
+        let mut manager = manager();
+        let keypair = Keypair::generate(&mut OsRng);
+        let key = keypair.public.to_bytes();
+
+        manager.learn(key, known(&keypair, 14730, false));
+
+        block_on(async {
+            let socket = UdpSocket::bind(addr(14731)).await.unwrap();
+
+            for _ in 0..MAX_UNANSWERED_QUERIES {
+                assert!(manager.known.contains_key(&key));
+                manager.pending.insert(0, addr(14730));
+                manager.query(&socket, &[]).await;
+            }
+        });
+
+        assert!(!manager.known.contains_key(&key));
+    }
+
+    // Spawns an autopeering node on loopback, returning the commands it sends to its network layer.
+    fn node(port: u16, entry_node: Option<u16>) -> mpsc::Receiver<Command> {
+        let (sender, receiver) = mpsc::channel(64);
+        let mut config = AutopeeringConfig::build()
+            .binding_addr("127.0.0.1")
+            .binding_port(port)
+            .query_interval(1);
+
+        if let Some(entry_node) = entry_node {
+            config = config.add_entry_node(&format!("127.0.0.1:{}", entry_node));
+        }
+
+        let network = Network::new(NetworkConfig::build().binding_port(port + 1000).finish(), sender);
+
+        spawn(AutopeeringManager::new(config.finish(), network, &Identity::generate()).run());
+
+        receiver
+    }
+
+    // Collects the gossip addresses added as endpoints until there are `expected` of them.
+    async fn added(commands: &mut mpsc::Receiver<Command>, expected: usize) -> HashSet<String> {
+        let mut added = HashSet::new();
+
+        while added.len() < expected {
+            match commands.next().await {
+                Some(Command::AddEndpoint { url, .. }) => {
+                    added.insert(url.address().to_string());
+                }
+                Some(_) => (),
+                None => break,
+            }
+        }
+
+        added
+    }
+
+    #[test]
+    fn loopback_neighbourhood() {
+        let mut a = node(14701, None);
+        let mut b = node(14702, Some(14701));
+        let mut c = node(14703, Some(14701));
+
+        block_on(async {
+            let deadline = Duration::from_secs(10);
+            let expected = |ports: &[u16]| {
+                ports
+                    .iter()
+                    .map(|port| format!("127.0.0.1:{}", port))
+                    .collect::<HashSet<String>>()
+            };
+
+            assert_eq!(
+                timeout(deadline, added(&mut a, 2)).await.unwrap(),
+                expected(&[15702, 15703])
+            );
+            assert_eq!(
+                timeout(deadline, added(&mut b, 2)).await.unwrap(),
+                expected(&[15701, 15703])
+            );
+            assert_eq!(
+                timeout(deadline, added(&mut c, 2)).await.unwrap(),
+                expected(&[15701, 15702])
+            );
+        });
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Signed autopeering packets, exchanged over UDP.
+//!
+//! A packet is laid out as
+//! `kind | public key | gossip key | gossip port | timestamp | nonce | destination | payload | signature`, the signature
+//! covering everything before it. The gossip key is the public key the node authenticates its gossip connections with. The destination and the random nonce bind a packet to the node it was
+//! sent to and make replays detectable, and responses carry the nonce of the request they answer.
+
+use crate::autopeering::salt::SALT_LENGTH;
+
+use bee_network::{PublicKey as GossipKey, KEY_LENGTH as GOSSIP_KEY_LENGTH};
+
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use thiserror::Error;
+
+use std::{
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+/// Maximum size of a packet, kept below the minimum IPv6 MTU to avoid fragmentation.
+pub(crate) const MAX_PACKET_SIZE: usize = 1232;
+/// Maximum number of peers advertised in a discovery response so that it fits a packet.
+pub(crate) const MAX_ADVERTISED_PEERS: usize = 16;
+
+const KIND_DISCOVERY_REQUEST: u8 = 0;
+const KIND_DISCOVERY_RESPONSE: u8 = 1;
+const KIND_PEERING_REQUEST: u8 = 2;
+const KIND_PEERING_RESPONSE: u8 = 3;
+const KIND_PEERING_DROP: u8 = 4;
+
+#[derive(Debug, Error, PartialEq)]
+pub(crate) enum Error {
+    #[error("Truncated packet.")]
+    Truncated,
+
+    #[error("Unknown packet kind {0}.")]
+    UnknownKind(u8),
+
+    #[error("Invalid public key.")]
+    InvalidPublicKey,
+
+    #[error("Invalid signature.")]
+    InvalidSignature,
+
+    #[error("Invalid IP version {0}.")]
+    InvalidIpVersion(u8),
+}
+
+/// A peer as advertised in a discovery response.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PeerRecord {
+    pub(crate) public_key: PublicKey,
+    pub(crate) autopeering_addr: SocketAddr,
+    pub(crate) gossip_port: u16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Message {
+    DiscoveryRequest,
+    DiscoveryResponse(u64, Vec<PeerRecord>),
+    PeeringRequest([u8; SALT_LENGTH]),
+    PeeringResponse(u64, bool),
+    PeeringDrop,
+}
+
+impl Message {
+    /// Returns the nonce of the request answered by the message, if it is a response.
+    pub(crate) fn request_nonce(&self) -> Option<u64> {
+        match self {
+            Message::DiscoveryResponse(nonce, _) | Message::PeeringResponse(nonce, _) => Some(*nonce),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Packet {
+    pub(crate) public_key: PublicKey,
+    pub(crate) gossip_key: GossipKey,
+    pub(crate) gossip_port: u16,
+    pub(crate) timestamp: u64,
+    pub(crate) nonce: u64,
+    pub(crate) destination: SocketAddr,
+    pub(crate) message: Message,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::Truncated);
+        }
+
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_be_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn public_key(&mut self) -> Result<PublicKey, Error> {
+        PublicKey::from_bytes(self.take(PUBLIC_KEY_LENGTH)?).map_err(|_| Error::InvalidPublicKey)
+    }
+
+    fn gossip_key(&mut self) -> Result<GossipKey, Error> {
+        let mut bytes = [0u8; GOSSIP_KEY_LENGTH];
+        bytes.copy_from_slice(self.take(GOSSIP_KEY_LENGTH)?);
+        Ok(GossipKey::from_bytes(bytes))
+    }
+
+    fn socket_addr(&mut self) -> Result<SocketAddr, Error> {
+        let ip = match self.u8()? {
+            4 => {
+                let mut bytes = [0u8; 4];
+                bytes.copy_from_slice(self.take(4)?);
+                IpAddr::V4(Ipv4Addr::from(bytes))
+            }
+            6 => {
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(self.take(16)?);
+                IpAddr::V6(Ipv6Addr::from(bytes))
+            }
+            version => return Err(Error::InvalidIpVersion(version)),
+        };
+
+        Ok(SocketAddr::new(ip, self.u16()?))
+    }
+}
+
+fn write_socket_addr(bytes: &mut Vec<u8>, addr: &SocketAddr) {
+    match addr.ip() {
+        IpAddr::V4(ip) => {
+            bytes.push(4);
+            bytes.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            bytes.push(6);
+            bytes.extend_from_slice(&ip.octets());
+        }
+    }
+    bytes.extend_from_slice(&addr.port().to_be_bytes());
+}
+
+impl Packet {
+    /// Encodes and signs a packet, advertised peers beyond `MAX_ADVERTISED_PEERS` being left out.
+    pub(crate) fn sign(
+        keypair: &Keypair,
+        gossip_key: &GossipKey,
+        gossip_port: u16,
+        timestamp: u64,
+        nonce: u64,
+        destination: &SocketAddr,
+        message: &Message,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAX_PACKET_SIZE);
+
+        bytes.push(match message {
+            Message::DiscoveryRequest => KIND_DISCOVERY_REQUEST,
+            Message::DiscoveryResponse(..) => KIND_DISCOVERY_RESPONSE,
+            Message::PeeringRequest(_) => KIND_PEERING_REQUEST,
+            Message::PeeringResponse(..) => KIND_PEERING_RESPONSE,
+            Message::PeeringDrop => KIND_PEERING_DROP,
+        });
+        bytes.extend_from_slice(keypair.public.as_bytes());
+        bytes.extend_from_slice(gossip_key.as_bytes());
+        bytes.extend_from_slice(&gossip_port.to_be_bytes());
+        bytes.extend_from_slice(&timestamp.to_be_bytes());
+        bytes.extend_from_slice(&nonce.to_be_bytes());
+        write_socket_addr(&mut bytes, destination);
+
+        match message {
+            Message::DiscoveryRequest | Message::PeeringDrop => (),
+            Message::DiscoveryResponse(request, peers) => {
+                let peers = &peers[..peers.len().min(MAX_ADVERTISED_PEERS)];
+
+                bytes.extend_from_slice(&request.to_be_bytes());
+                bytes.push(peers.len() as u8);
+                for peer in peers {
+                    bytes.extend_from_slice(peer.public_key.as_bytes());
+                    write_socket_addr(&mut bytes, &peer.autopeering_addr);
+                    bytes.extend_from_slice(&peer.gossip_port.to_be_bytes());
+                }
+            }
+            Message::PeeringRequest(salt) => bytes.extend_from_slice(salt),
+            Message::PeeringResponse(request, accepted) => {
+                bytes.extend_from_slice(&request.to_be_bytes());
+                bytes.push(*accepted as u8);
+            }
+        }
+
+        let signature = keypair.sign(&bytes);
+        bytes.extend_from_slice(&signature.to_bytes());
+
+        bytes
+    }
+
+    /// Decodes a packet and verifies that it is signed by the public key it carries.
+    pub(crate) fn verify(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SIGNATURE_LENGTH {
+            return Err(Error::Truncated);
+        }
+
+        let (body, signature) = bytes.split_at(bytes.len() - SIGNATURE_LENGTH);
+        let mut reader = Reader { bytes: body };
+
+        let kind = reader.u8()?;
+        let public_key = reader.public_key()?;
+        let gossip_key = reader.gossip_key()?;
+        let gossip_port = reader.u16()?;
+        let timestamp = reader.u64()?;
+        let nonce = reader.u64()?;
+        let destination = reader.socket_addr()?;
+
+        let message = match kind {
+            KIND_DISCOVERY_REQUEST => Message::DiscoveryRequest,
+            KIND_DISCOVERY_RESPONSE => {
+                let request = reader.u64()?;
+                let len = reader.u8()? as usize;
+                let mut peers = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    peers.push(PeerRecord {
+                        public_key: reader.public_key()?,
+                        autopeering_addr: reader.socket_addr()?,
+                        gossip_port: reader.u16()?,
+                    });
+                }
+
+                Message::DiscoveryResponse(request, peers)
+            }
+            KIND_PEERING_REQUEST => {
+                let mut salt = [0u8; SALT_LENGTH];
+                salt.copy_from_slice(reader.take(SALT_LENGTH)?);
+                Message::PeeringRequest(salt)
+            }
+            KIND_PEERING_RESPONSE => Message::PeeringResponse(reader.u64()?, reader.u8()? != 0),
+            KIND_PEERING_DROP => Message::PeeringDrop,
+            kind => return Err(Error::UnknownKind(kind)),
+        };
+
+        let signature = Signature::try_from(signature).map_err(|_| Error::InvalidSignature)?;
+        public_key
+            .verify(body, &signature)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(Self {
+            public_key,
+            gossip_key,
+            gossip_port,
+            timestamp,
+            nonce,
+            destination,
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::rngs::OsRng;
+
+    const GOSSIP_KEY: [u8; GOSSIP_KEY_LENGTH] = [7u8; GOSSIP_KEY_LENGTH];
+
+    fn destination() -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 14626)
+    }
+
+    fn record(port: u16) -> PeerRecord {
+        PeerRecord {
+            public_key: Keypair::generate(&mut OsRng).public,
+            autopeering_addr: SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port),
+            gossip_port: port + 1,
+        }
+    }
+
+    #[test]
+    fn sign_verify() {
+        let keypair = Keypair::generate(&mut OsRng);
+
+        for message in vec![
+            Message::DiscoveryRequest,
+            Message::DiscoveryResponse(7, vec![record(14626), record(14627)]),
+            Message::PeeringRequest([42u8; SALT_LENGTH]),
+            Message::PeeringResponse(7, true),
+            Message::PeeringDrop,
+        ] {
+            let bytes = Packet::sign(
+                &keypair,
+                &GossipKey::from_bytes(GOSSIP_KEY),
+                15600,
+                1_600_000_000,
+                42,
+                &destination(),
+                &message,
+            );
+            let packet = Packet::verify(&bytes).unwrap();
+
+            assert!(bytes.len() <= MAX_PACKET_SIZE);
+            assert_eq!(packet.public_key, keypair.public);
+            assert_eq!(packet.gossip_key, GossipKey::from_bytes(GOSSIP_KEY));
+            assert_eq!(packet.gossip_port, 15600);
+            assert_eq!(packet.timestamp, 1_600_000_000);
+            assert_eq!(packet.nonce, 42);
+            assert_eq!(packet.destination, destination());
+            assert_eq!(packet.message, message);
+        }
+    }
+
+    #[test]
+    fn advertised_peers_fit_a_packet() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let peers = (0..2 * MAX_ADVERTISED_PEERS as u16).map(record).collect();
+        let bytes = Packet::sign(
+            &keypair,
+            &GossipKey::from_bytes(GOSSIP_KEY),
+            15600,
+            0,
+            0,
+            &SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 14626),
+            &Message::DiscoveryResponse(0, peers),
+        );
+
+        assert!(bytes.len() <= MAX_PACKET_SIZE);
+        match Packet::verify(&bytes).unwrap().message {
+            Message::DiscoveryResponse(_, peers) => assert_eq!(peers.len(), MAX_ADVERTISED_PEERS),
+            _ => panic!("Unexpected message."),
+        }
+    }
+
+    #[test]
+    fn tampered_packet() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let mut bytes = Packet::sign(
+            &keypair,
+            &GossipKey::from_bytes(GOSSIP_KEY),
+            15600,
+            0,
+            0,
+            &destination(),
+            &Message::PeeringResponse(0, false),
+        );
+
+        bytes[PUBLIC_KEY_LENGTH + GOSSIP_KEY_LENGTH + 11] = 1;
+
+        assert_eq!(Packet::verify(&bytes), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn redirected_packet() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let mut bytes = Packet::sign(
+            &keypair,
+            &GossipKey::from_bytes(GOSSIP_KEY),
+            15600,
+            0,
+            0,
+            &destination(),
+            &Message::DiscoveryRequest,
+        );
+
+        // Last byte of the destination port.
+        bytes[1 + PUBLIC_KEY_LENGTH + GOSSIP_KEY_LENGTH + 2 + 8 + 8 + 1 + 4 + 1] ^= 1;
+
+        assert_eq!(Packet::verify(&bytes), Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn truncated_packet() {
+        let keypair = Keypair::generate(&mut OsRng);
+        let bytes = Packet::sign(
+            &keypair,
+            &GossipKey::from_bytes(GOSSIP_KEY),
+            15600,
+            0,
+            0,
+            &destination(),
+            &Message::PeeringRequest([0u8; SALT_LENGTH]),
+        );
+
+        assert_eq!(Packet::verify(&bytes[..bytes.len() - 1]), Err(Error::Truncated));
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use blake2::{Blake2b, Digest};
+use ed25519_dalek::PublicKey;
+use rand::{rngs::OsRng, RngCore};
+
+use std::time::{Duration, Instant};
+
+pub(crate) const SALT_LENGTH: usize = 20;
+
+/// Random bytes mixed into the distance between peers, rotated so that neighbourhoods do not freeze.
+pub(crate) struct Salt {
+    pub(crate) bytes: [u8; SALT_LENGTH],
+    expiration: Instant,
+}
+
+impl Salt {
+    pub(crate) fn new(lifetime: Duration) -> Self {
+        let mut bytes = [0u8; SALT_LENGTH];
+
+        OsRng.fill_bytes(&mut bytes);
+
+        Self {
+            bytes,
+            expiration: Instant::now() + lifetime,
+        }
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        Instant::now() >= self.expiration
+    }
+}
+
+/// Returns the salted distance from `id` to `other`, distances being compared lexicographically.
+pub(crate) fn distance(id: &PublicKey, salt: &[u8], other: &PublicKey) -> Vec<u8> {
+    let id = Blake2b::new().chain(id.as_bytes()).chain(salt).finalize();
+    let other = Blake2b::digest(other.as_bytes());
+
+    id.iter().zip(other.iter()).map(|(a, b)| a ^ b).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ed25519_dalek::Keypair;
+
+    #[test]
+    fn distance_is_salted() {
+        let id = Keypair::generate(&mut OsRng).public;
+        let other = Keypair::generate(&mut OsRng).public;
+        let salt = Salt::new(Duration::from_secs(60));
+
+        assert_eq!(distance(&id, &salt.bytes, &other), distance(&id, &salt.bytes, &other));
+        assert_ne!(
+            distance(&id, &salt.bytes, &other),
+            distance(&id, &[0u8; SALT_LENGTH], &other)
+        );
+        assert!(!salt.is_expired());
+        assert!(Salt::new(Duration::from_secs(0)).is_expired());
+    }
+}
@@ -9,13 +9,18 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::r#static::{StaticPeeringConfig, StaticPeeringConfigBuilder};
+use crate::{
+    autopeering::{AutopeeringConfig, AutopeeringConfigBuilder},
+    r#static::{StaticPeeringConfig, StaticPeeringConfigBuilder},
+};
 
 use serde::Deserialize;
 
 #[derive(Default, Deserialize)]
 pub struct PeeringConfigBuilder {
-    r#static: StaticPeeringConfigBuilder,
+    #[serde(default)]
+    pub(crate) autopeering: AutopeeringConfigBuilder,
+    pub(crate) r#static: StaticPeeringConfigBuilder,
 }
 
 impl PeeringConfigBuilder {
@@ -25,6 +30,7 @@ impl PeeringConfigBuilder {
 
     pub fn finish(self) -> PeeringConfig {
         PeeringConfig {
+            autopeering: self.autopeering.finish(),
             r#static: self.r#static.finish(),
         }
     }
@@ -32,6 +38,7 @@ impl PeeringConfigBuilder {
 
 #[derive(Clone)]
 pub struct PeeringConfig {
+    pub autopeering: AutopeeringConfig,
     pub r#static: StaticPeeringConfig,
 }
 
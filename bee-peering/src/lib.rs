@@ -9,10 +9,12 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
+mod autopeering;
 mod config;
 mod manager;
 mod r#static;
 
+pub use autopeering::{AutopeeringConfig, AutopeeringConfigBuilder, AutopeeringManager};
 pub use config::{PeeringConfig, PeeringConfigBuilder};
 pub use manager::PeerManager;
 pub use r#static::StaticPeerManager;
@@ -9,12 +9,33 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{r#static::config::StaticPeeringConfig, PeerManager};
+use crate::{config::PeeringConfigBuilder, r#static::config::StaticPeeringConfig, PeerManager};
 
-use bee_network::{Command::AddEndpoint, Network, Url};
+use bee_network::{
+    response_channel,
+    Command::{AddEndpoint, RemoveEndpoint},
+    EndpointId, Network, PublicKey, Url,
+};
 
+use async_std::{fs, task::sleep};
 use async_trait::async_trait;
-use log::warn;
+use log::{info, warn};
+use serde::Deserialize;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+// Only the peering section of the watched config file is of interest.
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    peering: PeeringConfigBuilder,
+}
 
 // Manages a peer list and watches a config file for changes
 // Sends changes (peer added/removed) to the network
@@ -22,34 +43,221 @@ use log::warn;
 pub struct StaticPeerManager {
     config: StaticPeeringConfig,
     network: Network,
+    config_path: Option<PathBuf>,
+    // The endpoints added by this manager and the config entries they were added from.
+    peers: HashMap<EndpointId, String>,
 }
 
 impl StaticPeerManager {
     pub fn new(config: StaticPeeringConfig, network: Network) -> Self {
-        Self { config, network }
+        Self {
+            config,
+            network,
+            config_path: None,
+            peers: HashMap::new(),
+        }
     }
 
-    async fn add_endpoint(&mut self, url: &str) {
-        match Url::from_url_str(url).await {
-            Ok(url) => {
-                if let Err(e) = self.network.send(AddEndpoint { url, responder: None }).await {
-                    warn!("Failed to add endpoint \"{}\": {}", url, e);
-                }
+    /// Watches a config file, applying changes of its static peer list without restarting the node.
+    pub fn watch<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.config_path.replace(path.as_ref().to_path_buf());
+        self
+    }
+
+    async fn add_endpoint(&mut self, peer: &str) {
+        if self.peers.len() >= self.config.limit as usize {
+            warn!(
+                "Refusing to add endpoint \"{}\": limit of {} static peers reached.",
+                peer, self.config.limit
+            );
+            return;
+        }
+
+        let public_key = match PublicKey::from_url_str(peer) {
+            Ok(public_key) => public_key,
+            Err(e) => {
+                warn!("Failed to parse public key of \"{}\": {}", peer, e);
+                return;
             }
+        };
+
+        let url = match Url::from_url_str(peer).await {
+            Ok(url) => url,
             Err(e) => {
-                warn!("Failed to resolve URL \"{}\": {}", url, e);
+                warn!("Failed to resolve URL \"{}\": {}", peer, e);
+                return;
             }
+        };
+        let epid = EndpointId::from(&url);
+
+        if let Some(other) = self.peers.get(&epid) {
+            warn!("Not adding endpoint \"{}\": same endpoint as \"{}\".", peer, other);
+            return;
+        }
+
+        let (responder, requester) = response_channel();
+
+        if let Err(e) = self
+            .network
+            .send(AddEndpoint {
+                url,
+                public_key,
+                responder: Some(responder),
+            })
+            .await
+        {
+            warn!("Failed to add endpoint \"{}\": {}", peer, e);
+            return;
+        }
+
+        // Only endpoints actually added are recorded, so that a refused one is retried on the next reload.
+        match requester.await {
+            Ok(true) => {
+                self.peers.insert(epid, peer.to_owned());
+            }
+            Ok(false) => warn!("Endpoint \"{}\" refused by the network layer.", peer),
+            Err(_) => warn!("Failed to add endpoint \"{}\": network layer unavailable.", peer),
+        }
+    }
+
+    async fn remove_endpoint(&mut self, peer: &str) {
+        let epid = self
+            .peers
+            .iter()
+            .find_map(|(epid, entry)| if entry == peer { Some(*epid) } else { None });
+
+        if let Some(epid) = epid {
+            self.peers.remove(&epid);
+
+            if let Err(e) = self.network.send(RemoveEndpoint { epid, responder: None }).await {
+                warn!("Failed to remove endpoint \"{}\": {}", peer, e);
+            }
+        }
+    }
+
+    async fn reload(&mut self, path: &Path) {
+        let config = match fs::read_to_string(path).await {
+            Ok(toml) => match toml::from_str::<ConfigFile>(&toml) {
+                Ok(file) => file.peering.r#static.finish(),
+                Err(e) => {
+                    warn!("Failed to parse config file \"{}\": {}", path.display(), e);
+                    return;
+                }
+            },
+            Err(e) => {
+                warn!("Failed to read config file \"{}\": {}", path.display(), e);
+                return;
+            }
+        };
+
+        let current = self.peers.values().cloned().collect::<Vec<String>>();
+        let (added, removed) = diff(&current, &config.peers);
+
+        // Lowering the limit does not drop peers that are already added, it only refuses new ones.
+        self.config = config;
+
+        for peer in removed {
+            self.remove_endpoint(&peer).await;
+        }
+        for peer in added {
+            self.add_endpoint(&peer).await;
         }
     }
 }
 
+// Returns the peers to add and the peers to remove to go from the `current` peer list to the `desired` one.
+fn diff(current: &[String], desired: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut added: Vec<String> = Vec::new();
+
+    for peer in desired {
+        if !current.contains(peer) && !added.contains(peer) {
+            added.push(peer.clone());
+        }
+    }
+
+    let removed = current
+        .iter()
+        .filter(|peer| !desired.contains(*peer))
+        .cloned()
+        .collect();
+
+    (added, removed)
+}
+
+async fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).await.ok()?.modified().ok()
+}
+
 #[async_trait]
 impl PeerManager for StaticPeerManager {
     async fn run(mut self) {
-        // TODO config file watcher
-        // TODO use limit
         for peer in self.config.peers.clone() {
             self.add_endpoint(&peer).await;
         }
+
+        if let Some(path) = self.config_path.clone() {
+            let mut last_modified = modified(&path).await;
+
+            loop {
+                sleep(WATCH_INTERVAL).await;
+
+                let modified = modified(&path).await;
+
+                if modified != last_modified {
+                    last_modified = modified;
+                    info!("Config file \"{}\" changed, reloading static peers.", path.display());
+                    self.reload(&path).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peers(peers: &[&str]) -> Vec<String> {
+        peers.iter().map(|peer| peer.to_string()).collect()
+    }
+
+    #[test]
+    fn diff_peers() {
+        let (added, removed) = diff(
+            &peers(&["tcp://1.1.1.1:15600", "tcp://2.2.2.2:15600"]),
+            &peers(&["tcp://2.2.2.2:15600", "tcp://3.3.3.3:15600", "tcp://3.3.3.3:15600"]),
+        );
+
+        assert_eq!(added, peers(&["tcp://3.3.3.3:15600"]));
+        assert_eq!(removed, peers(&["tcp://1.1.1.1:15600"]));
+    }
+
+    #[test]
+    fn diff_unchanged_peers() {
+        let current = peers(&["tcp://1.1.1.1:15600"]);
+        let (added, removed) = diff(&current, &current);
+
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn parse_peering_section() {
+        let file = toml::from_str::<ConfigFile>(
+            r#"
+            [logger]
+            color_enabled = true
+
+            [peering]
+            [peering.static]
+            limit = 2
+            peers = [ "tcp://1.1.1.1:15600" ]
+            "#,
+        )
+        .unwrap();
+        let config = file.peering.r#static.finish();
+
+        assert_eq!(config.limit, 2);
+        assert_eq!(config.peers, peers(&["tcp://1.1.1.1:15600"]));
     }
 }
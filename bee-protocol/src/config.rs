@@ -25,7 +25,11 @@ const DEFAULT_COO_SPONGE_TYPE: &str = "kerl";
 const DEFAULT_TRANSACTION_WORKER_CACHE: usize = 10000;
 const DEFAULT_RECEIVER_WORKER_BOUND: usize = 10000;
 const DEFAULT_STATUS_INTERVAL: u64 = 10;
+const DEFAULT_MAX_OUTSTANDING_REQUESTS: usize = 100;
+const DEFAULT_MAX_REQUEST_RETRIES: u32 = 10;
 const DEFAULT_HANDSHAKE_WINDOW: u64 = 10;
+const DEFAULT_REPUTATION_THRESHOLD: i64 = -100;
+const DEFAULT_REPUTATION_BAN_DURATION: u64 = 3600;
 
 #[derive(Default, Deserialize)]
 struct ProtocolCoordinatorConfigBuilder {
@@ -40,6 +44,14 @@ struct ProtocolWorkersConfigBuilder {
     transaction_worker_cache: Option<usize>,
     receiver_worker_bound: Option<usize>,
     status_interval: Option<u64>,
+    max_outstanding_requests: Option<usize>,
+    max_request_retries: Option<u32>,
+}
+
+#[derive(Default, Deserialize)]
+struct ProtocolReputationConfigBuilder {
+    threshold: Option<i64>,
+    ban_duration: Option<u64>,
 }
 
 #[derive(Default, Deserialize)]
@@ -47,6 +59,8 @@ pub struct ProtocolConfigBuilder {
     mwm: Option<u8>,
     coordinator: ProtocolCoordinatorConfigBuilder,
     workers: ProtocolWorkersConfigBuilder,
+    #[serde(default)]
+    reputation: ProtocolReputationConfigBuilder,
     handshake_window: Option<u64>,
 }
 
@@ -95,6 +109,26 @@ impl ProtocolConfigBuilder {
         self
     }
 
+    pub fn max_outstanding_requests(mut self, max_outstanding_requests: usize) -> Self {
+        self.workers.max_outstanding_requests.replace(max_outstanding_requests);
+        self
+    }
+
+    pub fn max_request_retries(mut self, max_request_retries: u32) -> Self {
+        self.workers.max_request_retries.replace(max_request_retries);
+        self
+    }
+
+    pub fn reputation_threshold(mut self, reputation_threshold: i64) -> Self {
+        self.reputation.threshold.replace(reputation_threshold);
+        self
+    }
+
+    pub fn reputation_ban_duration(mut self, reputation_ban_duration: u64) -> Self {
+        self.reputation.ban_duration.replace(reputation_ban_duration);
+        self
+    }
+
     pub fn handshake_window(mut self, handshake_window: u64) -> Self {
         self.handshake_window.replace(handshake_window);
         self
@@ -155,6 +189,15 @@ impl ProtocolConfigBuilder {
                     .receiver_worker_bound
                     .unwrap_or(DEFAULT_RECEIVER_WORKER_BOUND),
                 status_interval: self.workers.status_interval.unwrap_or(DEFAULT_STATUS_INTERVAL),
+                max_outstanding_requests: self
+                    .workers
+                    .max_outstanding_requests
+                    .unwrap_or(DEFAULT_MAX_OUTSTANDING_REQUESTS),
+                max_request_retries: self.workers.max_request_retries.unwrap_or(DEFAULT_MAX_REQUEST_RETRIES),
+            },
+            reputation: ProtocolReputationConfig {
+                threshold: self.reputation.threshold.unwrap_or(DEFAULT_REPUTATION_THRESHOLD),
+                ban_duration: self.reputation.ban_duration.unwrap_or(DEFAULT_REPUTATION_BAN_DURATION),
             },
             handshake_window: self.handshake_window.unwrap_or(DEFAULT_HANDSHAKE_WINDOW),
         }
@@ -174,6 +217,10 @@ impl ProtocolCoordinatorConfig {
     pub fn depth(&self) -> u8 {
         self.depth
     }
+
+    pub fn public_key(&self) -> &Address {
+        &self.public_key
+    }
 }
 
 #[derive(Clone)]
@@ -181,6 +228,14 @@ pub struct ProtocolWorkersConfig {
     pub(crate) transaction_worker_cache: usize,
     pub(crate) receiver_worker_bound: usize,
     pub(crate) status_interval: u64,
+    pub(crate) max_outstanding_requests: usize,
+    pub(crate) max_request_retries: u32,
+}
+
+#[derive(Clone)]
+pub struct ProtocolReputationConfig {
+    pub(crate) threshold: i64,
+    pub(crate) ban_duration: u64,
 }
 
 #[derive(Clone)]
@@ -188,6 +243,7 @@ pub struct ProtocolConfig {
     pub(crate) mwm: u8,
     pub(crate) coordinator: ProtocolCoordinatorConfig,
     pub(crate) workers: ProtocolWorkersConfig,
+    pub(crate) reputation: ProtocolReputationConfig,
     pub(crate) handshake_window: u64,
 }
 
@@ -9,16 +9,67 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::Milestone;
+use crate::{tangle::TransactionMetadata, Milestone, MilestoneIndex};
 
+use bee_crypto::ternary::Hash;
 use bee_network::Address;
 
 pub struct HandshakeCompleted(pub Address);
 
+/// A peer was banned because its reputation fell below the configured threshold.
+pub struct PeerBanned {
+    pub address: Address,
+    pub score: i64,
+}
+
 pub struct LastMilestoneChanged(pub Milestone);
 
 pub struct LastSolidMilestoneChanged(pub Milestone);
 
+/// A transaction was inserted in the tangle for the first time.
+pub struct NewTransaction {
+    pub hash: Hash,
+    pub metadata: TransactionMetadata,
+}
+
+/// A transaction and, transitively, its whole past cone became solid.
+pub struct TransactionSolidified {
+    pub hash: Hash,
+    pub metadata: TransactionMetadata,
+}
+
+/// A transaction was confirmed by the milestone in its metadata.
+pub struct TransactionConfirmed {
+    pub hash: Hash,
+    pub metadata: TransactionMetadata,
+}
+
+/// A transaction was confirmed but its ledger mutations conflict and were ignored.
+pub struct TransactionConflicting {
+    pub hash: Hash,
+    pub metadata: TransactionMetadata,
+}
+
+/// A missing transaction was requested from a peer. As it is not in the tangle yet, there is no metadata but the
+/// index of the milestone it is needed by.
+pub struct TransactionRequested {
+    pub hash: Hash,
+    pub index: MilestoneIndex,
+}
+
+/// A requested transaction was not received after the maximum number of retries and is not requested again for a
+/// while.
+pub struct TransactionRequestAbandoned {
+    pub hash: Hash,
+    pub index: MilestoneIndex,
+}
+
+/// A transaction was removed from the tangle, the metadata is the last it had.
+pub struct TransactionPruned {
+    pub hash: Hash,
+    pub metadata: TransactionMetadata,
+}
+
 pub struct TpsMetricsUpdated {
     pub incoming: u64,
     pub new: u64,
@@ -20,4 +20,5 @@ mod protocol;
 mod worker;
 
 pub use milestone::{Milestone, MilestoneIndex};
+pub use peer::{HandshakeState, PeerInfo, PeerMetrics};
 pub use protocol::{Protocol, ProtocolMetrics};
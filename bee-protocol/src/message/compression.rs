@@ -33,14 +33,23 @@ pub(crate) fn compress_transaction_bytes(bytes: &[u8]) -> Vec<u8> {
     compresses_bytes
 }
 
-pub(crate) fn uncompress_transaction_bytes(bytes: &[u8]) -> [u8; TRANSACTION_BYTE_LEN] {
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum UncompressionError {
+    InvalidLength(usize),
+}
+
+pub(crate) fn uncompress_transaction_bytes(bytes: &[u8]) -> Result<[u8; TRANSACTION_BYTE_LEN], UncompressionError> {
+    if bytes.len() < NON_PAYLOAD_SIZE || bytes.len() > TRANSACTION_BYTE_LEN {
+        return Err(UncompressionError::InvalidLength(bytes.len()));
+    }
+
     let mut uncompressed_bytes = [0u8; TRANSACTION_BYTE_LEN];
     let payload_size = bytes.len() - NON_PAYLOAD_SIZE;
 
     uncompressed_bytes[..payload_size].copy_from_slice(&bytes[..payload_size]);
     uncompressed_bytes[MAX_PAYLOAD_SIZE..].copy_from_slice(&bytes[payload_size..]);
 
-    uncompressed_bytes
+    Ok(uncompressed_bytes)
 }
 
 #[cfg(test)]
@@ -48,7 +57,12 @@ mod tests {
 
     use super::*;
 
-    use bee_test::slices::slice_eq;
+    use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf};
+    use bee_test::{slices::slice_eq, strategy::transaction};
+    use bee_transaction::bundled::BundledTransaction as Transaction;
+
+    use bytemuck::cast_slice;
+    use proptest::prelude::*;
 
     const TRANSACTION_BYTES: [u8; TRANSACTION_BYTE_LEN] = [
         65, 96, 90, 85, 12, 26, 72, 235, 46, 55, 188, 26, 78, 102, 37, 58, 8, 38, 115, 187, 46, 82, 85, 36, 70, 253,
@@ -104,9 +118,69 @@ mod tests {
     #[test]
     fn roundtrip_test() {
         let compressed_bytes = compress_transaction_bytes(&TRANSACTION_BYTES);
-        let uncompressed_bytes = uncompress_transaction_bytes(&compressed_bytes);
+        let uncompressed_bytes = uncompress_transaction_bytes(&compressed_bytes).unwrap();
 
         assert_eq!(compressed_bytes.len(), 340);
         assert!(slice_eq(&TRANSACTION_BYTES, &uncompressed_bytes));
     }
+
+    fn transaction_bytes(transaction: &Transaction) -> Vec<u8> {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(Transaction::trit_len());
+        transaction.into_trits_allocated(&mut trits);
+        cast_slice(trits.encode::<T5B1Buf>().as_i8_slice()).to_vec()
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_property(transaction in transaction()) {
+            let bytes = transaction_bytes(&transaction);
+            let compressed_bytes = compress_transaction_bytes(&bytes);
+
+            prop_assert!(slice_eq(&bytes, &uncompress_transaction_bytes(&compressed_bytes).unwrap()));
+        }
+    }
+
+    #[test]
+    fn compress_empty_payload() {
+        let mut bytes = [0u8; TRANSACTION_BYTE_LEN];
+        bytes[MAX_PAYLOAD_SIZE..].copy_from_slice(&[7u8; NON_PAYLOAD_SIZE]);
+
+        assert_eq!(compress_transaction_bytes(&bytes), vec![7u8; NON_PAYLOAD_SIZE]);
+    }
+
+    #[test]
+    fn compress_full_payload() {
+        let mut bytes = [0u8; TRANSACTION_BYTE_LEN];
+        bytes[MAX_PAYLOAD_SIZE - 1] = 1;
+
+        assert!(slice_eq(&compress_transaction_bytes(&bytes), &bytes));
+    }
+
+    #[test]
+    fn compress_keeps_inner_zeros() {
+        let mut bytes = [0u8; TRANSACTION_BYTE_LEN];
+        bytes[0] = 1;
+        bytes[3] = 2;
+        bytes[MAX_PAYLOAD_SIZE] = 3;
+        bytes[TRANSACTION_BYTE_LEN - 1] = 4;
+
+        let mut expected = vec![1, 0, 0, 2, 3];
+        expected.extend_from_slice(&[0u8; NON_PAYLOAD_SIZE - 2]);
+        expected.push(4);
+
+        assert_eq!(compress_transaction_bytes(&bytes), expected);
+    }
+
+    #[test]
+    fn uncompress_invalid_length() {
+        assert_eq!(
+            uncompress_transaction_bytes(&[0u8; NON_PAYLOAD_SIZE - 1]).err(),
+            Some(UncompressionError::InvalidLength(NON_PAYLOAD_SIZE - 1))
+        );
+        assert_eq!(
+            uncompress_transaction_bytes(&[0u8; TRANSACTION_BYTE_LEN + 1]).err(),
+            Some(UncompressionError::InvalidLength(TRANSACTION_BYTE_LEN + 1))
+        );
+        assert!(uncompress_transaction_bytes(&[0u8; NON_PAYLOAD_SIZE]).is_ok());
+    }
 }
@@ -31,6 +31,9 @@ const VARIABLE_MAX_SIZE: usize = 32;
 ///
 /// Contains useful information to verify that the pairing node is operating on the same configuration.
 /// Any difference in configuration will end up in the connection being closed and the nodes not pairing.
+///
+/// NOTE: The layout is shared with Hornet and IRI and must not change. The public key of a node isn't part of it, it
+/// is authenticated by the secure channel instead, when both nodes enable it.
 pub(crate) struct Handshake {
     /// Protocol port of the node.
     pub(crate) port: u16,
@@ -180,4 +183,25 @@ mod tests {
         assert_eq!(message_to.minimum_weight_magnitude, MINIMUM_WEIGHT_MAGNITUDE);
         assert!(slice_eq(&message_to.supported_versions, &SUPPORTED_VERSIONS));
     }
+
+    #[test]
+    fn from_baseline_bytes() {
+        // Port, timestamp, coordinator, MWM, then supported versions, as sent by Hornet and IRI.
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&PORT.to_be_bytes());
+        bytes.extend_from_slice(&0x0000_0174_6d2a_8f10u64.to_be_bytes());
+        bytes.extend_from_slice(&COORDINATOR);
+        bytes.push(MINIMUM_WEIGHT_MAGNITUDE);
+        bytes.extend_from_slice(&SUPPORTED_VERSIONS);
+
+        assert!(Handshake::size_range().contains(&bytes.len()));
+
+        let message = Handshake::from_bytes(&bytes);
+
+        assert_eq!(message.port, PORT);
+        assert_eq!(message.timestamp, 0x0000_0174_6d2a_8f10);
+        assert!(slice_eq(&message.coordinator, &COORDINATOR));
+        assert_eq!(message.minimum_weight_magnitude, MINIMUM_WEIGHT_MAGNITUDE);
+        assert!(slice_eq(&message.supported_versions, &SUPPORTED_VERSIONS));
+    }
 }
@@ -12,14 +12,17 @@
 use crate::{
     message::{Heartbeat, MilestoneRequest, Transaction as TransactionMessage, TransactionRequest},
     milestone::MilestoneIndex,
-    peer::PeerMetrics,
+    peer::{PeerMetrics, Reputation, RttEstimator, MAX_REQUEST_TIMEOUT},
 };
 
-use bee_network::{Address, EndpointId};
+use bee_network::{Address, EndpointId, PublicKey};
 
-use std::sync::{
-    atomic::{AtomicU32, AtomicU8, Ordering},
-    Mutex,
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
 };
 
 use futures::channel::{mpsc, oneshot};
@@ -27,12 +30,16 @@ use futures::channel::{mpsc, oneshot};
 pub struct HandshakedPeer {
     pub(crate) epid: EndpointId,
     pub(crate) address: Address,
+    pub(crate) public_key: Option<PublicKey>,
     pub(crate) metrics: PeerMetrics,
+    pub(crate) reputation: Reputation,
     pub(crate) last_solid_milestone_index: AtomicU32,
     pub(crate) snapshot_milestone_index: AtomicU32,
     pub(crate) last_milestone_index: AtomicU32,
     pub(crate) connected_peers: AtomicU8,
     pub(crate) synced_peers: AtomicU8,
+    pub(crate) outstanding_requests: AtomicUsize,
+    pub(crate) rtt: Mutex<RttEstimator>,
     pub(crate) milestone_request: (
         mpsc::UnboundedSender<MilestoneRequest>,
         Mutex<Option<oneshot::Sender<()>>>,
@@ -52,6 +59,7 @@ impl HandshakedPeer {
     pub(crate) fn new(
         epid: EndpointId,
         address: Address,
+        public_key: Option<PublicKey>,
         milestone_request: (
             mpsc::UnboundedSender<MilestoneRequest>,
             Mutex<Option<oneshot::Sender<()>>>,
@@ -69,12 +77,16 @@ impl HandshakedPeer {
         Self {
             epid,
             address,
+            public_key,
             metrics: PeerMetrics::default(),
+            reputation: Reputation::default(),
             last_solid_milestone_index: AtomicU32::new(0),
             snapshot_milestone_index: AtomicU32::new(0),
             last_milestone_index: AtomicU32::new(0),
             connected_peers: AtomicU8::new(0),
             synced_peers: AtomicU8::new(0),
+            outstanding_requests: AtomicUsize::new(0),
+            rtt: Mutex::new(RttEstimator::default()),
             milestone_request,
             transaction,
             transaction_request,
@@ -121,4 +133,47 @@ impl HandshakedPeer {
     pub(crate) fn synced_peers(&self) -> u8 {
         self.synced_peers.load(Ordering::Relaxed)
     }
+
+    pub(crate) fn outstanding_requests(&self) -> usize {
+        self.outstanding_requests.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn outstanding_requests_inc(&self) {
+        self.outstanding_requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn outstanding_requests_dec(&self) {
+        // Saturates at zero, the peer may have reconnected since the request was sent.
+        let mut current = self.outstanding_requests();
+
+        while current > 0 {
+            match self
+                .outstanding_requests
+                .compare_exchange(current, current - 1, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    pub(crate) fn rtt_sample(&self, rtt: Duration) {
+        if let Ok(mut estimator) = self.rtt.lock() {
+            estimator.sample(rtt);
+        }
+    }
+
+    /// Timeout of a request to the peer, derived from the round-trip time of the previous ones.
+    pub(crate) fn request_timeout(&self) -> Duration {
+        match self.rtt.lock() {
+            Ok(estimator) => estimator.timeout(),
+            Err(_) => MAX_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Returns whether the advertised range of the peer, as learnt from its last heartbeat, can contain the milestone
+    /// `index` and the transactions it confirms.
+    pub(crate) fn has_data(&self, index: MilestoneIndex) -> bool {
+        index > self.snapshot_milestone_index() && index <= self.last_solid_milestone_index()
+    }
 }
@@ -0,0 +1,116 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    milestone::MilestoneIndex,
+    peer::{HandshakedPeer, Peer, PeerMetrics},
+};
+
+use bee_network::{Address, EndpointId, Origin, PublicKey};
+
+use std::sync::Arc;
+
+/// The state of the handshake with a peer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandshakeState {
+    /// The peer is connected but has not completed the handshake yet.
+    Pending,
+    /// The peer completed the handshake and exchanges messages.
+    Completed,
+}
+
+enum Inner {
+    Handshaking(Arc<Peer>),
+    Handshaked(Arc<HandshakedPeer>),
+}
+
+/// A view on a peer known to the protocol, either still handshaking or already handshaked.
+///
+/// Heartbeat milestone indices and metrics are only available once the handshake completed and are read live.
+pub struct PeerInfo(Inner);
+
+impl PeerInfo {
+    pub(crate) fn handshaking(peer: Arc<Peer>) -> Self {
+        Self(Inner::Handshaking(peer))
+    }
+
+    pub(crate) fn handshaked(peer: Arc<HandshakedPeer>) -> Self {
+        Self(Inner::Handshaked(peer))
+    }
+
+    pub fn epid(&self) -> EndpointId {
+        match &self.0 {
+            Inner::Handshaking(peer) => peer.epid,
+            Inner::Handshaked(peer) => peer.epid,
+        }
+    }
+
+    pub fn address(&self) -> &Address {
+        match &self.0 {
+            Inner::Handshaking(peer) => &peer.address,
+            Inner::Handshaked(peer) => &peer.address,
+        }
+    }
+
+    /// Returns the public key identifying the peer, only known if authenticated by a secure connection.
+    pub fn public_key(&self) -> Option<&PublicKey> {
+        match &self.0 {
+            Inner::Handshaking(peer) => peer.public_key.as_ref(),
+            Inner::Handshaked(peer) => peer.public_key.as_ref(),
+        }
+    }
+
+    /// Returns the origin of the connection, only known while handshaking.
+    pub fn origin(&self) -> Option<&Origin> {
+        match &self.0 {
+            Inner::Handshaking(peer) => Some(&peer.origin),
+            Inner::Handshaked(_) => None,
+        }
+    }
+
+    pub fn handshake_state(&self) -> HandshakeState {
+        match &self.0 {
+            Inner::Handshaking(_) => HandshakeState::Pending,
+            Inner::Handshaked(_) => HandshakeState::Completed,
+        }
+    }
+
+    fn handshaked_peer(&self) -> Option<&HandshakedPeer> {
+        match &self.0 {
+            Inner::Handshaking(_) => None,
+            Inner::Handshaked(peer) => Some(peer.as_ref()),
+        }
+    }
+
+    pub fn last_solid_milestone_index(&self) -> Option<MilestoneIndex> {
+        self.handshaked_peer().map(|peer| peer.last_solid_milestone_index())
+    }
+
+    pub fn snapshot_milestone_index(&self) -> Option<MilestoneIndex> {
+        self.handshaked_peer().map(|peer| peer.snapshot_milestone_index())
+    }
+
+    pub fn last_milestone_index(&self) -> Option<MilestoneIndex> {
+        self.handshaked_peer().map(|peer| peer.last_milestone_index())
+    }
+
+    pub fn connected_peers(&self) -> Option<u8> {
+        self.handshaked_peer().map(|peer| peer.connected_peers())
+    }
+
+    pub fn synced_peers(&self) -> Option<u8> {
+        self.handshaked_peer().map(|peer| peer.synced_peers())
+    }
+
+    pub fn metrics(&self) -> Option<&PeerMetrics> {
+        self.handshaked_peer().map(|peer| &peer.metrics)
+    }
+}
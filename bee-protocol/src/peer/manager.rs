@@ -9,8 +9,6 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-// TODO get peer info
-
 use crate::{
     message::{Heartbeat, MilestoneRequest, Transaction as TransactionMessage, TransactionRequest},
     peer::{HandshakedPeer, Peer},
@@ -18,7 +16,7 @@ use crate::{
 };
 
 use bee_common::shutdown_stream::ShutdownStream;
-use bee_network::{Address, EndpointId, Network};
+use bee_network::{Address, EndpointId, Network, PublicKey};
 
 use async_std::{sync::RwLock, task::spawn};
 use dashmap::DashMap;
@@ -27,11 +25,16 @@ use log::warn;
 
 use std::sync::{Arc, Mutex};
 
+// NOTE: Peers are keyed by the id of their endpoint, i.e. their address, as long as the network is. Their authenticated
+// public key, only known if the connection is secure, is indexed on the side to detect a peer connecting again from
+// another address.
 pub(crate) struct PeerManager {
     network: Network,
     pub(crate) peers: DashMap<EndpointId, Arc<Peer>>,
     pub(crate) handshaked_peers: DashMap<EndpointId, Arc<HandshakedPeer>>,
     pub(crate) handshaked_peers_keys: RwLock<Vec<EndpointId>>,
+    // Handshaked peers by authenticated public key, the stable identity of a peer whatever its address.
+    pub(crate) identities: DashMap<PublicKey, EndpointId>,
 }
 
 impl PeerManager {
@@ -41,6 +44,7 @@ impl PeerManager {
             peers: Default::default(),
             handshaked_peers: Default::default(),
             handshaked_peers_keys: Default::default(),
+            identities: Default::default(),
         }
     }
 
@@ -48,7 +52,12 @@ impl PeerManager {
         self.peers.insert(peer.epid, peer);
     }
 
-    pub(crate) async fn handshake(&self, epid: &EndpointId, address: Address) {
+    /// Returns the id of the endpoint a peer, identified by its public key, is handshaked on.
+    pub(crate) fn epid(&self, public_key: &PublicKey) -> Option<EndpointId> {
+        self.identities.get(public_key).map(|epid| *epid)
+    }
+
+    pub(crate) async fn handshake(&self, epid: &EndpointId, address: Address, public_key: Option<PublicKey>) {
         if self.peers.remove(epid).is_some() {
             // TODO check if not already added
 
@@ -71,6 +80,7 @@ impl PeerManager {
             let peer = Arc::new(HandshakedPeer::new(
                 *epid,
                 address,
+                public_key,
                 (milestone_request_tx, Mutex::new(Some(milestone_request_shutdown_tx))),
                 (transaction_tx, Mutex::new(Some(transaction_shutdown_tx))),
                 (
@@ -80,6 +90,9 @@ impl PeerManager {
                 (heartbeat_tx, Mutex::new(Some(heartbeat_shutdown_tx))),
             ));
 
+            if let Some(public_key) = public_key {
+                self.identities.insert(public_key, *epid);
+            }
             self.handshaked_peers.insert(*epid, peer.clone());
             self.handshaked_peers_keys.write().await.push(*epid);
 
@@ -127,6 +140,10 @@ impl PeerManager {
         self.handshaked_peers_keys.write().await.retain(|e| e != epid);
 
         if let Some((_, peer)) = self.handshaked_peers.remove(epid) {
+            if let Some(public_key) = &peer.public_key {
+                self.identities.remove(public_key);
+            }
+
             if let Ok(mut shutdown) = peer.milestone_request.1.lock() {
                 if let Some(shutdown) = shutdown.take() {
                     if let Err(e) = shutdown.send(()) {
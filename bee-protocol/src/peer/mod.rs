@@ -10,11 +10,17 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 mod handshaked_peer;
+mod info;
 mod manager;
 mod metrics;
 mod peer;
+mod reputation;
+mod rtt;
 
 pub(crate) use handshaked_peer::HandshakedPeer;
+pub use info::{HandshakeState, PeerInfo};
 pub(crate) use manager::PeerManager;
-pub(crate) use metrics::PeerMetrics;
+pub use metrics::PeerMetrics;
 pub(crate) use peer::Peer;
+pub(crate) use reputation::{Misbehaviour, Reputation};
+pub(crate) use rtt::{RttEstimator, MAX_REQUEST_TIMEOUT};
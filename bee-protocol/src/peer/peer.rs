@@ -9,16 +9,23 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use bee_network::{Address, EndpointId, Origin};
+use bee_network::{Address, EndpointId, Origin, PublicKey};
 
 pub(crate) struct Peer {
     pub(crate) epid: EndpointId,
     pub(crate) address: Address,
     pub(crate) origin: Origin,
+    // Public key authenticated by the network layer, if the connection is secure.
+    pub(crate) public_key: Option<PublicKey>,
 }
 
 impl Peer {
-    pub fn new(epid: EndpointId, address: Address, origin: Origin) -> Self {
-        Self { epid, address, origin }
+    pub fn new(epid: EndpointId, address: Address, origin: Origin, public_key: Option<PublicKey>) -> Self {
+        Self {
+            epid,
+            address,
+            origin,
+            public_key,
+        }
     }
 }
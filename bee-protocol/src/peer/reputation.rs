@@ -0,0 +1,165 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Highest reputation a peer can build up, so that a long history of good behaviour can't hide a sudden flood of
+/// invalid messages.
+pub(crate) const MAX_REPUTATION: i64 = 100;
+
+/// Highest penalty a peer can get for unanswered requests within `UNANSWERED_REQUESTS_INTERVAL`, so that a slow or
+/// overloaded peer isn't banned for a burst of timeouts.
+pub(crate) const MAX_UNANSWERED_REQUESTS_PENALTY: i64 = 5;
+
+pub(crate) const UNANSWERED_REQUESTS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A misbehaviour of a peer, lowering its reputation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Misbehaviour {
+    /// The peer sent a message that couldn't be read or is of an unsupported type.
+    InvalidMessage,
+    /// The peer sent a malformed transaction or one with an insufficient proof of work.
+    InvalidTransaction,
+    /// The peer sent a transaction with a timestamp outside of the allowed window.
+    StaleTransaction,
+    /// The peer didn't answer in time a request for data its advertised range covered.
+    UnansweredRequest,
+}
+
+impl Misbehaviour {
+    pub(crate) fn penalty(&self) -> i64 {
+        match self {
+            Misbehaviour::InvalidMessage => 20,
+            Misbehaviour::InvalidTransaction => 10,
+            Misbehaviour::StaleTransaction => 2,
+            Misbehaviour::UnansweredRequest => 1,
+        }
+    }
+}
+
+/// Reputation of a handshaked peer; it starts at zero, grows with every new transaction the peer sends and drops with
+/// every misbehaviour.
+#[derive(Default)]
+pub(crate) struct Reputation {
+    score: AtomicI64,
+    banned: AtomicBool,
+    // Start of the current interval of unanswered requests and the penalty they got so far.
+    unanswered: Mutex<Option<(Instant, i64)>>,
+}
+
+impl Reputation {
+    pub(crate) fn score(&self) -> i64 {
+        self.score.load(Ordering::Relaxed)
+    }
+
+    /// Lowers the reputation according to the misbehaviour and returns the new score.
+    pub(crate) fn penalise(&self, misbehaviour: Misbehaviour) -> i64 {
+        let penalty = match misbehaviour {
+            Misbehaviour::UnansweredRequest => self.unanswered_penalty(misbehaviour.penalty()),
+            _ => misbehaviour.penalty(),
+        };
+
+        self.score.fetch_sub(penalty, Ordering::SeqCst) - penalty
+    }
+
+    /// Part of `penalty` still allowed for unanswered requests within the current interval.
+    fn unanswered_penalty(&self, penalty: i64) -> i64 {
+        let mut unanswered = match self.unanswered.lock() {
+            Ok(unanswered) => unanswered,
+            Err(_) => return 0,
+        };
+        let now = Instant::now();
+
+        let (since, total) = match *unanswered {
+            Some((since, total)) if now - since < UNANSWERED_REQUESTS_INTERVAL => (since, total),
+            _ => (now, 0),
+        };
+        let penalty = penalty.min(MAX_UNANSWERED_REQUESTS_PENALTY - total);
+
+        unanswered.replace((since, total + penalty));
+
+        penalty
+    }
+
+    /// Raises the reputation by one, up to `MAX_REPUTATION`, and returns the new score.
+    pub(crate) fn reward(&self) -> i64 {
+        // Only the transaction processor rewards peers, so rewards never race with each other.
+        if self.score() < MAX_REPUTATION {
+            self.score.fetch_add(1, Ordering::SeqCst) + 1
+        } else {
+            MAX_REPUTATION
+        }
+    }
+
+    /// Marks the peer as banned and returns `true` if it wasn't already.
+    pub(crate) fn ban(&self) -> bool {
+        !self.banned.swap(true, Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn penalise_lowers_score() {
+        let reputation = Reputation::default();
+
+        assert_eq!(reputation.penalise(Misbehaviour::InvalidMessage), -20);
+        assert_eq!(reputation.penalise(Misbehaviour::UnansweredRequest), -21);
+        assert_eq!(reputation.score(), -21);
+    }
+
+    #[test]
+    fn unanswered_requests_penalty_is_capped() {
+        let reputation = Reputation::default();
+
+        for _ in 0..MAX_UNANSWERED_REQUESTS_PENALTY * 2 {
+            reputation.penalise(Misbehaviour::UnansweredRequest);
+        }
+
+        assert_eq!(reputation.score(), -MAX_UNANSWERED_REQUESTS_PENALTY);
+        assert_eq!(
+            reputation.penalise(Misbehaviour::InvalidMessage),
+            -MAX_UNANSWERED_REQUESTS_PENALTY - 20
+        );
+    }
+
+    #[test]
+    fn reward_is_capped() {
+        let reputation = Reputation::default();
+
+        for _ in 0..MAX_REPUTATION + 10 {
+            reputation.reward();
+        }
+
+        assert_eq!(reputation.score(), MAX_REPUTATION);
+        assert_eq!(
+            reputation.penalise(Misbehaviour::InvalidTransaction),
+            MAX_REPUTATION - 10
+        );
+    }
+
+    #[test]
+    fn ban_only_once() {
+        let reputation = Reputation::default();
+
+        assert!(reputation.ban());
+        assert!(!reputation.ban());
+    }
+}
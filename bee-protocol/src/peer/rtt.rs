@@ -0,0 +1,99 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use std::time::Duration;
+
+/// Timeout of a request to a peer whose round-trip time hasn't been measured yet.
+pub(crate) const INITIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Bounds of the timeout of a request, whatever the measured round-trip time.
+pub(crate) const MIN_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+pub(crate) const MAX_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Estimates the round-trip time of the requests to a peer, and derives their timeout from it, as TCP does (RFC 6298).
+#[derive(Default)]
+pub(crate) struct RttEstimator {
+    // Smoothed round-trip time, if it has been measured at least once.
+    srtt: Option<Duration>,
+    rttvar: Duration,
+}
+
+impl RttEstimator {
+    pub(crate) fn sample(&mut self, rtt: Duration) {
+        match self.srtt {
+            None => {
+                self.srtt = Some(rtt);
+                self.rttvar = rtt / 2;
+            }
+            Some(srtt) => {
+                let delta = if srtt > rtt { srtt - rtt } else { rtt - srtt };
+
+                self.rttvar = (self.rttvar * 3 + delta) / 4;
+                self.srtt = Some((srtt * 7 + rtt) / 8);
+            }
+        }
+    }
+
+    pub(crate) fn timeout(&self) -> Duration {
+        match self.srtt {
+            None => INITIAL_REQUEST_TIMEOUT,
+            Some(srtt) => (srtt + self.rttvar * 4)
+                .max(MIN_REQUEST_TIMEOUT)
+                .min(MAX_REQUEST_TIMEOUT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn initial_timeout() {
+        let estimator = RttEstimator::default();
+
+        assert_eq!(estimator.srtt, None);
+        assert_eq!(estimator.timeout(), INITIAL_REQUEST_TIMEOUT);
+    }
+
+    #[test]
+    fn first_sample() {
+        let mut estimator = RttEstimator::default();
+
+        estimator.sample(Duration::from_millis(800));
+
+        assert_eq!(estimator.srtt, Some(Duration::from_millis(800)));
+        assert_eq!(estimator.timeout(), Duration::from_millis(2400));
+    }
+
+    #[test]
+    fn converges() {
+        let mut estimator = RttEstimator::default();
+
+        for _ in 0..100 {
+            estimator.sample(Duration::from_millis(2000));
+        }
+
+        assert_eq!(estimator.srtt, Some(Duration::from_millis(2000)));
+        assert!(estimator.timeout() < Duration::from_millis(2100));
+    }
+
+    #[test]
+    fn bounded_timeout() {
+        let mut estimator = RttEstimator::default();
+
+        estimator.sample(Duration::from_millis(10));
+        assert_eq!(estimator.timeout(), MIN_REQUEST_TIMEOUT);
+
+        estimator.sample(Duration::from_secs(600));
+        assert_eq!(estimator.timeout(), MAX_REQUEST_TIMEOUT);
+    }
+}
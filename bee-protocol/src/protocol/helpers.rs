@@ -10,24 +10,74 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    message::{Heartbeat, Transaction as TransactionMessage},
+    config::ProtocolConfig,
+    event::PeerBanned,
+    message::{compress_transaction_bytes, Heartbeat, Transaction as TransactionMessage},
     milestone::MilestoneIndex,
-    protocol::Protocol,
+    peer::{Misbehaviour, PeerInfo, PeerMetrics},
+    protocol::{Protocol, ProtocolMetrics},
     tangle::tangle,
     worker::{
-        BroadcasterWorkerEvent, MilestoneRequesterWorkerEntry, MilestoneSolidifierWorkerEvent, SenderWorker,
-        TransactionRequesterWorkerEntry, TransactionSolidifierWorkerEvent,
+        BroadcasterWorkerEvent, HasherWorkerEvent, MilestoneRequesterWorkerEntry, MilestoneSolidifierWorkerEvent,
+        SenderWorker, SolidPropagatorWorkerEvent, TransactionRequesterWorkerEntry, TransactionSolidifierWorkerEvent,
     },
 };
 
 use bee_crypto::ternary::Hash;
-use bee_network::EndpointId;
+use bee_network::{Address, Command::BanEndpoint, EndpointId};
+use bee_ternary::{T1B1Buf, T5B1Buf, TritBuf};
+use bee_transaction::bundled::BundledTransaction as Transaction;
 
+use async_std::task::spawn;
+use bytemuck::cast_slice;
 use log::warn;
 
+use std::time::{Duration, Instant};
+
 const MILESTONE_REQUEST_RANGE: usize = 50;
 
 impl Protocol {
+    // Info
+
+    pub fn config() -> &'static ProtocolConfig {
+        &Protocol::get().config
+    }
+
+    pub fn metrics() -> &'static ProtocolMetrics {
+        &Protocol::get().metrics
+    }
+
+    pub fn num_handshaked_peers() -> usize {
+        Protocol::get().peer_manager.handshaked_peers.len()
+    }
+
+    pub fn num_requested_transactions() -> usize {
+        Protocol::get().requested_transactions.len()
+    }
+
+    /// Lists the peers that are either handshaking or handshaked.
+    pub fn peers() -> Vec<PeerInfo> {
+        let peer_manager = &Protocol::get().peer_manager;
+
+        peer_manager
+            .peers
+            .iter()
+            .map(|peer| PeerInfo::handshaking(peer.value().clone()))
+            .chain(
+                peer_manager
+                    .handshaked_peers
+                    .iter()
+                    .map(|peer| PeerInfo::handshaked(peer.value().clone())),
+            )
+            .collect()
+    }
+
+    pub fn for_each_handshaked_peer<F: FnMut(&Address, &PeerMetrics)>(mut f: F) {
+        for peer in Protocol::get().peer_manager.handshaked_peers.iter() {
+            f(&peer.address, &peer.metrics);
+        }
+    }
+
     // MilestoneRequest
 
     pub fn request_milestone(index: MilestoneIndex, to: Option<EndpointId>) {
@@ -82,6 +132,33 @@ impl Protocol {
         Protocol::broadcast_transaction_message(source, TransactionMessage::new(transaction));
     }
 
+    fn submit(transaction: &Transaction, broadcast: bool) {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(Transaction::trit_len());
+        transaction.into_trits_allocated(&mut trits);
+        let bytes = compress_transaction_bytes(cast_slice(trits.encode::<T5B1Buf>().as_i8_slice()));
+
+        if let Err(e) = Protocol::get().hasher_worker.unbounded_send(HasherWorkerEvent {
+            from: None,
+            received: Instant::now(),
+            transaction: TransactionMessage::new(&bytes),
+            broadcast,
+        }) {
+            warn!("Submitting transaction failed: {}.", e);
+        }
+    }
+
+    // Goes through the whole processing pipeline, exactly like a transaction received from a peer, which means it is
+    // validated, stored and broadcast to all peers.
+    pub fn submit_transaction(transaction: &Transaction) {
+        Protocol::submit(transaction, true);
+    }
+
+    // Goes through the whole processing pipeline like `submit_transaction`, except that the transaction is not
+    // broadcast.
+    pub fn store_transaction(transaction: &Transaction) {
+        Protocol::submit(transaction, false);
+    }
+
     // TransactionRequest
 
     pub fn request_transaction(hash: Hash, index: MilestoneIndex) {
@@ -140,6 +217,18 @@ impl Protocol {
         }
     }
 
+    /// Solidifies the transaction of hash `hash` and its children, as far as their parents are solid.
+    ///
+    /// Needed for transactions inserted in the tangle by other means than the processor worker.
+    pub fn propagate_solidity(hash: Hash) {
+        if let Err(e) = Protocol::get()
+            .solid_propagator_worker
+            .unbounded_send(SolidPropagatorWorkerEvent(hash))
+        {
+            warn!("Triggering solidity propagation failed: {}.", e);
+        }
+    }
+
     pub fn trigger_milestone_solidification() {
         if let Err(e) = Protocol::get()
             .milestone_solidifier_worker
@@ -148,4 +237,46 @@ impl Protocol {
             warn!("Triggering milestone solidification failed: {}.", e);
         }
     }
+
+    // Reputation
+
+    /// Lowers the reputation of a handshaked peer and, if it falls below the configured threshold, bans it.
+    pub(crate) fn penalise_peer(epid: EndpointId, misbehaviour: Misbehaviour) {
+        let peer = match Protocol::get().peer_manager.handshaked_peers.get(&epid) {
+            Some(peer) => peer.value().clone(),
+            None => return,
+        };
+
+        let score = peer.reputation.penalise(misbehaviour);
+
+        if score >= Protocol::get().config.reputation.threshold || !peer.reputation.ban() {
+            return;
+        }
+
+        warn!(
+            "[{}] Banning peer, reputation {} after {:?}.",
+            peer.address, score, misbehaviour
+        );
+
+        let mut network = Protocol::get().network.clone();
+        let duration = Duration::from_secs(Protocol::get().config.reputation.ban_duration);
+
+        spawn(async move {
+            if let Err(e) = network
+                .send(BanEndpoint {
+                    epid,
+                    duration,
+                    responder: None,
+                })
+                .await
+            {
+                warn!("[{}] Banning peer failed: {}.", epid, e);
+            }
+        });
+
+        Protocol::get().bus.dispatch(PeerBanned {
+            address: peer.address,
+            score,
+        });
+    }
 }
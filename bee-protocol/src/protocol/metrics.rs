@@ -34,6 +34,11 @@ pub struct ProtocolMetrics {
     non_value_transactions: AtomicU64,
     confirmed_transactions: AtomicU64,
     conflicting_transactions: AtomicU64,
+
+    transaction_requests_answered: AtomicU64,
+    transaction_requests_retried: AtomicU64,
+    transaction_requests_abandoned: AtomicU64,
+    transaction_request_latency: AtomicU64,
 }
 
 impl ProtocolMetrics {
@@ -178,6 +183,40 @@ impl ProtocolMetrics {
     pub(crate) fn conflicting_transactions_inc(&self) -> u64 {
         self.conflicting_transactions.fetch_add(1, Ordering::SeqCst)
     }
+
+    pub fn transaction_requests_answered(&self) -> u64 {
+        self.transaction_requests_answered.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn transaction_requests_answered_inc(&self) -> u64 {
+        self.transaction_requests_answered.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn transaction_requests_retried(&self) -> u64 {
+        self.transaction_requests_retried.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn transaction_requests_retried_inc(&self) -> u64 {
+        self.transaction_requests_retried.fetch_add(1, Ordering::SeqCst)
+    }
+
+    pub fn transaction_requests_abandoned(&self) -> u64 {
+        self.transaction_requests_abandoned.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn transaction_requests_abandoned_inc(&self) -> u64 {
+        self.transaction_requests_abandoned.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Cumulated time, in milliseconds, between the first request of a transaction and its reception; divided by
+    /// `transaction_requests_answered` it gives the average latency.
+    pub fn transaction_request_latency(&self) -> u64 {
+        self.transaction_request_latency.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn transaction_request_latency_add(&self, latency: u64) -> u64 {
+        self.transaction_request_latency.fetch_add(latency, Ordering::SeqCst)
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +306,25 @@ mod tests {
         assert_eq!(metrics.confirmed_transactions(), 1);
         assert_eq!(metrics.conflicting_transactions(), 1);
     }
+
+    #[test]
+    fn protocol_metrics_transaction_requests() {
+        let metrics = ProtocolMetrics::default();
+
+        assert_eq!(metrics.transaction_requests_answered(), 0);
+        assert_eq!(metrics.transaction_requests_retried(), 0);
+        assert_eq!(metrics.transaction_requests_abandoned(), 0);
+        assert_eq!(metrics.transaction_request_latency(), 0);
+
+        metrics.transaction_requests_answered_inc();
+        metrics.transaction_requests_retried_inc();
+        metrics.transaction_requests_abandoned_inc();
+        metrics.transaction_request_latency_add(42);
+        metrics.transaction_request_latency_add(58);
+
+        assert_eq!(metrics.transaction_requests_answered(), 1);
+        assert_eq!(metrics.transaction_requests_retried(), 1);
+        assert_eq!(metrics.transaction_requests_abandoned(), 1);
+        assert_eq!(metrics.transaction_request_latency(), 100);
+    }
 }
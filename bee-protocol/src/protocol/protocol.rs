@@ -20,9 +20,9 @@ use crate::{
         BroadcasterWorker, BroadcasterWorkerEvent, HasherWorker, HasherWorkerEvent, MilestoneRequesterWorker,
         MilestoneRequesterWorkerEntry, MilestoneResponderWorker, MilestoneResponderWorkerEvent,
         MilestoneSolidifierWorker, MilestoneSolidifierWorkerEvent, MilestoneValidatorWorker, PeerHandshakerWorker,
-        ProcessorWorker, StatusWorker, TpsWorker, TransactionRequesterWorker, TransactionRequesterWorkerEntry,
-        TransactionResponderWorker, TransactionResponderWorkerEvent, TransactionSolidifierWorker,
-        TransactionSolidifierWorkerEvent,
+        ProcessorWorker, RequestedTransaction, SolidPropagatorWorker, SolidPropagatorWorkerEvent, StatusWorker,
+        TpsWorker, TransactionRequesterWorker, TransactionRequesterWorkerEntry, TransactionResponderWorker,
+        TransactionResponderWorkerEvent, TransactionSolidifierWorker, TransactionSolidifierWorkerEvent,
     },
 };
 
@@ -32,7 +32,7 @@ use bee_crypto::ternary::{
     sponge::{CurlP27, CurlP81, Kerl, SpongeKind},
     Hash,
 };
-use bee_network::{Address, EndpointId, Network, Origin};
+use bee_network::{Address, EndpointId, Network, Origin, PublicKey};
 use bee_signing::ternary::wots::WotsPublicKey;
 
 use async_std::task::spawn;
@@ -47,6 +47,7 @@ static mut PROTOCOL: *const Protocol = ptr::null();
 pub struct Protocol {
     pub(crate) config: ProtocolConfig,
     pub(crate) network: Network,
+    pub(crate) public_key: PublicKey,
     // TODO temporary
     pub(crate) local_snapshot_timestamp: u64,
     pub(crate) bus: Arc<Bus<'static>>,
@@ -58,16 +59,21 @@ pub struct Protocol {
     pub(crate) milestone_requester_worker: WaitPriorityQueue<MilestoneRequesterWorkerEntry>,
     pub(crate) transaction_solidifier_worker: mpsc::UnboundedSender<TransactionSolidifierWorkerEvent>,
     pub(crate) milestone_solidifier_worker: mpsc::UnboundedSender<MilestoneSolidifierWorkerEvent>,
+    pub(crate) solid_propagator_worker: mpsc::UnboundedSender<SolidPropagatorWorkerEvent>,
     pub(crate) broadcaster_worker: mpsc::UnboundedSender<BroadcasterWorkerEvent>,
     pub(crate) peer_manager: PeerManager,
-    pub(crate) requested_transactions: DashMap<Hash, (MilestoneIndex, Instant)>,
-    pub(crate) requested_milestones: DashMap<MilestoneIndex, Instant>,
+    pub(crate) requested_transactions: DashMap<Hash, RequestedTransaction>,
+    // Transactions whose requests were abandoned, and when, not to be requested again right away.
+    pub(crate) abandoned_transactions: DashMap<Hash, Instant>,
+    // Requested milestones, with the peer the request was sent to, when, and whether it was only a fallback.
+    pub(crate) requested_milestones: DashMap<MilestoneIndex, (EndpointId, Instant, bool)>,
 }
 
 impl Protocol {
     pub async fn init(
         config: ProtocolConfig,
         network: Network,
+        public_key: PublicKey,
         local_snapshot_timestamp: u64,
         bus: Arc<Bus<'static>>,
         shutdown: &mut Shutdown,
@@ -96,6 +102,9 @@ impl Protocol {
         let (milestone_validator_worker_tx, milestone_validator_worker_rx) = mpsc::unbounded();
         let (milestone_validator_worker_shutdown_tx, milestone_validator_worker_shutdown_rx) = oneshot::channel();
 
+        let (solid_propagator_worker_tx, solid_propagator_worker_rx) = mpsc::unbounded();
+        let (solid_propagator_worker_shutdown_tx, solid_propagator_worker_shutdown_rx) = oneshot::channel();
+
         let (transaction_solidifier_worker_tx, transaction_solidifier_worker_rx) = mpsc::unbounded();
         let (transaction_solidifier_worker_shutdown_tx, transaction_solidifier_worker_shutdown_rx) = oneshot::channel();
 
@@ -112,6 +121,7 @@ impl Protocol {
         let protocol = Protocol {
             config,
             network: network.clone(),
+            public_key,
             local_snapshot_timestamp,
             bus,
             metrics: ProtocolMetrics::new(),
@@ -122,9 +132,11 @@ impl Protocol {
             milestone_requester_worker: Default::default(),
             transaction_solidifier_worker: transaction_solidifier_worker_tx,
             milestone_solidifier_worker: milestone_solidifier_worker_tx,
+            solid_propagator_worker: solid_propagator_worker_tx.clone(),
             broadcaster_worker: broadcaster_worker_tx,
             peer_manager: PeerManager::new(network.clone()),
             requested_transactions: Default::default(),
+            abandoned_transactions: Default::default(),
             requested_milestones: Default::default(),
         };
 
@@ -153,6 +165,7 @@ impl Protocol {
             spawn(
                 ProcessorWorker::new(
                     milestone_validator_worker_tx,
+                    solid_propagator_worker_tx,
                     ShutdownStream::new(processor_worker_shutdown_rx, processor_worker_rx),
                 )
                 .run(),
@@ -236,6 +249,17 @@ impl Protocol {
             ),
         };
 
+        shutdown.add_worker_shutdown(
+            solid_propagator_worker_shutdown_tx,
+            spawn(
+                SolidPropagatorWorker::new(ShutdownStream::new(
+                    solid_propagator_worker_shutdown_rx,
+                    solid_propagator_worker_rx,
+                ))
+                .run(),
+            ),
+        );
+
         shutdown.add_worker_shutdown(
             transaction_solidifier_worker_shutdown_tx,
             spawn(
@@ -288,14 +312,46 @@ impl Protocol {
         }
     }
 
+    /// Initializes the network, the tangle and the protocol, once for all the tests of the crate since they are
+    /// singletons.
+    #[cfg(test)]
+    pub(crate) fn init_for_tests() {
+        use bee_network::{Identity, NetworkConfig};
+
+        use async_std::task::block_on;
+        use std::sync::Once;
+
+        static INIT: Once = Once::new();
+
+        INIT.call_once(|| {
+            // Dropping the shutdown would stop the workers.
+            let shutdown = Box::leak(Box::new(Shutdown::new()));
+            let identity = Identity::generate();
+            let public_key = *identity.public_key();
+            let (network, _) = bee_network::init(NetworkConfig::build().finish(), identity, shutdown);
+
+            crate::tangle::init();
+
+            block_on(Protocol::init(
+                ProtocolConfig::build().finish(),
+                network,
+                public_key,
+                0,
+                Arc::new(Bus::default()),
+                shutdown,
+            ));
+        });
+    }
+
     pub fn register(
         epid: EndpointId,
         address: Address,
         origin: Origin,
+        public_key: Option<PublicKey>,
     ) -> (mpsc::Sender<Vec<u8>>, oneshot::Sender<()>) {
         // TODO check if not already added ?
 
-        let peer = Arc::new(Peer::new(epid, address, origin));
+        let peer = Arc::new(Peer::new(epid, address, origin, public_key));
 
         let (receiver_tx, receiver_rx) = mpsc::channel(Protocol::get().config.workers.receiver_worker_bound);
         let (receiver_shutdown_tx, receiver_shutdown_rx) = oneshot::channel();
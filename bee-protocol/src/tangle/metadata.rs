@@ -50,6 +50,18 @@ impl TransactionMetadata {
         self.milestone_index = index;
     }
 
+    pub fn arrival_timestamp(&self) -> u64 {
+        self.arrival_timestamp
+    }
+
+    pub fn solidification_timestamp(&self) -> u64 {
+        self.solidification_timestamp
+    }
+
+    pub fn confirmation_timestamp(&self) -> u64 {
+        self.confirmation_timestamp
+    }
+
     pub fn set_confirmation_timestamp(&mut self, timestamp: u64) {
         self.confirmation_timestamp = timestamp;
     }
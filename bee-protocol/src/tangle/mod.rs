@@ -14,27 +14,18 @@ mod metadata;
 
 pub use metadata::TransactionMetadata;
 
-// TODO: reinstate the async worker
-// pub(crate) mod propagator;
-
-use crate::{
-    event::LastSolidMilestoneChanged,
-    milestone::{Milestone, MilestoneIndex},
-    protocol::Protocol,
-    tangle::flags::Flags,
-};
+use crate::{event::TransactionPruned, milestone::MilestoneIndex, protocol::Protocol, tangle::flags::Flags};
 
 use bee_crypto::ternary::Hash;
 use bee_tangle::{Tangle, TransactionRef as TxRef};
-use bee_transaction::{bundled::BundledTransaction as Tx, Vertex};
+use bee_transaction::bundled::{Address, BundledTransaction as Tx};
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 
 use std::{
     ops::Deref,
     ptr,
     sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering},
-    time::{SystemTime, UNIX_EPOCH},
 };
 
 /// Milestone-based Tangle.
@@ -42,6 +33,8 @@ pub struct MsTangle {
     pub(crate) inner: Tangle<TransactionMetadata>,
     pub(crate) milestones: DashMap<MilestoneIndex, Hash>,
     pub(crate) solid_entry_points: DashMap<Hash, MilestoneIndex>,
+    // Addresses spent from by a bundle confirmed since the node started.
+    pub(crate) spent_addresses: DashSet<Address>,
     last_milestone_index: AtomicU32,
     last_solid_milestone_index: AtomicU32,
     snapshot_milestone_index: AtomicU32,
@@ -61,55 +54,31 @@ impl MsTangle {
             inner: Tangle::new(),
             milestones: DashMap::new(),
             solid_entry_points: DashMap::new(),
+            spent_addresses: DashSet::new(),
             last_milestone_index: AtomicU32::new(0),
             last_solid_milestone_index: AtomicU32::new(0),
             snapshot_milestone_index: AtomicU32::new(0),
         }
     }
 
+    /// Inserts a transaction without propagating solidity.
+    ///
+    /// The transaction and its children only get solidified once its hash is sent to the solid propagator worker,
+    /// which the processor worker does for every transaction it inserts. Transactions inserted from anywhere else keep
+    /// their solid flag as given in `metadata` until `Protocol::propagate_solidity` is called with their hash.
     pub fn insert(&self, transaction: Tx, hash: Hash, metadata: TransactionMetadata) -> Option<TxRef> {
-        if let Some(tx) = self.inner.insert(hash, transaction, metadata) {
-            self.propagate_solid_flag(hash);
-            return Some(tx);
-        }
-        None
+        self.inner.insert(hash, transaction, metadata)
     }
 
-    // NOTE: not implemented as an async worker atm, but it makes things much easier
-    #[inline]
-    fn propagate_solid_flag(&self, root: Hash) {
-        let mut children = vec![root];
-
-        while let Some(ref hash) = children.pop() {
-            if self.is_solid_transaction(hash) {
-                continue;
-            }
-
-            if let Some(tx) = self.inner.get(&hash) {
-                if self.is_solid_transaction(tx.trunk()) && self.is_solid_transaction(tx.branch()) {
-                    self.inner.update_metadata(&hash, |metadata| {
-                        metadata.flags.set_solid();
-                        // This is possibly not sufficient as there is no guarantee a milestone has been validated
-                        // before being solidified, we then also need to check when a milestone gets validated if it's
-                        // already solid.
-                        if metadata.flags.is_milestone() {
-                            Protocol::get().bus.dispatch(LastSolidMilestoneChanged(Milestone {
-                                hash: *hash,
-                                index: metadata.milestone_index,
-                            }));
-                        }
-                        metadata.solidification_timestamp = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .expect("Clock may have gone backwards")
-                            .as_millis() as u64;
-                    });
-
-                    for child in self.inner.get_children(&hash) {
-                        children.push(child);
-                    }
-                }
-            }
-        }
+    /// Removes a transaction from the tangle and notifies it with a `TransactionPruned` event.
+    pub fn prune(&self, hash: &Hash) -> Option<TxRef> {
+        let (tx, metadata) = self.inner.remove(hash)?;
+
+        Protocol::get()
+            .bus
+            .dispatch(TransactionPruned { hash: *hash, metadata });
+
+        Some(tx)
     }
 
     pub fn get_metadata(&self, hash: &Hash) -> Option<TransactionMetadata> {
@@ -173,6 +142,16 @@ impl MsTangle {
         self.snapshot_milestone_index.store(*new_index, Ordering::Relaxed);
     }
 
+    /// Records that `address` was spent from by a confirmed and non conflicting bundle.
+    pub fn add_spent_address(&self, address: Address) {
+        self.spent_addresses.insert(address);
+    }
+
+    /// Returns whether `address` was spent from by a bundle confirmed since the node started.
+    pub fn is_spent_address(&self, address: &Address) -> bool {
+        self.spent_addresses.contains(address)
+    }
+
     // TODO reduce to one atomic value ?
     pub fn is_synced(&self) -> bool {
         self.get_last_solid_milestone_index() == self.get_last_milestone_index()
@@ -233,6 +212,10 @@ mod tests {
     use bee_tangle::traversal;
     use bee_test::{field::rand_trits_field, transaction::create_random_attached_tx};
 
+    use serial_test::serial;
+
+    use std::sync::{Arc, Mutex};
+
     #[test]
     fn confirm_transaction() {
         // Example from https://github.com/iotaledger/protocol-rfcs/blob/master/text/0005-white-flag/0005-white-flag.md
@@ -387,6 +370,32 @@ mod tests {
         // assert_eq!(hashes[10], s_hash);
         // assert_eq!(hashes[11], v_hash);
     }
+
+    #[test]
+    #[serial]
+    fn prune_dispatches_event() {
+        Protocol::init_for_tests();
+
+        let pruned = Arc::new(Mutex::new(Vec::new()));
+        let (hash, transaction) = create_random_attached_tx(Hash::zeros(), Hash::zeros());
+        let mut metadata = TransactionMetadata::new();
+
+        metadata.flags.set_solid();
+        tangle().insert(transaction, hash, metadata);
+
+        let listener_pruned = pruned.clone();
+        Protocol::get().bus.add_listener(move |event: &TransactionPruned| {
+            listener_pruned
+                .lock()
+                .unwrap()
+                .push((event.hash, event.metadata.flags.is_solid()));
+        });
+
+        assert!(tangle().prune(&hash).is_some());
+        assert!(!tangle().contains(&hash));
+        assert!(tangle().prune(&hash).is_none());
+        assert_eq!(*pruned.lock().unwrap(), vec![(hash, true)]);
+    }
 }
 
 // use crate::{
@@ -14,6 +14,7 @@ use crate::{
     milestone::{Milestone, MilestoneBuilder, MilestoneBuilderError},
     protocol::Protocol,
     tangle::tangle,
+    worker::request_completed,
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
@@ -116,7 +117,9 @@ where
                     Protocol::get().bus.dispatch(LastMilestoneChanged(milestone.clone()));
                 }
 
-                Protocol::get().requested_milestones.remove(&milestone.index);
+                if let Some((_, (epid, _, _))) = Protocol::get().requested_milestones.remove(&milestone.index) {
+                    request_completed(&epid);
+                }
                 Protocol::request_milestone_fill();
             }
             Err(e) => match e {
@@ -12,6 +12,7 @@
 mod broadcaster;
 mod milestone_validator;
 mod peer;
+mod propagator;
 mod requester;
 mod responder;
 mod sender;
@@ -23,9 +24,10 @@ mod transaction;
 pub(crate) use broadcaster::{BroadcasterWorker, BroadcasterWorkerEvent};
 pub(crate) use milestone_validator::MilestoneValidatorWorker;
 pub(crate) use peer::{PeerHandshakerWorker, PeerWorker};
+pub(crate) use propagator::{SolidPropagatorWorker, SolidPropagatorWorkerEvent};
 pub(crate) use requester::{
-    MilestoneRequesterWorker, MilestoneRequesterWorkerEntry, TransactionRequesterWorker,
-    TransactionRequesterWorkerEntry,
+    request_completed, MilestoneRequesterWorker, MilestoneRequesterWorkerEntry, RequestedTransaction,
+    TransactionRequesterWorker, TransactionRequesterWorkerEntry,
 };
 pub(crate) use responder::{
     MilestoneResponderWorker, MilestoneResponderWorkerEvent, TransactionResponderWorker,
@@ -23,8 +23,8 @@ use crate::{
 
 use bee_network::{
     Address,
-    Command::{Disconnect, SendMessage},
-    Network, Origin, Port,
+    Command::{BanEndpoint, Disconnect, SendMessage},
+    Network, Origin, Port, PublicKey,
 };
 
 use async_std::{net::SocketAddr, task::spawn};
@@ -37,7 +37,7 @@ use log::{debug, error, info, warn};
 
 use std::{
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 #[derive(Debug)]
@@ -47,6 +47,7 @@ pub(crate) enum HandshakeError {
     MwmMismatch(u8, u8),
     UnsupportedVersion(u8),
     PortMismatch(u16, u16),
+    SelfConnection,
     UnboundPeer,
     AlreadyHandshaked,
 }
@@ -58,6 +59,8 @@ enum HandshakeStatus {
     Awaiting,
     Done,
     Duplicate,
+    Failed,
+    Invalid,
 }
 
 pub struct PeerHandshakerWorker {
@@ -108,9 +111,11 @@ impl PeerHandshakerWorker {
             if let Err(e) = self.process_message(&header, bytes).await {
                 error!("[{}] Processing message failed: {:?}.", self.peer.address, e);
             }
-            if let HandshakeStatus::Done | HandshakeStatus::Duplicate = self.status {
-                break;
+            if let HandshakeStatus::Awaiting = self.status {
+                continue;
             }
+
+            break;
         }
 
         match self.status {
@@ -128,8 +133,8 @@ impl PeerHandshakerWorker {
                     .run(message_handler),
                 );
             }
-            HandshakeStatus::Duplicate => {
-                info!("[{}] Closing duplicate connection.", self.peer.epid);
+            HandshakeStatus::Duplicate | HandshakeStatus::Failed => {
+                info!("[{}] Closing connection.", self.peer.epid);
                 if let Err(e) = self
                     .network
                     .send(Disconnect {
@@ -141,13 +146,30 @@ impl PeerHandshakerWorker {
                     warn!("[{}] Disconnecting peer failed: {}.", self.peer.epid, e);
                 }
             }
-            _ => (),
+            HandshakeStatus::Invalid => {
+                warn!("[{}] Banning peer, invalid handshake.", self.peer.address);
+                if let Err(e) = self
+                    .network
+                    .send(BanEndpoint {
+                        epid: self.peer.epid,
+                        duration: Duration::from_secs(Protocol::get().config.reputation.ban_duration),
+                        responder: None,
+                    })
+                    .await
+                {
+                    warn!("[{}] Banning peer failed: {}.", self.peer.epid, e);
+                }
+            }
+            HandshakeStatus::Awaiting => (),
         }
 
         info!("[{}] Stopped.", self.peer.address);
     }
 
-    pub(crate) fn validate_handshake(&mut self, handshake: Handshake) -> Result<Address, HandshakeError> {
+    pub(crate) fn validate_handshake(
+        &mut self,
+        handshake: Handshake,
+    ) -> Result<(Address, Option<PublicKey>), HandshakeError> {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Clock may have gone backwards")
@@ -193,14 +215,28 @@ impl PeerHandshakerWorker {
             Origin::Unbound => return Err(HandshakeError::UnboundPeer),
         };
 
-        for peer in Protocol::get().peer_manager.handshaked_peers.iter() {
-            if peer.address == address {
-                self.status = HandshakeStatus::Duplicate;
-                return Err(HandshakeError::AlreadyHandshaked);
+        // NOTE: Only a public key authenticated by a secure connection identifies a peer, several of which may then
+        // share an address, e.g. behind a NAT. Peers connected insecurely are identified by their address instead.
+        let duplicate = match self.peer.public_key {
+            Some(public_key) => {
+                if public_key == Protocol::get().public_key {
+                    return Err(HandshakeError::SelfConnection);
+                }
+                Protocol::get().peer_manager.epid(&public_key).is_some()
             }
+            None => Protocol::get()
+                .peer_manager
+                .handshaked_peers
+                .iter()
+                .any(|peer| peer.address == address),
+        };
+
+        if duplicate {
+            self.status = HandshakeStatus::Duplicate;
+            return Err(HandshakeError::AlreadyHandshaked);
         }
 
-        Ok(address)
+        Ok((address, self.peer.public_key))
     }
 
     async fn process_message(&mut self, header: &Header, bytes: &[u8]) -> Result<(), PeerHandshakerWorkerError> {
@@ -208,10 +244,18 @@ impl PeerHandshakerWorker {
             debug!("[{}] Reading Handshake...", self.peer.address);
             match tlv_from_bytes::<Handshake>(&header, bytes) {
                 Ok(handshake) => match self.validate_handshake(handshake) {
-                    Ok(address) => {
-                        info!("[{}] Handshake completed.", self.peer.address);
+                    Ok((address, public_key)) => {
+                        match public_key {
+                            Some(public_key) => {
+                                info!("[{}] Handshake completed with {}.", self.peer.address, public_key)
+                            }
+                            None => info!("[{}] Handshake completed.", self.peer.address),
+                        }
 
-                        Protocol::get().peer_manager.handshake(&self.peer.epid, address).await;
+                        Protocol::get()
+                            .peer_manager
+                            .handshake(&self.peer.epid, address, public_key)
+                            .await;
 
                         Protocol::get().bus.dispatch(HandshakeCompleted(address));
 
@@ -229,12 +273,18 @@ impl PeerHandshakerWorker {
                     }
                     Err(e) => {
                         warn!("[{}] Handshaking failed: {:?}.", self.peer.address, e);
+
+                        if let HandshakeStatus::Awaiting = self.status {
+                            self.status = HandshakeStatus::Failed;
+                        }
                     }
                 },
                 Err(e) => {
                     warn!("[{}] Reading Handshake failed: {:?}.", self.peer.address, e);
 
                     Protocol::get().metrics.invalid_messages_inc();
+
+                    self.status = HandshakeStatus::Invalid;
                 }
             }
         } else {
@@ -14,7 +14,7 @@ use crate::{
         tlv_from_bytes, Header, Heartbeat, Message, MilestoneRequest, Transaction as TransactionMessage,
         TransactionRequest,
     },
-    peer::HandshakedPeer,
+    peer::{HandshakedPeer, Misbehaviour},
     protocol::Protocol,
     tangle::tangle,
     worker::{peer::MessageHandler, HasherWorkerEvent, MilestoneResponderWorkerEvent, TransactionResponderWorkerEvent},
@@ -23,7 +23,7 @@ use crate::{
 use futures::channel::mpsc;
 use log::{debug, error, info, warn};
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 #[derive(Debug)]
 pub(crate) enum PeerWorkerError {
@@ -82,6 +82,7 @@ impl PeerWorker {
 
                         self.peer.metrics.invalid_messages_inc();
                         Protocol::get().metrics.invalid_messages_inc();
+                        Protocol::penalise_peer(self.peer.epid, Misbehaviour::InvalidMessage);
                     }
                 }
             }
@@ -91,8 +92,10 @@ impl PeerWorker {
                     Ok(message) => {
                         self.hasher_worker
                             .unbounded_send(HasherWorkerEvent {
-                                from: self.peer.epid,
+                                from: Some(self.peer.epid),
+                                received: Instant::now(),
                                 transaction: message,
+                                broadcast: true,
                             })
                             .map_err(|_| PeerWorkerError::FailedSend)?;
 
@@ -104,6 +107,7 @@ impl PeerWorker {
 
                         self.peer.metrics.invalid_messages_inc();
                         Protocol::get().metrics.invalid_messages_inc();
+                        Protocol::penalise_peer(self.peer.epid, Misbehaviour::InvalidMessage);
                     }
                 }
             }
@@ -126,6 +130,7 @@ impl PeerWorker {
 
                         self.peer.metrics.invalid_messages_inc();
                         Protocol::get().metrics.invalid_messages_inc();
+                        Protocol::penalise_peer(self.peer.epid, Misbehaviour::InvalidMessage);
                     }
                 }
             }
@@ -166,6 +171,7 @@ impl PeerWorker {
 
                         self.peer.metrics.invalid_messages_inc();
                         Protocol::get().metrics.invalid_messages_inc();
+                        Protocol::penalise_peer(self.peer.epid, Misbehaviour::InvalidMessage);
                     }
                 }
             }
@@ -177,6 +183,7 @@ impl PeerWorker {
 
                 self.peer.metrics.invalid_messages_inc();
                 Protocol::get().metrics.invalid_messages_inc();
+                Protocol::penalise_peer(self.peer.epid, Misbehaviour::InvalidMessage);
             }
         };
 
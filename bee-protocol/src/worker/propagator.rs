@@ -0,0 +1,211 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{
+    event::{LastSolidMilestoneChanged, TransactionSolidified},
+    milestone::Milestone,
+    protocol::Protocol,
+    tangle::tangle,
+};
+
+use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
+use bee_crypto::ternary::Hash;
+use bee_transaction::Vertex;
+
+use async_std::task;
+use futures::{
+    channel::mpsc,
+    future::FutureExt,
+    stream::{Fuse, StreamExt},
+};
+use log::info;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of transactions visited before the worker yields back to the executor.
+const PROPAGATION_BATCH_SIZE: usize = 1000;
+
+type Receiver = ShutdownStream<Fuse<mpsc::UnboundedReceiver<SolidPropagatorWorkerEvent>>>;
+
+pub(crate) struct SolidPropagatorWorkerEvent(pub(crate) Hash);
+
+pub(crate) struct SolidPropagatorWorker {
+    receiver: Receiver,
+    pending: Vec<Hash>,
+}
+
+impl SolidPropagatorWorker {
+    pub(crate) fn new(receiver: Receiver) -> Self {
+        Self {
+            receiver,
+            pending: Vec::new(),
+        }
+    }
+
+    fn solidify(&self, hash: Hash) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Clock may have gone backwards")
+            .as_millis() as u64;
+
+        tangle().update_metadata(&hash, |metadata| {
+            metadata.flags.set_solid();
+            metadata.solidification_timestamp = timestamp;
+        });
+
+        if let Some(metadata) = tangle().get_metadata(&hash) {
+            Protocol::get().bus.dispatch(TransactionSolidified { hash, metadata });
+
+            // This is possibly not sufficient as there is no guarantee a milestone has been validated before being
+            // solidified, we then also need to check when a milestone gets validated if it's already solid.
+            if metadata.flags.is_milestone() {
+                Protocol::get().bus.dispatch(LastSolidMilestoneChanged(Milestone {
+                    hash,
+                    index: metadata.milestone_index,
+                }));
+            }
+        }
+    }
+
+    // Visits at most `PROPAGATION_BATCH_SIZE` pending transactions, solidifying those whose parents are solid and
+    // scheduling their children in turn.
+    fn propagate(&mut self) {
+        let mut visited = 0;
+
+        while visited < PROPAGATION_BATCH_SIZE {
+            let hash = match self.pending.pop() {
+                Some(hash) => hash,
+                None => break,
+            };
+
+            visited += 1;
+
+            if tangle().is_solid_transaction(&hash) {
+                continue;
+            }
+
+            let solid_parents = match tangle().get(&hash) {
+                Some(tx) => tangle().is_solid_transaction(tx.trunk()) && tangle().is_solid_transaction(tx.branch()),
+                None => false,
+            };
+
+            if solid_parents {
+                self.solidify(hash);
+                self.pending.extend(tangle().get_children(&hash));
+            }
+        }
+    }
+
+    pub(crate) async fn run(mut self) -> Result<(), WorkerError> {
+        info!("Running.");
+
+        while let Some(SolidPropagatorWorkerEvent(hash)) = self.receiver.next().await {
+            self.pending.push(hash);
+
+            loop {
+                // Takes in every transaction inserted in the meantime so that they are handled in the same batch.
+                while let Some(Some(SolidPropagatorWorkerEvent(hash))) = self.receiver.next().now_or_never() {
+                    self.pending.push(hash);
+                }
+
+                self.propagate();
+
+                if self.pending.is_empty() {
+                    break;
+                }
+
+                // A deep cone becoming solid at once spans several batches, other workers get to run in between.
+                task::yield_now().await;
+            }
+        }
+
+        info!("Stopped.");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{milestone::MilestoneIndex, tangle::TransactionMetadata};
+
+    use bee_test::{field::rand_trits_field, transaction::create_random_attached_tx};
+
+    use futures::{channel::oneshot, executor::block_on, join};
+    use serial_test::serial;
+
+    // Inserts a chain of `length` transactions approving a new solid entry point, from the oldest to the newest.
+    fn insert_chain(length: usize) -> Vec<Hash> {
+        let mut last = rand_trits_field::<Hash>();
+        let mut chain = Vec::with_capacity(length);
+
+        tangle().add_solid_entry_point(last, MilestoneIndex(0));
+
+        for _ in 0..length {
+            let (hash, transaction) = create_random_attached_tx(last, last);
+            tangle().insert(transaction, hash, TransactionMetadata::new());
+            chain.push(hash);
+            last = hash;
+        }
+
+        chain
+    }
+
+    // Runs a worker propagating from the oldest transaction of the chain until the newest one is solid and returns
+    // whether other work got to run in between.
+    fn propagate(chain: &[Hash]) -> bool {
+        let (first, last) = (chain[0], chain[chain.len() - 1]);
+        let (sender, receiver) = mpsc::unbounded();
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let worker = SolidPropagatorWorker::new(ShutdownStream::new(shutdown_receiver, receiver));
+
+        sender.unbounded_send(SolidPropagatorWorkerEvent(first)).unwrap();
+
+        // Both futures run on the same task, the second one only runs when the worker yields.
+        let (result, interleaved) = block_on(async {
+            join!(worker.run(), async {
+                let interleaved = tangle().is_solid_transaction(&first) && !tangle().is_solid_transaction(&last);
+
+                while !tangle().is_solid_transaction(&last) {
+                    task::yield_now().await;
+                }
+                shutdown_sender.send(()).unwrap();
+
+                interleaved
+            })
+        });
+        result.unwrap();
+
+        interleaved
+    }
+
+    #[test]
+    #[serial]
+    fn propagate_deeper_than_batch() {
+        Protocol::init_for_tests();
+
+        let chain = insert_chain(3 * PROPAGATION_BATCH_SIZE + 1);
+
+        assert!(chain.iter().all(|hash| !tangle().is_solid_transaction(hash)));
+        propagate(&chain);
+        assert!(chain.iter().all(|hash| tangle().is_solid_transaction(hash)));
+    }
+
+    #[test]
+    #[serial]
+    fn yield_between_batches() {
+        Protocol::init_for_tests();
+
+        assert!(propagate(&insert_chain(2 * PROPAGATION_BATCH_SIZE)));
+    }
+}
@@ -10,7 +10,14 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
-    message::MilestoneRequest, milestone::MilestoneIndex, protocol::Protocol, tangle::tangle, worker::SenderWorker,
+    message::MilestoneRequest,
+    milestone::MilestoneIndex,
+    protocol::Protocol,
+    tangle::tangle,
+    worker::{
+        requester::{request_completed, request_sent, request_timed_out, select_peer},
+        SenderWorker,
+    },
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
@@ -65,55 +72,51 @@ impl<'a> MilestoneRequesterWorker<'a> {
             return;
         }
 
-        if self.process_request_unchecked(index, epid).await {
+        if let Some((epid, fallback)) = self.process_request_unchecked(index, epid).await {
             if index.0 != 0 {
-                Protocol::get().requested_milestones.insert(index, Instant::now());
+                request_sent(&epid);
+                Protocol::get()
+                    .requested_milestones
+                    .insert(index, (epid, Instant::now(), fallback));
             }
         }
     }
 
-    /// Return `true` if the milestone was requested
-    async fn process_request_unchecked(&mut self, index: MilestoneIndex, epid: Option<EndpointId>) -> bool {
+    /// Return the id of the peer the milestone was requested from, if any, and whether it is only a fallback.
+    async fn process_request_unchecked(
+        &mut self,
+        index: MilestoneIndex,
+        epid: Option<EndpointId>,
+    ) -> Option<(EndpointId, bool)> {
         if Protocol::get().peer_manager.handshaked_peers.is_empty() {
-            return false;
+            return None;
         }
 
-        match epid {
-            Some(epid) => {
-                SenderWorker::<MilestoneRequest>::send(&epid, MilestoneRequest::new(*index));
-                true
-            }
-            None => {
-                let guard = Protocol::get().peer_manager.handshaked_peers_keys.read().await;
-
-                for _ in 0..guard.len() {
-                    let epid = &guard[self.counter % guard.len()];
-
-                    self.counter += 1;
+        let (epid, fallback) = match epid {
+            Some(epid) => (epid, false),
+            None => select_peer(&mut self.counter, index).await?,
+        };
 
-                    if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
-                        if index > peer.snapshot_milestone_index() && index <= peer.last_solid_milestone_index() {
-                            SenderWorker::<MilestoneRequest>::send(&epid, MilestoneRequest::new(*index));
-                            return true;
-                        }
-                    }
-                }
+        SenderWorker::<MilestoneRequest>::send(&epid, MilestoneRequest::new(*index));
 
-                false
-            }
-        }
+        Some((epid, fallback))
     }
 
     async fn retry_requests(&mut self) {
         let mut retry_counts = 0;
 
         for mut milestone in Protocol::get().requested_milestones.iter_mut() {
-            let (index, instant) = milestone.pair_mut();
+            let (index, (epid, instant, fallback)) = milestone.pair_mut();
             let now = Instant::now();
             if (now - *instant).as_secs() > RETRY_INTERVAL_SECS {
                 debug!("Milestone timed out, retrying request.");
-                if self.process_request_unchecked(*index, None).await {
+                request_timed_out(epid, *index, *fallback);
+                if let Some((to, to_fallback)) = self.process_request_unchecked(*index, None).await {
+                    request_completed(epid);
+                    request_sent(&to);
+                    *epid = to;
                     *instant = now;
+                    *fallback = to_fallback;
                     retry_counts += 1;
                 };
             }
@@ -13,4 +13,213 @@ mod milestone;
 mod transaction;
 
 pub(crate) use milestone::{MilestoneRequesterWorker, MilestoneRequesterWorkerEntry};
-pub(crate) use transaction::{TransactionRequesterWorker, TransactionRequesterWorkerEntry};
+pub(crate) use transaction::{RequestedTransaction, TransactionRequesterWorker, TransactionRequesterWorkerEntry};
+
+use crate::{milestone::MilestoneIndex, peer::Misbehaviour, protocol::Protocol};
+
+use bee_network::EndpointId;
+
+/// Picks, round-robin from `counter`, a peer to request something needed by the milestone `index` from, and whether it
+/// is only a fallback.
+///
+/// Peers whose advertised range can contain `index` are preferred. If there are none, the request falls back to a peer
+/// that at least knows about the milestone, then to any peer. Peers with too many outstanding requests are skipped.
+pub(crate) async fn select_peer(counter: &mut usize, index: MilestoneIndex) -> Option<(EndpointId, bool)> {
+    let peer_manager = &Protocol::get().peer_manager;
+    let max_outstanding_requests = Protocol::get().config.workers.max_outstanding_requests;
+
+    let guard = peer_manager.handshaked_peers_keys.read().await;
+    let mut knowing = None;
+    let mut any = None;
+
+    for _ in 0..guard.len() {
+        let epid = &guard[*counter % guard.len()];
+
+        *counter += 1;
+
+        if let Some(peer) = peer_manager.handshaked_peers.get(epid) {
+            if peer.outstanding_requests() >= max_outstanding_requests {
+                continue;
+            }
+
+            if peer.has_data(index) {
+                return Some((*epid, false));
+            }
+
+            if knowing.is_none() && index <= peer.last_milestone_index() {
+                knowing = Some(*epid);
+            }
+
+            if any.is_none() {
+                any = Some(*epid);
+            }
+        }
+    }
+
+    knowing.or(any).map(|epid| (epid, true))
+}
+
+/// Accounts for a request sent to a peer and now outstanding.
+pub(crate) fn request_sent(epid: &EndpointId) {
+    if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
+        peer.outstanding_requests_inc();
+    }
+}
+
+/// Accounts for a request to a peer that is not outstanding anymore, either answered or moved to another peer.
+pub(crate) fn request_completed(epid: &EndpointId) {
+    if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
+        peer.outstanding_requests_dec();
+    }
+}
+
+/// Accounts for a request to a peer that timed out, needed by the milestone `index`.
+///
+/// The peer is only penalised if it wasn't a `fallback` and its advertised range covered `index`, otherwise it is
+/// merely missing the data.
+pub(crate) fn request_timed_out(epid: &EndpointId, index: MilestoneIndex, fallback: bool) {
+    if fallback {
+        return;
+    }
+
+    let covered = Protocol::get()
+        .peer_manager
+        .handshaked_peers
+        .get(epid)
+        .map_or(false, |peer| peer.has_data(index));
+
+    if covered {
+        Protocol::penalise_peer(*epid, Misbehaviour::UnansweredRequest);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+    use crate::peer::Peer;
+
+    use bee_network::{Address, Origin};
+
+    use async_std::task::block_on;
+    use serial_test::serial;
+
+    use std::{net::Ipv4Addr, sync::Arc};
+
+    /// Handshakes a peer on `port` whose advertised range is `(snapshot_index, last_solid_index]`.
+    pub(crate) async fn handshaked_peer(port: u16, snapshot_index: u32, last_solid_index: u32) -> EndpointId {
+        let address = Address::from_v4_addr_and_port(Ipv4Addr::LOCALHOST, port);
+        let epid = EndpointId::from(address);
+        let peer_manager = &Protocol::get().peer_manager;
+
+        peer_manager.add(Arc::new(Peer::new(epid, address, Origin::Outbound, None)));
+        peer_manager.handshake(&epid, address, None).await;
+
+        if let Some(peer) = peer_manager.handshaked_peers.get(&epid) {
+            peer.set_snapshot_milestone_index(snapshot_index.into());
+            peer.set_last_solid_milestone_index(last_solid_index.into());
+        }
+
+        epid
+    }
+
+    async fn remove_peers(epids: &[EndpointId]) {
+        for epid in epids {
+            Protocol::get().peer_manager.remove(epid).await;
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn select_peer_prefers_peers_having_the_data() {
+        Protocol::init_for_tests();
+
+        block_on(async {
+            let missing = handshaked_peer(17211, 0, 10).await;
+            let having = handshaked_peer(17212, 0, 30).await;
+            let mut counter = 0;
+
+            for _ in 0..4 {
+                assert_eq!(select_peer(&mut counter, 20.into()).await, Some((having, false)));
+            }
+
+            remove_peers(&[missing, having]).await;
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn select_peer_falls_back_to_peers_knowing_the_milestone() {
+        Protocol::init_for_tests();
+
+        block_on(async {
+            let unaware = handshaked_peer(17213, 0, 10).await;
+            let knowing = handshaked_peer(17214, 0, 10).await;
+            let mut counter = 0;
+
+            if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(&knowing) {
+                peer.set_last_milestone_index(25.into());
+            }
+
+            for _ in 0..4 {
+                assert_eq!(select_peer(&mut counter, 20.into()).await, Some((knowing, true)));
+            }
+
+            // Without any peer knowing the milestone, any peer is a fallback.
+            assert!(matches!(select_peer(&mut counter, 30.into()).await, Some((_, true))));
+
+            remove_peers(&[unaware, knowing]).await;
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn select_peer_skips_peers_with_too_many_outstanding_requests() {
+        Protocol::init_for_tests();
+
+        block_on(async {
+            let busy = handshaked_peer(17215, 0, 30).await;
+            let idle = handshaked_peer(17216, 0, 10).await;
+            let mut counter = 0;
+
+            for _ in 0..Protocol::get().config.workers.max_outstanding_requests {
+                request_sent(&busy);
+            }
+
+            assert_eq!(select_peer(&mut counter, 20.into()).await, Some((idle, true)));
+
+            request_completed(&busy);
+            assert_eq!(select_peer(&mut counter, 20.into()).await, Some((busy, false)));
+
+            remove_peers(&[busy, idle]).await;
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn request_completed_saturates_outstanding_requests() {
+        Protocol::init_for_tests();
+
+        block_on(async {
+            let epid = handshaked_peer(17217, 0, 10).await;
+            let outstanding_requests = || {
+                Protocol::get()
+                    .peer_manager
+                    .handshaked_peers
+                    .get(&epid)
+                    .map(|peer| peer.outstanding_requests())
+            };
+
+            request_sent(&epid);
+            request_sent(&epid);
+            assert_eq!(outstanding_requests(), Some(2));
+
+            for _ in 0..3 {
+                request_completed(&epid);
+            }
+            assert_eq!(outstanding_requests(), Some(0));
+
+            remove_peers(&[epid]).await;
+        });
+    }
+}
@@ -9,11 +9,22 @@
 // an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and limitations under the License.
 
-use crate::{message::TransactionRequest, milestone::MilestoneIndex, protocol::Protocol, worker::SenderWorker};
+use crate::{
+    event::{TransactionRequestAbandoned, TransactionRequested},
+    message::TransactionRequest,
+    milestone::MilestoneIndex,
+    peer::MAX_REQUEST_TIMEOUT,
+    protocol::Protocol,
+    worker::{
+        requester::{request_completed, request_sent, request_timed_out, select_peer},
+        SenderWorker,
+    },
+};
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
 use bee_common_ext::wait_priority_queue::WaitIncoming;
 use bee_crypto::ternary::Hash;
+use bee_network::EndpointId;
 use bee_ternary::T5B1Buf;
 
 use async_std::stream::{interval, Interval};
@@ -26,7 +37,11 @@ use std::{
     time::{Duration, Instant},
 };
 
-const RETRY_INTERVAL_SECS: u64 = 5;
+// Timeouts are derived from the round-trip time of each peer, this is only how often they are checked.
+const TIMEOUT_CHECK_INTERVAL_MS: u64 = 500;
+
+// How long an abandoned transaction is not requested again, so that the solidifier doesn't restart its retries at once.
+const ABANDONED_REQUEST_COOLDOWN: Duration = Duration::from_secs(60);
 
 type Receiver<'a> = ShutdownStream<WaitIncoming<'a, TransactionRequesterWorkerEntry>>;
 
@@ -45,6 +60,64 @@ impl Ord for TransactionRequesterWorkerEntry {
     }
 }
 
+/// A transaction requested and not received yet.
+pub(crate) struct RequestedTransaction {
+    pub(crate) index: MilestoneIndex,
+    // The peer the request was last sent to.
+    pub(crate) epid: EndpointId,
+    // Whether that peer was only a fallback, not expected to have the transaction.
+    pub(crate) fallback: bool,
+    pub(crate) first_sent: Instant,
+    pub(crate) last_sent: Instant,
+    pub(crate) retries: u32,
+}
+
+impl RequestedTransaction {
+    fn new(index: MilestoneIndex, epid: EndpointId, fallback: bool) -> Self {
+        let now = Instant::now();
+
+        Self {
+            index,
+            epid,
+            fallback,
+            first_sent: now,
+            last_sent: now,
+            retries: 0,
+        }
+    }
+
+    /// Timeout of the last request, doubling with every retry.
+    fn timeout(&self) -> Duration {
+        let timeout = match Protocol::get().peer_manager.handshaked_peers.get(&self.epid) {
+            Some(peer) => peer.request_timeout(),
+            // The peer is gone, there is no point in waiting for it.
+            None => return Duration::from_secs(0),
+        };
+
+        timeout
+            .checked_mul(1 << self.retries.min(31))
+            .map_or(MAX_REQUEST_TIMEOUT, |timeout| timeout.min(MAX_REQUEST_TIMEOUT))
+    }
+
+    /// Accounts for the reception of the requested transaction from `from`, `received` before it was queued for
+    /// processing.
+    pub(crate) fn answered(&self, from: Option<EndpointId>, received: Instant) {
+        request_completed(&self.epid);
+
+        Protocol::get().metrics.transaction_requests_answered_inc();
+        Protocol::get()
+            .metrics
+            .transaction_request_latency_add(received.saturating_duration_since(self.first_sent).as_millis() as u64);
+
+        // The round-trip time of a retried request is ambiguous, it may be the answer to any of the attempts.
+        if self.retries == 0 && from == Some(self.epid) {
+            if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(&self.epid) {
+                peer.rtt_sample(received.saturating_duration_since(self.last_sent));
+            }
+        }
+    }
+}
+
 pub(crate) struct TransactionRequesterWorker<'a> {
     counter: usize,
     receiver: Receiver<'a>,
@@ -56,7 +129,7 @@ impl<'a> TransactionRequesterWorker<'a> {
         Self {
             counter: 0,
             receiver,
-            timeouts: interval(Duration::from_secs(RETRY_INTERVAL_SECS)).fuse(),
+            timeouts: interval(Duration::from_millis(TIMEOUT_CHECK_INTERVAL_MS)).fuse(),
         }
     }
 
@@ -65,51 +138,92 @@ impl<'a> TransactionRequesterWorker<'a> {
             return;
         }
 
-        if self.process_request_unchecked(hash, index).await {
+        let abandoned = Protocol::get()
+            .abandoned_transactions
+            .get(&hash)
+            .map_or(false, |since| since.elapsed() < ABANDONED_REQUEST_COOLDOWN);
+
+        if abandoned {
+            return;
+        }
+
+        if let Some((epid, fallback)) = self.process_request_unchecked(hash, index).await {
+            request_sent(&epid);
             Protocol::get()
                 .requested_transactions
-                .insert(hash, (index, Instant::now()));
+                .insert(hash, RequestedTransaction::new(index, epid, fallback));
+            Protocol::get().bus.dispatch(TransactionRequested { hash, index });
         }
     }
 
-    /// Return `true` if the transaction was requested.
-    async fn process_request_unchecked(&mut self, hash: Hash, index: MilestoneIndex) -> bool {
+    /// Return the id of the peer the transaction was requested from, if any, and whether it is only a fallback.
+    async fn process_request_unchecked(&mut self, hash: Hash, index: MilestoneIndex) -> Option<(EndpointId, bool)> {
         if Protocol::get().peer_manager.handshaked_peers.is_empty() {
-            return false;
+            return None;
         }
 
-        let guard = Protocol::get().peer_manager.handshaked_peers_keys.read().await;
+        let (epid, fallback) = select_peer(&mut self.counter, index).await?;
 
-        for _ in 0..guard.len() {
-            let epid = &guard[self.counter % guard.len()];
+        SenderWorker::<TransactionRequest>::send(
+            &epid,
+            TransactionRequest::new(cast_slice(hash.as_trits().encode::<T5B1Buf>().as_i8_slice())),
+        );
 
-            self.counter += 1;
-
-            if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(epid) {
-                if index > peer.snapshot_milestone_index() && index <= peer.last_solid_milestone_index() {
-                    SenderWorker::<TransactionRequest>::send(
-                        epid,
-                        TransactionRequest::new(cast_slice(hash.as_trits().encode::<T5B1Buf>().as_i8_slice())),
-                    );
-                    return true;
-                }
-            }
-        }
-        false
+        Some((epid, fallback))
     }
 
     async fn retry_requests(&mut self) {
+        let max_request_retries = Protocol::get().config.workers.max_request_retries;
         let mut retry_counts = 0;
+        let mut abandoned = Vec::new();
+
+        Protocol::get()
+            .abandoned_transactions
+            .retain(|_, since| since.elapsed() < ABANDONED_REQUEST_COOLDOWN);
 
         for mut transaction in Protocol::get().requested_transactions.iter_mut() {
-            let (hash, (index, instant)) = transaction.pair_mut();
+            let (hash, request) = transaction.pair_mut();
             let now = Instant::now();
-            if (now - *instant).as_secs() > RETRY_INTERVAL_SECS {
-                debug!("Transaction timed out, retrying request.");
-                if self.process_request_unchecked(hash.clone(), *index).await {
-                    *instant = now;
-                    retry_counts += 1;
-                }
+
+            if now - request.last_sent <= request.timeout() {
+                continue;
+            }
+
+            request_timed_out(&request.epid, request.index, request.fallback);
+
+            if request.retries >= max_request_retries {
+                abandoned.push(hash.clone());
+                continue;
+            }
+
+            debug!("Transaction timed out, retrying request.");
+            // Without any peer to retry with, this waits for another timeout rather than penalising the peer again.
+            request.last_sent = now;
+            if let Some((to, fallback)) = self.process_request_unchecked(hash.clone(), request.index).await {
+                request_completed(&request.epid);
+                request_sent(&to);
+                request.epid = to;
+                request.fallback = fallback;
+                request.retries += 1;
+                retry_counts += 1;
+                Protocol::get().metrics.transaction_requests_retried_inc();
+            }
+        }
+
+        // Removing while iterating would deadlock the map.
+        for hash in abandoned {
+            if let Some((hash, request)) = Protocol::get().requested_transactions.remove(&hash) {
+                debug!(
+                    "Transaction still missing after {} retries, abandoning request.",
+                    request.retries
+                );
+                request_completed(&request.epid);
+                Protocol::get().abandoned_transactions.insert(hash, Instant::now());
+                Protocol::get().metrics.transaction_requests_abandoned_inc();
+                Protocol::get().bus.dispatch(TransactionRequestAbandoned {
+                    hash,
+                    index: request.index,
+                });
             }
         }
 
@@ -136,3 +250,130 @@ impl<'a> TransactionRequesterWorker<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::worker::requester::tests::handshaked_peer;
+
+    use bee_common_ext::wait_priority_queue::WaitPriorityQueue;
+    use bee_test::field::rand_trits_field;
+
+    use async_std::task::block_on;
+    use futures::channel::oneshot;
+    use serial_test::serial;
+
+    // Inserts a request for a transaction needed by the milestone `index`, sent to `epid` and already timed out.
+    fn timed_out_request(index: u32, epid: EndpointId, fallback: bool) -> Hash {
+        let hash = rand_trits_field::<Hash>();
+        let mut request = RequestedTransaction::new(index.into(), epid, fallback);
+
+        request.last_sent = Instant::now() - MAX_REQUEST_TIMEOUT - Duration::from_secs(1);
+        Protocol::get().requested_transactions.insert(hash, request);
+
+        hash
+    }
+
+    #[test]
+    #[serial]
+    fn retry_requests_only_penalises_peers_expected_to_have_the_data() {
+        Protocol::init_for_tests();
+
+        let queue = WaitPriorityQueue::default();
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel();
+        let mut worker = TransactionRequesterWorker::new(ShutdownStream::from_fused(shutdown_rx, queue.incoming()));
+
+        block_on(async {
+            let missing = handshaked_peer(17201, 0, 10).await;
+            let having = handshaked_peer(17202, 0, 30).await;
+            let hashes = [
+                timed_out_request(20, missing, false),
+                timed_out_request(20, having, false),
+                timed_out_request(20, having, true),
+            ];
+
+            worker.retry_requests().await;
+
+            let peers = &Protocol::get().peer_manager.handshaked_peers;
+            assert_eq!(peers.get(&missing).unwrap().reputation.score(), 0);
+            assert_eq!(peers.get(&having).unwrap().reputation.score(), -1);
+
+            for hash in hashes.iter() {
+                let request = Protocol::get().requested_transactions.remove(hash).unwrap().1;
+                assert_eq!(request.retries, 1);
+                assert_eq!(request.epid, having);
+            }
+
+            Protocol::get().peer_manager.remove(&missing).await;
+            Protocol::get().peer_manager.remove(&having).await;
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn timeout_doubles_with_every_retry() {
+        Protocol::init_for_tests();
+
+        block_on(async {
+            let epid = handshaked_peer(17203, 0, 30).await;
+            let timeout = Protocol::get()
+                .peer_manager
+                .handshaked_peers
+                .get(&epid)
+                .unwrap()
+                .request_timeout();
+            let mut request = RequestedTransaction::new(20.into(), epid, false);
+
+            for retries in 0..3 {
+                request.retries = retries;
+                assert_eq!(request.timeout(), (timeout * (1 << retries)).min(MAX_REQUEST_TIMEOUT));
+            }
+
+            request.retries = 40;
+            assert_eq!(request.timeout(), MAX_REQUEST_TIMEOUT);
+
+            Protocol::get().peer_manager.remove(&epid).await;
+            assert_eq!(request.timeout(), Duration::from_secs(0));
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn abandoned_requests_are_not_requested_again_right_away() {
+        Protocol::init_for_tests();
+
+        let queue = WaitPriorityQueue::default();
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel();
+        let mut worker = TransactionRequesterWorker::new(ShutdownStream::from_fused(shutdown_rx, queue.incoming()));
+
+        block_on(async {
+            let epid = handshaked_peer(17204, 0, 30).await;
+            let max_request_retries = Protocol::get().config.workers.max_request_retries;
+            let retried = timed_out_request(20, epid, false);
+            let exhausted = timed_out_request(20, epid, false);
+
+            if let Some(mut request) = Protocol::get().requested_transactions.get_mut(&exhausted) {
+                request.retries = max_request_retries;
+            }
+
+            worker.retry_requests().await;
+
+            assert!(Protocol::get().requested_transactions.contains_key(&retried));
+            assert!(!Protocol::get().requested_transactions.contains_key(&exhausted));
+            assert!(Protocol::get().abandoned_transactions.contains_key(&exhausted));
+
+            // The retried request now waits for a doubled timeout.
+            worker.retry_requests().await;
+            assert_eq!(Protocol::get().requested_transactions.get(&retried).unwrap().retries, 1);
+
+            // As the solidifier would, request the abandoned transaction again.
+            worker.process_request(exhausted, 20.into()).await;
+            assert!(!Protocol::get().requested_transactions.contains_key(&exhausted));
+
+            Protocol::get().requested_transactions.remove(&retried);
+            Protocol::get().abandoned_transactions.remove(&exhausted);
+            Protocol::get().peer_manager.remove(&epid).await;
+        });
+    }
+}
@@ -11,8 +11,9 @@
 
 use crate::{
     message::{uncompress_transaction_bytes, Transaction as TransactionMessage},
+    peer::Misbehaviour,
     protocol::Protocol,
-    worker::transaction::{HashCache, ProcessorWorkerEvent},
+    worker::transaction::{processor::penalise_sender, HashCache, ProcessorWorkerEvent},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
@@ -33,7 +34,7 @@ use futures::{
 use log::{debug, info, warn};
 use pin_project::pin_project;
 
-use std::pin::Pin;
+use std::{pin::Pin, time::Instant};
 
 // If a batch has less than this number of transactions, the regular CurlP hasher is used instead
 // of the batched one.
@@ -42,8 +43,12 @@ const BATCH_SIZE_THRESHOLD: usize = 3;
 type Receiver = ShutdownStream<Fuse<mpsc::UnboundedReceiver<HasherWorkerEvent>>>;
 
 pub(crate) struct HasherWorkerEvent {
-    pub(crate) from: EndpointId,
+    pub(crate) from: Option<EndpointId>,
+    // When the transaction was received, before any queueing in the workers.
+    pub(crate) received: Instant,
     pub(crate) transaction: TransactionMessage,
+    // Whether the transaction gets broadcast once stored.
+    pub(crate) broadcast: bool,
 }
 
 #[pin_project(project = HasherWorkerProj)]
@@ -88,11 +93,13 @@ impl HasherWorker {
         events: &mut Vec<HasherWorkerEvent>,
         processor_worker: &mut mpsc::UnboundedSender<ProcessorWorkerEvent>,
     ) {
-        for (HasherWorkerEvent { from, transaction }, hash) in events.drain(..).zip(hashes) {
+        for (event, hash) in events.drain(..).zip(hashes) {
             if let Err(e) = processor_worker.unbounded_send(ProcessorWorkerEvent {
                 hash: Hash::from_inner_unchecked(hash),
-                from,
-                transaction,
+                from: event.from,
+                received: event.received,
+                transaction: event.transaction,
+                broadcast: event.broadcast,
             }) {
                 warn!("Sending event to the processor worker failed: {}.", e);
             }
@@ -148,15 +155,24 @@ impl Stream for HasherWorker {
                     };
                 }
                 Poll::Ready(Some(event)) => {
-                    // If the transaction was already received, we skip it and poll again.
-                    if !cache.insert(&event.transaction.bytes) {
+                    // If the transaction was already received, we skip it and poll again. Submitted transactions are never
+                    // skipped since a stored transaction can be submitted again to be broadcast.
+                    if !cache.insert(&event.transaction.bytes) && event.from.is_some() {
                         debug!("Transaction already received.");
                         Protocol::get().metrics.known_transactions_inc();
                         continue;
                     }
                     // Given that the current batch has less than `BATCH_SIZE` transactions. We can
                     // add the transaction in the current event to the batch.
-                    let transaction_bytes = uncompress_transaction_bytes(&event.transaction.bytes);
+                    let transaction_bytes = match uncompress_transaction_bytes(&event.transaction.bytes) {
+                        Ok(transaction_bytes) => transaction_bytes,
+                        Err(e) => {
+                            debug!("Invalid transaction: {:?}.", e);
+                            Protocol::get().metrics.invalid_transactions_inc();
+                            penalise_sender(event.from, Misbehaviour::InvalidTransaction);
+                            continue;
+                        }
+                    };
 
                     let trits = Trits::<T5B1>::try_from_raw(cast_slice(&transaction_bytes), TRANSACTION_TRIT_LEN)
                         .unwrap()
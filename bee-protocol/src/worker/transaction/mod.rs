@@ -20,17 +20,11 @@ pub(crate) use processor::{ProcessorWorker, ProcessorWorkerEvent};
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        config::ProtocolConfig,
-        message::Transaction as TransactionMessage,
-        protocol::Protocol,
-        tangle::{self, tangle},
-    };
+    use crate::{message::Transaction as TransactionMessage, protocol::Protocol, tangle::tangle};
 
-    use bee_common::{shutdown::Shutdown, shutdown_stream::ShutdownStream};
-    use bee_common_ext::event::Bus;
+    use bee_common::shutdown_stream::ShutdownStream;
     use bee_crypto::ternary::Hash;
-    use bee_network::{EndpointId, NetworkConfig, Url};
+    use bee_network::{EndpointId, Url};
 
     use async_std::task::{self, block_on, spawn};
     use futures::{
@@ -38,24 +32,14 @@ mod tests {
         join,
         sink::SinkExt,
     };
+    use serial_test::serial;
 
-    use std::{sync::Arc, time::Duration};
+    use std::time::{Duration, Instant};
 
     #[test]
+    #[serial]
     fn test_tx_workers_with_compressed_buffer() {
-        let mut shutdown = Shutdown::new();
-        let bus = Arc::new(Bus::default());
-
-        // build network
-        let network_config = NetworkConfig::build().finish();
-        let (network, _) = bee_network::init(network_config, &mut shutdown);
-
-        // init tangle
-        tangle::init();
-
-        // init protocol
-        let protocol_config = ProtocolConfig::build().finish();
-        block_on(Protocol::init(protocol_config, network, 0, bus, &mut shutdown));
+        Protocol::init_for_tests();
 
         assert_eq!(tangle().len(), 0);
 
@@ -64,6 +48,7 @@ mod tests {
         let (processor_worker_sender, processor_worker_receiver) = mpsc::unbounded();
         let (processor_worker_shutdown_sender, processor_worker_shutdown_receiver) = oneshot::channel();
         let (milestone_validator_worker_sender, _milestone_validator_worker_receiver) = mpsc::unbounded();
+        let (solid_propagator_worker_sender, _solid_propagator_worker_receiver) = mpsc::unbounded();
 
         let hasher_handle = HasherWorker::new(
             processor_worker_sender,
@@ -74,6 +59,7 @@ mod tests {
 
         let processor_handle = ProcessorWorker::new(
             milestone_validator_worker_sender,
+            solid_propagator_worker_sender,
             ShutdownStream::new(processor_worker_shutdown_receiver, processor_worker_receiver),
         )
         .run();
@@ -83,8 +69,10 @@ mod tests {
             let message = TransactionMessage::new(&tx);
             let epid: EndpointId = Url::from_url_str("tcp://[::1]:16000").await.unwrap().into();
             let event = HasherWorkerEvent {
-                from: epid,
+                from: Some(epid),
+                received: Instant::now(),
                 transaction: message,
+                broadcast: true,
             };
             hasher_worker_sender.unbounded_send(event).unwrap();
             task::sleep(Duration::from_secs(5)).await;
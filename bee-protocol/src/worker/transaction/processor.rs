@@ -10,10 +10,12 @@
 // See the License for the specific language governing permissions and limitations under the License.
 
 use crate::{
+    event::NewTransaction,
     message::{uncompress_transaction_bytes, Transaction as TransactionMessage},
+    peer::Misbehaviour,
     protocol::Protocol,
     tangle::{tangle, TransactionMetadata},
-    worker::milestone_validator::MilestoneValidatorWorkerEvent,
+    worker::{milestone_validator::MilestoneValidatorWorkerEvent, SolidPropagatorWorkerEvent},
 };
 
 use bee_common::{shutdown_stream::ShutdownStream, worker::Error as WorkerError};
@@ -30,7 +32,7 @@ use futures::{
 };
 use log::{debug, error, info};
 
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 /// Timeframe to allow past or future transactions, 10 minutes in milliseconds.
 const ALLOWED_TIMESTAMP_WINDOW_MS: u64 = 10 * 60 * 1000;
@@ -39,22 +41,27 @@ type Receiver = ShutdownStream<Fuse<mpsc::UnboundedReceiver<ProcessorWorkerEvent
 
 pub(crate) struct ProcessorWorkerEvent {
     pub(crate) hash: Hash,
-    pub(crate) from: EndpointId,
+    pub(crate) from: Option<EndpointId>,
+    pub(crate) received: Instant,
     pub(crate) transaction: TransactionMessage,
+    pub(crate) broadcast: bool,
 }
 
 pub(crate) struct ProcessorWorker {
     milestone_validator_worker: mpsc::UnboundedSender<MilestoneValidatorWorkerEvent>,
+    solid_propagator_worker: mpsc::UnboundedSender<SolidPropagatorWorkerEvent>,
     receiver: Receiver,
 }
 
 impl ProcessorWorker {
     pub(crate) fn new(
         milestone_validator_worker: mpsc::UnboundedSender<MilestoneValidatorWorkerEvent>,
+        solid_propagator_worker: mpsc::UnboundedSender<SolidPropagatorWorkerEvent>,
         receiver: Receiver,
     ) -> Self {
         Self {
             milestone_validator_worker,
+            solid_propagator_worker,
             receiver,
         }
     }
@@ -65,10 +72,12 @@ impl ProcessorWorker {
         while let Some(ProcessorWorkerEvent {
             hash,
             from,
+            received,
             transaction,
+            broadcast,
         }) = self.receiver.next().await
         {
-            self.process_transaction_brodcast(hash, from, transaction);
+            self.process_transaction_brodcast(hash, from, received, transaction, broadcast);
         }
 
         info!("Stopped.");
@@ -87,17 +96,32 @@ impl ProcessorWorker {
         let past = now - ALLOWED_TIMESTAMP_WINDOW_MS;
         let future = now + ALLOWED_TIMESTAMP_WINDOW_MS;
 
-        // (is_timestamp_valid, should_broadcast)
+        // (is_timestamp_valid, is_recent)
         (
             timestamp >= Protocol::get().local_snapshot_timestamp && timestamp < future,
             timestamp >= past,
         )
     }
 
-    fn process_transaction_brodcast(&mut self, hash: Hash, from: EndpointId, transaction_message: TransactionMessage) {
+    fn process_transaction_brodcast(
+        &mut self,
+        hash: Hash,
+        from: Option<EndpointId>,
+        received: Instant,
+        transaction_message: TransactionMessage,
+        broadcast: bool,
+    ) {
         debug!("Processing received transaction...");
 
-        let transaction_bytes = uncompress_transaction_bytes(&transaction_message.bytes);
+        let transaction_bytes = match uncompress_transaction_bytes(&transaction_message.bytes) {
+            Ok(transaction_bytes) => transaction_bytes,
+            Err(e) => {
+                debug!("Invalid transaction: {:?}.", e);
+                Protocol::get().metrics.invalid_transactions_inc();
+                penalise_sender(from, Misbehaviour::InvalidTransaction);
+                return;
+            }
+        };
         let transaction = match Trits::<T5B1>::try_from_raw(cast_slice(&transaction_bytes), TRANSACTION_TRIT_LEN) {
             Ok(transaction_trits) => {
                 let transaction_buf = transaction_trits.to_buf::<T5B1Buf>().encode::<T1B1Buf>();
@@ -106,6 +130,7 @@ impl ProcessorWorker {
                     Err(e) => {
                         debug!("Invalid transaction: {:?}.", e);
                         Protocol::get().metrics.invalid_transactions_inc();
+                        penalise_sender(from, Misbehaviour::InvalidTransaction);
                         return;
                     }
                 }
@@ -113,6 +138,7 @@ impl ProcessorWorker {
             Err(e) => {
                 debug!("Invalid transaction: {:?}.", e);
                 Protocol::get().metrics.invalid_transactions_inc();
+                penalise_sender(from, Misbehaviour::InvalidTransaction);
                 return;
             }
         };
@@ -122,14 +148,17 @@ impl ProcessorWorker {
         if !requested && hash.weight() < Protocol::get().config.mwm {
             debug!("Insufficient weight magnitude: {}.", hash.weight());
             Protocol::get().metrics.invalid_transactions_inc();
+            penalise_sender(from, Misbehaviour::InvalidTransaction);
             return;
         }
 
-        let (is_timestamp_valid, should_broadcast) = self.validate_timestamp(&transaction);
+        let (is_timestamp_valid, is_recent) = self.validate_timestamp(&transaction);
+        let should_broadcast = broadcast && is_recent;
 
         if !requested && !is_timestamp_valid {
             debug!("Stale transaction, invalid timestamp.");
             Protocol::get().metrics.stale_transactions_inc();
+            penalise_sender(from, Misbehaviour::StaleTransaction);
             return;
         }
 
@@ -145,18 +174,33 @@ impl ProcessorWorker {
         // store transaction
         if let Some(transaction) = tangle().insert(transaction, hash, metadata) {
             Protocol::get().metrics.new_transactions_inc();
+            if let Some(epid) = from {
+                if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(&epid) {
+                    peer.metrics.new_transactions_inc();
+                    peer.reputation.reward();
+                }
+            }
+            Protocol::get().bus.dispatch(NewTransaction { hash, metadata });
+
+            if let Err(e) = self
+                .solid_propagator_worker
+                .unbounded_send(SolidPropagatorWorkerEvent(hash))
+            {
+                error!("Sending hash to solid propagation failed: {:?}.", e);
+            }
 
             if !tangle().is_synced() && Protocol::get().requested_transactions.is_empty() {
                 Protocol::trigger_milestone_solidification();
             }
 
             match Protocol::get().requested_transactions.remove(&hash) {
-                Some((hash, (index, _))) => {
-                    Protocol::trigger_transaction_solidification(hash, index);
+                Some((hash, request)) => {
+                    request.answered(from, received);
+                    Protocol::trigger_transaction_solidification(hash, request.index);
                 }
                 None => {
                     if should_broadcast {
-                        Protocol::broadcast_transaction_message(Some(from), transaction_message)
+                        Protocol::broadcast_transaction_message(from, transaction_message)
                     }
                 }
             };
@@ -200,6 +244,23 @@ impl ProcessorWorker {
             }
         } else {
             Protocol::get().metrics.known_transactions_inc();
+
+            // A submitted transaction may have been stored without being broadcast.
+            if from.is_none() && should_broadcast {
+                Protocol::broadcast_transaction_message(from, transaction_message);
+            }
+        }
+    }
+}
+
+pub(super) fn penalise_sender(from: Option<EndpointId>, misbehaviour: Misbehaviour) {
+    if let Some(epid) = from {
+        if let Some(peer) = Protocol::get().peer_manager.handshaked_peers.get(&epid) {
+            match misbehaviour {
+                Misbehaviour::StaleTransaction => peer.metrics.stale_transactions_inc(),
+                _ => peer.metrics.invalid_transactions_inc(),
+            };
         }
+        Protocol::penalise_peer(epid, misbehaviour);
     }
 }
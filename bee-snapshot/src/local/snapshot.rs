@@ -41,6 +41,7 @@ pub enum Error {
     InvalidSolidEntryPointHash,
     InvalidSeenMilestoneHash,
     InvalidAddress,
+    InvalidSpentAddress,
     InvalidSupply,
 }
 impl LocalSnapshot {
@@ -115,8 +116,8 @@ impl LocalSnapshot {
         // Number of spent addresses
 
         let mut buf = [0u8; std::mem::size_of::<u32>()];
-        match reader.read_exact(&mut buf) {
-            Ok(_) => {}
+        let spent_addresses_num = match reader.read_exact(&mut buf) {
+            Ok(_) => u32::from_le_bytes(buf),
             Err(e) => return Err(Error::IOError(e)),
         };
 
@@ -201,7 +202,23 @@ impl LocalSnapshot {
             return Err(Error::InvalidSupply);
         }
 
-        // TODO spend addresses ?
+        // Spent addresses
+
+        let mut buf_address = [0u8; 49];
+        for _ in 0..spent_addresses_num {
+            let address = match reader.read_exact(&mut buf_address) {
+                Ok(_) => match Trits::<T5B1>::try_from_raw(cast_slice(&buf_address), 243) {
+                    Ok(trits) => {
+                        Address::try_from_inner(trits.encode::<T1B1Buf>()).map_err(|_| Error::InvalidSpentAddress)
+                    }
+                    Err(_) => Err(Error::InvalidSpentAddress),
+                },
+                Err(e) => Err(Error::IOError(e)),
+            }?;
+
+            state.insert_spent_address(address);
+        }
+
         // TODO hash ?
 
         Ok(LocalSnapshot {
@@ -11,13 +11,14 @@
 
 use bee_transaction::bundled::Address;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // TODO Abstract balances
 
 #[derive(Default)]
 pub struct SnapshotState {
     pub(crate) balances: HashMap<Address, u64>,
+    pub(crate) spent_addresses: HashSet<Address>,
 }
 
 impl SnapshotState {
@@ -28,6 +29,7 @@ impl SnapshotState {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             balances: HashMap::with_capacity(capacity),
+            spent_addresses: HashSet::new(),
         }
     }
 
@@ -54,4 +56,12 @@ impl SnapshotState {
     pub fn into_balances(self) -> HashMap<Address, u64> {
         self.balances
     }
+
+    pub fn insert_spent_address(&mut self, address: Address) -> bool {
+        self.spent_addresses.insert(address)
+    }
+
+    pub fn spent_addresses(&self) -> &HashSet<Address> {
+        &self.spent_addresses
+    }
 }
@@ -0,0 +1,23 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_snapshot::local::LocalSnapshot;
+use bee_transaction::bundled::Address;
+
+#[test]
+fn spent_addresses() {
+    let snapshot = LocalSnapshot::from_file("tests/files/local_snapshot_spent_addresses.bin").unwrap();
+
+    assert_eq!(snapshot.metadata().index(), 42);
+    assert_eq!(snapshot.state().len(), 1);
+    assert_eq!(snapshot.state().spent_addresses().len(), 2);
+    assert!(!snapshot.state().spent_addresses().contains(&Address::zeros()));
+}
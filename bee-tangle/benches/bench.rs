@@ -12,13 +12,222 @@
 #[macro_use]
 extern crate criterion;
 
-use criterion::Criterion;
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput};
 
-use bee_tangle::Tangle;
+use bee_crypto::ternary::Hash;
+use bee_tangle::{traversal, Tangle};
+use bee_test::transaction::{create_random_attached_tx, create_random_tx};
+use bee_transaction::bundled::BundledTransaction as Tx;
 
-fn bench_insert_transaction(c: &mut Criterion) {
-    todo!("insert a few thousand transactions as fast as possible")
+use rand::Rng;
+
+use std::{sync::Arc, thread};
+
+const DAG_SIZES: &[usize] = &[1_000, 10_000];
+const TIP_POOL_WIDTH: usize = 16;
+const INSERT_THREADS: &[usize] = &[1, 2, 4, 8];
+
+/// A synthetic DAG in insertion order, i.e. every transaction is preceded by its trunk and branch.
+struct Dag {
+    genesis: Hash,
+    transactions: Vec<(Hash, Tx)>,
+}
+
+impl Dag {
+    fn tail(&self) -> Hash {
+        self.transactions.last().map(|(hash, _)| *hash).unwrap_or(self.genesis)
+    }
+}
+
+/// Creates a DAG of `size` transactions in which every new transaction approves two of the `width` most recent ones,
+/// which roughly mimics the shape produced by a tip selection with a bounded tip pool.
+fn create_dag(size: usize, width: usize) -> Dag {
+    let mut rng = rand::thread_rng();
+    let (genesis, genesis_tx) = create_random_tx();
+    let mut transactions = Vec::with_capacity(size);
+
+    transactions.push((genesis, genesis_tx));
+
+    while transactions.len() < size {
+        let window = transactions.len().min(width);
+        let offset = transactions.len() - window;
+        let trunk = transactions[offset + rng.gen_range(0, window)].0;
+        let branch = transactions[offset + rng.gen_range(0, window)].0;
+
+        transactions.push(create_random_attached_tx(branch, trunk));
+    }
+
+    Dag { genesis, transactions }
+}
+
+fn create_tangle(dag: &Dag) -> Tangle<()> {
+    let tangle = Tangle::new();
+
+    for (hash, tx) in dag.transactions.iter() {
+        tangle.insert(*hash, tx.clone(), ());
+    }
+
+    tangle
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+
+    for size in DAG_SIZES {
+        let dag = create_dag(*size, TIP_POOL_WIDTH);
+
+        group.throughput(Throughput::Elements(*size as u64));
+
+        for threads in INSERT_THREADS {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_threads", threads), size),
+                threads,
+                |b, threads| {
+                    b.iter_batched(
+                        || {
+                            let mut chunks = vec![Vec::new(); *threads];
+                            // Interleaves the transactions so that all threads work on the same region of the DAG at
+                            // the same time and contend for the same children entries.
+                            for (i, (hash, tx)) in dag.transactions.iter().enumerate() {
+                                chunks[i % threads].push((*hash, tx.clone()));
+                            }
+                            (Arc::new(Tangle::<()>::new()), chunks)
+                        },
+                        |(tangle, chunks)| {
+                            let handles = chunks
+                                .into_iter()
+                                .map(|chunk| {
+                                    let tangle = tangle.clone();
+                                    thread::spawn(move || {
+                                        for (hash, tx) in chunk {
+                                            tangle.insert(hash, tx, ());
+                                        }
+                                    })
+                                })
+                                .collect::<Vec<_>>();
+
+                            for handle in handles {
+                                handle.join().unwrap();
+                            }
+
+                            tangle
+                        },
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_get_children(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_children");
+
+    for size in DAG_SIZES {
+        let dag = create_dag(*size, TIP_POOL_WIDTH);
+        let tangle = create_tangle(&dag);
+
+        group.throughput(Throughput::Elements(*size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &dag, |b, dag| {
+            b.iter(|| {
+                for (hash, _) in dag.transactions.iter() {
+                    criterion::black_box(tangle.get_children(hash));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_tips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tips");
+
+    for size in DAG_SIZES {
+        let dag = create_dag(*size, TIP_POOL_WIDTH);
+        let tangle = create_tangle(&dag);
+        let (tip_hash, tip) = create_random_attached_tx(dag.tail(), dag.tail());
+
+        group.bench_with_input(BenchmarkId::new("num_tips", size), &tangle, |b, tangle| {
+            b.iter(|| criterion::black_box(tangle.num_tips()))
+        });
+        // Attaching a new transaction to the most recent one removes it from the tips and adds a new tip.
+        group.bench_with_input(BenchmarkId::new("attach_tip", size), &dag, |b, dag| {
+            b.iter_batched(
+                || (create_tangle(dag), tip.clone()),
+                |(tangle, tip)| {
+                    tangle.insert(tip_hash, tip, ());
+                    tangle
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_traversal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("traversal");
+
+    for size in DAG_SIZES {
+        let dag = create_dag(*size, TIP_POOL_WIDTH);
+        let tangle = create_tangle(&dag);
+        let tail = dag.tail();
+
+        // No throughput is set as the number of visited vertices depends on the traversal and the shape of the DAG.
+        group.bench_with_input(
+            BenchmarkId::new("visit_parents_follow_trunk", size),
+            &tail,
+            |b, tail| {
+                b.iter(|| {
+                    let mut visited = 0;
+                    traversal::visit_parents_follow_trunk(&tangle, *tail, |_, _| true, |_, _, _| visited += 1);
+                    visited
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("visit_children_follow_trunk", size),
+            &dag.genesis,
+            |b, genesis| {
+                b.iter(|| {
+                    let mut visited = 0;
+                    traversal::visit_children_follow_trunk(&tangle, *genesis, |_, _| true, |_, _, _| visited += 1);
+                    visited
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("visit_parents_depth_first", size), &tail, |b, tail| {
+            b.iter(|| {
+                let mut visited = 0;
+                traversal::visit_parents_depth_first(&tangle, *tail, |_, _, _| true, |_, _, _| visited += 1, |_| ());
+                visited
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("visit_children_depth_first", size),
+            &dag.genesis,
+            |b, genesis| {
+                b.iter(|| {
+                    let mut visited = 0;
+                    traversal::visit_children_depth_first(
+                        &tangle,
+                        *genesis,
+                        |_, _| true,
+                        |_, _, _| visited += 1,
+                        |_| (),
+                    );
+                    visited
+                })
+            },
+        );
+    }
+
+    group.finish();
 }
 
-criterion_group!(benches, bench_insert_transaction);
+criterion_group!(benches, bench_insert, bench_get_children, bench_tips, bench_traversal);
 criterion_main!(benches);
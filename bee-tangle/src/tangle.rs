@@ -94,6 +94,37 @@ where
         }
     }
 
+    /// Removes a transaction, and returns it along with its metadata in case it existed.
+    ///
+    /// The transaction is detached from its parents, which become tips again if it was their last child, but its own
+    /// children are left untouched.
+    pub fn remove(&self, hash: &Hash) -> Option<(TxRef, T)> {
+        let (_, vtx) = self.vertices.remove(hash)?;
+
+        for parent in [vtx.trunk(), vtx.branch()].iter() {
+            let orphaned = match self.children.entry(**parent) {
+                Entry::Occupied(mut entry) => {
+                    entry.get_mut().remove(hash);
+                    if entry.get().is_empty() {
+                        entry.remove();
+                        true
+                    } else {
+                        false
+                    }
+                }
+                Entry::Vacant(_) => false,
+            };
+
+            if orphaned && self.vertices.contains_key(*parent) {
+                self.tips.insert(**parent);
+            }
+        }
+
+        self.tips.remove(hash);
+
+        Some((vtx.transaction().clone(), *vtx.metadata()))
+    }
+
     /// Get the data of a vertex associated with the given `hash`.
     pub fn get(&self, hash: &Hash) -> Option<TxRef> {
         self.vertices.get(hash).map(|vtx| vtx.value().transaction().clone())
@@ -145,6 +176,25 @@ where
         hashes
     }
 
+    /// Returns the current tips.
+    pub fn get_tips(&self) -> Vec<Hash> {
+        self.tips.iter().map(|tip| *tip).collect()
+    }
+
+    /// Returns the hashes of all the vertices satisfying `filter`.
+    ///
+    /// This walks the whole Tangle and should therefore be used with care.
+    pub fn filter<F>(&self, filter: F) -> Vec<Hash>
+    where
+        F: Fn(&Hash, &TxRef, &T) -> bool,
+    {
+        self.vertices
+            .iter()
+            .filter(|entry| filter(entry.key(), entry.value().transaction(), entry.value().metadata()))
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
     /// Returns the current number of tips.
     pub fn num_tips(&self) -> usize {
         self.tips.len()
@@ -166,7 +216,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bee_test::transaction::create_random_tx;
+    use bee_test::transaction::{create_random_attached_tx, create_random_tx};
 
     #[test]
     fn new_tangle() {
@@ -193,4 +243,65 @@ mod tests {
         assert!(tangle.contains(&hash));
         assert_eq!(1, tangle.num_tips());
     }
+
+    #[test]
+    fn insert_and_remove() {
+        let tangle = Tangle::new();
+
+        let (hash, tx) = create_random_tx();
+
+        tangle.insert(hash, tx.clone(), 42u8);
+
+        let (removed, metadata) = tangle.remove(&hash).unwrap();
+
+        assert_eq!(*removed.trunk(), *tx.trunk());
+        assert_eq!(42, metadata);
+        assert_eq!(0, tangle.len());
+        assert!(!tangle.contains(&hash));
+        assert_eq!(0, tangle.num_tips());
+        assert_eq!(0, tangle.num_children(tx.trunk()));
+        assert!(tangle.remove(&hash).is_none());
+    }
+
+    #[test]
+    fn remove_restores_tips() {
+        let tangle = Tangle::new();
+
+        let (parent_hash, parent) = create_random_tx();
+        let (child1_hash, child1) = create_random_attached_tx(parent_hash, parent_hash);
+        let (child2_hash, child2) = create_random_attached_tx(parent_hash, parent_hash);
+
+        tangle.insert(parent_hash, parent, ());
+        tangle.insert(child1_hash, child1, ());
+        tangle.insert(child2_hash, child2, ());
+
+        assert_eq!(2, tangle.num_tips());
+        assert_eq!(2, tangle.num_children(&parent_hash));
+
+        tangle.remove(&child1_hash);
+
+        assert_eq!(1, tangle.num_tips());
+        assert_eq!(1, tangle.num_children(&parent_hash));
+
+        tangle.remove(&child2_hash);
+
+        assert_eq!(vec![parent_hash], tangle.get_tips());
+        assert_eq!(0, tangle.num_children(&parent_hash));
+        assert!(!tangle.children.contains_key(&parent_hash));
+    }
+
+    #[test]
+    fn insert_and_filter() {
+        let tangle = Tangle::new();
+
+        let (hash1, tx1) = create_random_tx();
+        let (hash2, tx2) = create_random_tx();
+
+        tangle.insert(hash1, tx1, 1u8);
+        tangle.insert(hash2, tx2, 2u8);
+
+        assert_eq!(tangle.filter(|_, _, metadata| *metadata == 2), vec![hash2]);
+        assert_eq!(tangle.filter(|_, _, _| true).len(), 2);
+        assert!(tangle.filter(|_, _, _| false).is_empty());
+    }
 }
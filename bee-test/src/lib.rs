@@ -14,4 +14,6 @@
 pub mod field;
 pub mod milestone;
 pub mod slices;
+pub mod strategy;
+pub mod tangle;
 pub mod transaction;
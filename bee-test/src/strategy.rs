@@ -0,0 +1,158 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+//! Proptest strategies for the fields of a `BundledTransaction`.
+
+use bee_crypto::ternary::Hash;
+use bee_ternary::{T1B1Buf, TritBuf, Trits, T1B1};
+use bee_transaction::bundled::{
+    Address, BundledTransaction as Transaction, BundledTransactionBuilder as TransactionBuilder,
+    BundledTransactionField, Index, Nonce, Payload, Tag, Timestamp, Value, ADDRESS_TRIT_LEN, HASH_TRIT_LEN,
+    NONCE_TRIT_LEN, PAYLOAD_TRIT_LEN, TAG_TRIT_LEN,
+};
+
+use proptest::{collection::vec, prelude::*};
+
+/// The total supply of IOTA tokens, which bounds the absolute value of any `Value`.
+pub const IOTA_SUPPLY: i64 = 2_779_530_283_277_761;
+
+/// The largest number that fits in the 27 trits of a `Timestamp` or an `Index`, i.e. `(3^27 - 1) / 2`.
+pub const MAX_27_TRITS: u64 = 3_812_798_742_493;
+
+/// Generates `len` random trits.
+pub fn trits(len: usize) -> impl Strategy<Value = TritBuf> {
+    vec(-1i8..=1, len).prop_map(move |raw| {
+        Trits::<T1B1>::try_from_raw(raw.as_slice(), len)
+            .unwrap()
+            .to_buf::<T1B1Buf>()
+    })
+}
+
+/// Generates `len` trits of which only a prefix of random length is random, the rest being zeros.
+pub fn trailing_zero_trits(len: usize) -> impl Strategy<Value = TritBuf> {
+    (0..=len).prop_flat_map(move |prefix_len| {
+        trits(prefix_len).prop_map(move |prefix| {
+            let mut buf = TritBuf::<T1B1Buf>::zeros(len);
+            buf[0..prefix_len].copy_from(&prefix);
+            buf
+        })
+    })
+}
+
+/// Generates payloads, half of them ending with a random number of zero trits.
+pub fn payload() -> impl Strategy<Value = Payload> {
+    prop_oneof![trits(PAYLOAD_TRIT_LEN), trailing_zero_trits(PAYLOAD_TRIT_LEN)].prop_map(Payload::from_inner_unchecked)
+}
+
+/// Generates addresses.
+pub fn address() -> impl Strategy<Value = Address> {
+    trits(ADDRESS_TRIT_LEN).prop_map(Address::from_inner_unchecked)
+}
+
+/// Generates addresses with a zero last trit, i.e. addresses that can hold a value.
+pub fn value_address() -> impl Strategy<Value = Address> {
+    trits(ADDRESS_TRIT_LEN - 1).prop_map(|trits| {
+        let mut buf = TritBuf::<T1B1Buf>::zeros(ADDRESS_TRIT_LEN);
+        buf[0..ADDRESS_TRIT_LEN - 1].copy_from(&trits);
+        Address::from_inner_unchecked(buf)
+    })
+}
+
+/// Generates values within the supply, bounds included.
+pub fn value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(0),
+        Just(IOTA_SUPPLY),
+        Just(-IOTA_SUPPLY),
+        -IOTA_SUPPLY..=IOTA_SUPPLY
+    ]
+    .prop_map(Value::from_inner_unchecked)
+}
+
+/// Generates values beyond the supply, in both directions.
+pub fn invalid_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(IOTA_SUPPLY + 1),
+        Just(-IOTA_SUPPLY - 1),
+        Just(i64::MAX),
+        Just(i64::MIN),
+        (IOTA_SUPPLY + 1)..=i64::MAX,
+        i64::MIN..=(-IOTA_SUPPLY - 1)
+    ]
+    .prop_map(Value::from_inner_unchecked)
+}
+
+/// Generates tags.
+pub fn tag() -> impl Strategy<Value = Tag> {
+    trits(TAG_TRIT_LEN).prop_map(Tag::from_inner_unchecked)
+}
+
+/// Generates timestamps that fit in their 27 trits.
+pub fn timestamp() -> impl Strategy<Value = Timestamp> {
+    (0..=MAX_27_TRITS).prop_map(Timestamp::from_inner_unchecked)
+}
+
+/// Generates indexes that fit in their 27 trits.
+pub fn index() -> impl Strategy<Value = Index> {
+    (0..=MAX_27_TRITS as usize).prop_map(Index::from_inner_unchecked)
+}
+
+/// Generates nonces.
+pub fn nonce() -> impl Strategy<Value = Nonce> {
+    trits(NONCE_TRIT_LEN).prop_map(Nonce::from_inner_unchecked)
+}
+
+/// Generates hashes, to be used as bundle, trunk or branch.
+pub fn hash() -> impl Strategy<Value = Hash> {
+    trits(HASH_TRIT_LEN).prop_map(Hash::from_inner_unchecked)
+}
+
+/// Generates valid transactions out of the strategies of all their fields.
+pub fn transaction() -> impl Strategy<Value = Transaction> {
+    (
+        (
+            payload(),
+            value_address(),
+            value(),
+            tag(),
+            timestamp(),
+            index(),
+            index(),
+            hash(),
+        ),
+        (hash(), hash(), tag(), timestamp(), timestamp(), timestamp(), nonce()),
+    )
+        .prop_map(
+            |(
+                (payload, address, value, obsolete_tag, timestamp, index, last_index, bundle),
+                (trunk, branch, tag, attachment_ts, attachment_lbts, attachment_ubts, nonce),
+            )| {
+                TransactionBuilder::new()
+                    .with_payload(payload)
+                    .with_address(address)
+                    .with_value(value)
+                    .with_obsolete_tag(obsolete_tag)
+                    .with_timestamp(timestamp)
+                    .with_index(index)
+                    .with_last_index(last_index)
+                    .with_tag(tag)
+                    .with_attachment_ts(attachment_ts)
+                    .with_bundle(bundle)
+                    .with_trunk(trunk)
+                    .with_branch(branch)
+                    .with_attachment_lbts(attachment_lbts)
+                    .with_attachment_ubts(attachment_ubts)
+                    .with_nonce(nonce)
+                    .build()
+                    .unwrap()
+            },
+        )
+}
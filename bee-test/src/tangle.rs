@@ -0,0 +1,521 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use crate::{field::rand_trits_field, transaction::hash_tx};
+
+use bee_crypto::ternary::{
+    sponge::{Kerl, Sponge},
+    Hash,
+};
+use bee_protocol::{Milestone, MilestoneIndex};
+use bee_signing::ternary::{
+    seed::Seed,
+    wots::{WotsSecurityLevel, WotsSpongePrivateKeyGeneratorBuilder},
+    PrivateKey, PrivateKeyGenerator, PublicKey,
+};
+use bee_ternary::{T1B1Buf, TritBuf};
+use bee_transaction::bundled::{
+    Address, BundledTransaction as Transaction, BundledTransactionBuilder as TransactionBuilder,
+    BundledTransactionField, Index, Nonce, OutgoingBundleBuilder, Payload, Tag, Timestamp, Value, TAG_TRIT_LEN,
+};
+
+use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const DEFAULT_WIDTH: usize = 8;
+const DEFAULT_DEPTH: usize = 16;
+const DEFAULT_VALUE_RATIO: f64 = 0.1;
+const DEFAULT_CONFLICT_RATIO: f64 = 0.0;
+const DEFAULT_MILESTONE_INTERVAL: usize = 4;
+const DEFAULT_MILESTONE_SECURITY_LEVEL: usize = 2;
+const DEFAULT_FUNDED_ADDRESSES: usize = 8;
+const DEFAULT_BALANCE: u64 = 1_000_000;
+
+/// A synthetic tangle produced by a `TangleGenerator`.
+pub struct SyntheticTangle {
+    /// The solid entry point every generated transaction eventually refers to.
+    pub solid_entry_point: Hash,
+    /// The generated transactions, each one preceded by its trunk and its branch.
+    pub transactions: Vec<(Hash, Transaction)>,
+    /// The tails of all the generated bundles, milestones included.
+    pub tails: Vec<Hash>,
+    /// The generated milestones, by increasing index.
+    pub milestones: Vec<Milestone>,
+    /// The tails of the bundles that were injected as conflicts.
+    pub conflicts: Vec<Hash>,
+    /// The ledger state the value transfers spend from.
+    pub ledger: HashMap<Address, u64>,
+    /// The address the milestones are issued from.
+    pub coordinator: Address,
+}
+
+/// Generates connected tangles of bundles, with value transfers, conflicts and periodic milestones.
+///
+/// The tangle is built layer by layer; every bundle of a layer approves tails of the previous layer, and a milestone
+/// chained to the previous one is issued every `milestone_interval` layers.
+/// A value transfer moves the whole balance of an address to a new one and also approves the bundle that funded it,
+/// so that a milestone confirming the transfer always confirms its funding first. Conflicts are transfers that spend
+/// more than the balance of their input and are then guaranteed to be conflicting whatever the confirmation order.
+/// Bundle hashes and signatures are valid, transaction hashes are computed with CurlP81 but no PoW is performed and
+/// milestones are not signed.
+pub struct TangleGenerator {
+    width: usize,
+    depth: usize,
+    value_ratio: f64,
+    conflict_ratio: f64,
+    milestone_interval: usize,
+    milestone_security_level: usize,
+    funded_addresses: usize,
+    balance: u64,
+}
+
+impl Default for TangleGenerator {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            depth: DEFAULT_DEPTH,
+            value_ratio: DEFAULT_VALUE_RATIO,
+            conflict_ratio: DEFAULT_CONFLICT_RATIO,
+            milestone_interval: DEFAULT_MILESTONE_INTERVAL,
+            milestone_security_level: DEFAULT_MILESTONE_SECURITY_LEVEL,
+            funded_addresses: DEFAULT_FUNDED_ADDRESSES,
+            balance: DEFAULT_BALANCE,
+        }
+    }
+}
+
+impl TangleGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of bundles per layer.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the number of layers.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the probability, in `[0, 1]`, for a bundle to be a value transfer.
+    pub fn with_value_ratio(mut self, value_ratio: f64) -> Self {
+        self.value_ratio = value_ratio;
+        self
+    }
+
+    /// Sets the probability, in `[0, 1]`, for a value transfer to be a conflict.
+    pub fn with_conflict_ratio(mut self, conflict_ratio: f64) -> Self {
+        self.conflict_ratio = conflict_ratio;
+        self
+    }
+
+    /// Sets the number of layers between two milestones, `0` disables milestones.
+    pub fn with_milestone_interval(mut self, milestone_interval: usize) -> Self {
+        self.milestone_interval = milestone_interval;
+        self
+    }
+
+    /// Sets the number of signature transactions of a milestone bundle.
+    pub fn with_milestone_security_level(mut self, milestone_security_level: usize) -> Self {
+        self.milestone_security_level = milestone_security_level;
+        self
+    }
+
+    /// Sets the number of addresses funded by the initial ledger state and their balance.
+    pub fn with_funds(mut self, funded_addresses: usize, balance: u64) -> Self {
+        self.funded_addresses = funded_addresses;
+        self.balance = balance;
+        self
+    }
+
+    pub fn generate(self) -> SyntheticTangle {
+        let mut generation = Generation::new(&self);
+        let mut tips = vec![generation.tangle.solid_entry_point];
+        let mut last_milestone = generation.tangle.solid_entry_point;
+
+        for layer in 0..self.depth {
+            let mut layer_tails = Vec::with_capacity(self.width);
+
+            for _ in 0..self.width {
+                let trunk = generation.choose(&tips);
+
+                let tail = if !generation.funds.is_empty() && generation.rng.gen_bool(self.value_ratio) {
+                    if generation.rng.gen_bool(self.conflict_ratio) {
+                        generation.conflict(trunk, &tips)
+                    } else {
+                        generation.transfer(trunk, &tips)
+                    }
+                } else {
+                    let branch = generation.choose(&tips);
+                    let bundle = generation.data();
+                    generation.attach(bundle, trunk, branch)
+                };
+
+                layer_tails.push(tail);
+            }
+
+            tips = layer_tails;
+
+            if self.milestone_interval != 0 && (layer + 1) % self.milestone_interval == 0 {
+                let index = MilestoneIndex(generation.tangle.milestones.len() as u32 + 1);
+                let branch = generation.choose(&tips);
+                let bundle = generation.milestone(index, self.milestone_security_level);
+                let tail = generation.attach(bundle, last_milestone, branch);
+
+                generation.tangle.milestones.push(Milestone::new(tail, index));
+                tips.push(tail);
+                last_milestone = tail;
+            }
+        }
+
+        generation.tangle
+    }
+}
+
+struct Funds {
+    address: Address,
+    key_index: usize,
+    balance: u64,
+    // Tail of the bundle that funded the address, `None` for the initial ledger state.
+    funded_by: Option<Hash>,
+}
+
+struct Generation {
+    rng: ThreadRng,
+    seed: Seed,
+    next_key_index: usize,
+    timestamp: u64,
+    funds: Vec<Funds>,
+    tangle: SyntheticTangle,
+}
+
+impl Generation {
+    fn new(generator: &TangleGenerator) -> Self {
+        let mut generation = Self {
+            rng: rand::thread_rng(),
+            seed: Seed::rand(),
+            next_key_index: 0,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Clock may have gone backwards")
+                .as_secs(),
+            funds: Vec::with_capacity(generator.funded_addresses),
+            tangle: SyntheticTangle {
+                solid_entry_point: Hash::zeros(),
+                transactions: Vec::new(),
+                tails: Vec::new(),
+                milestones: Vec::new(),
+                conflicts: Vec::new(),
+                ledger: HashMap::new(),
+                coordinator: rand_trits_field::<Address>(),
+            },
+        };
+
+        for _ in 0..generator.funded_addresses {
+            let (address, key_index) = generation.new_address();
+
+            generation.tangle.ledger.insert(address.clone(), generator.balance);
+            generation.funds.push(Funds {
+                address,
+                key_index,
+                balance: generator.balance,
+                funded_by: None,
+            });
+        }
+
+        generation
+    }
+
+    fn choose(&mut self, hashes: &[Hash]) -> Hash {
+        // Safe to unwrap since there is always at least the solid entry point or the previous layer to choose from.
+        *hashes.choose(&mut self.rng).unwrap()
+    }
+
+    fn new_address(&mut self) -> (Address, usize) {
+        let key_index = self.next_key_index;
+        let address = Address::from_inner_unchecked(
+            WotsSpongePrivateKeyGeneratorBuilder::<Kerl>::default()
+                .with_security_level(WotsSecurityLevel::Low)
+                .build()
+                // Safe to unwrap because security level is provided
+                .unwrap()
+                .generate_from_seed(&self.seed, key_index)
+                .unwrap()
+                .generate_public_key()
+                .unwrap()
+                .as_trits()
+                .to_owned(),
+        );
+
+        self.next_key_index += 1;
+
+        (address, key_index)
+    }
+
+    fn transaction_builder(&mut self, address: Address, value: i64) -> TransactionBuilder {
+        self.timestamp += 1;
+
+        TransactionBuilder::new()
+            .with_payload(Payload::zeros())
+            .with_address(address)
+            .with_value(Value::from_inner_unchecked(value))
+            .with_obsolete_tag(rand_trits_field::<Tag>())
+            .with_timestamp(Timestamp::from_inner_unchecked(self.timestamp))
+            .with_index(Index::from_inner_unchecked(0))
+            .with_last_index(Index::from_inner_unchecked(0))
+            .with_tag(rand_trits_field::<Tag>())
+            .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+            .with_bundle(Hash::zeros())
+            .with_trunk(Hash::zeros())
+            .with_branch(Hash::zeros())
+            .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+            .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+            .with_nonce(rand_trits_field::<Nonce>())
+    }
+
+    fn data(&mut self) -> Vec<Transaction> {
+        let mut builder = OutgoingBundleBuilder::new();
+
+        builder.push(self.transaction_builder(rand_trits_field::<Address>(), 0));
+
+        builder
+            .seal()
+            .unwrap()
+            .attach_local(Hash::zeros(), Hash::zeros())
+            .unwrap()
+            .build()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    fn value(&mut self, input: &Funds, output: Address, value: u64) -> Vec<Transaction> {
+        let mut builder = OutgoingBundleBuilder::new();
+
+        builder.push(self.transaction_builder(output, value as i64));
+        builder.push(self.transaction_builder(input.address.clone(), -(value as i64)));
+
+        builder
+            .seal()
+            .unwrap()
+            .sign(
+                &self.seed,
+                &[(input.key_index, input.address.clone(), WotsSecurityLevel::Low)],
+            )
+            .unwrap()
+            .attach_local(Hash::zeros(), Hash::zeros())
+            .unwrap()
+            .build()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    fn transfer(&mut self, trunk: Hash, tips: &[Hash]) -> Hash {
+        let index = self.rng.gen_range(0, self.funds.len());
+        let input = self.funds.swap_remove(index);
+        let (address, key_index) = self.new_address();
+        let bundle = self.value(&input, address.clone(), input.balance);
+        let branch = match input.funded_by {
+            Some(funded_by) => funded_by,
+            None => self.choose(tips),
+        };
+        let tail = self.attach(bundle, trunk, branch);
+
+        self.funds.push(Funds {
+            address,
+            key_index,
+            balance: input.balance,
+            funded_by: Some(tail),
+        });
+
+        tail
+    }
+
+    fn conflict(&mut self, trunk: Hash, tips: &[Hash]) -> Hash {
+        let index = self.rng.gen_range(0, self.funds.len());
+        let input = self.funds.swap_remove(index);
+        let (address, _) = self.new_address();
+        let bundle = self.value(&input, address, input.balance + 1);
+        let branch = match input.funded_by {
+            Some(funded_by) => funded_by,
+            None => self.choose(tips),
+        };
+        let tail = self.attach(bundle, trunk, branch);
+
+        self.tangle.conflicts.push(tail);
+        // The overspent address is left untouched and can still be spent.
+        self.funds.push(input);
+
+        tail
+    }
+
+    fn milestone(&mut self, index: MilestoneIndex, security_level: usize) -> Vec<Transaction> {
+        let mut obsolete_tag = TritBuf::<T1B1Buf>::zeros(TAG_TRIT_LEN);
+        let index_trits = TritBuf::<T1B1Buf<_>>::from(*index as i64);
+
+        obsolete_tag[0..index_trits.len()].copy_from(&index_trits);
+
+        let coordinator = self.tangle.coordinator.clone();
+        let builders = (0..=security_level)
+            .map(|i| {
+                self.transaction_builder(coordinator.clone(), 0)
+                    .with_payload(rand_trits_field::<Payload>())
+                    .with_obsolete_tag(Tag::from_inner_unchecked(obsolete_tag.clone()))
+                    .with_index(Index::from_inner_unchecked(i))
+                    .with_last_index(Index::from_inner_unchecked(security_level))
+            })
+            .collect::<Vec<_>>();
+
+        // Not sealed by an `OutgoingBundleBuilder` since fixing the M-bug would alter the index in the obsolete tag.
+        let mut sponge = Kerl::default();
+
+        for builder in builders.iter() {
+            let _ = sponge.absorb(&builder.essence());
+        }
+
+        let bundle = Hash::from_inner_unchecked(
+            sponge
+                .squeeze()
+                .unwrap_or_else(|_| panic!("Panicked when unwrapping the sponge hash function.")),
+        );
+
+        builders
+            .into_iter()
+            .map(|builder| builder.with_bundle(bundle).build().unwrap())
+            .collect()
+    }
+
+    // Links the transactions of a bundle together and to its parents, registers them and returns the tail hash.
+    fn attach(&mut self, bundle: Vec<Transaction>, trunk: Hash, branch: Hash) -> Hash {
+        let last_index = bundle.len() - 1;
+        let mut next = trunk;
+
+        // Goes from the head to the tail so that every transaction is registered after its parents.
+        for (index, transaction) in bundle.iter().enumerate().rev() {
+            let transaction = if index == last_index {
+                with_parents(transaction, trunk, branch)
+            } else {
+                with_parents(transaction, next, trunk)
+            };
+
+            next = hash_tx(&transaction);
+            self.tangle.transactions.push((next, transaction));
+        }
+
+        self.tangle.tails.push(next);
+
+        next
+    }
+}
+
+fn with_parents(transaction: &Transaction, trunk: Hash, branch: Hash) -> Transaction {
+    TransactionBuilder::new()
+        .with_payload(transaction.payload().clone())
+        .with_address(transaction.address().clone())
+        .with_value(transaction.value().clone())
+        .with_obsolete_tag(transaction.obsolete_tag().clone())
+        .with_timestamp(transaction.timestamp().clone())
+        .with_index(transaction.index().clone())
+        .with_last_index(transaction.last_index().clone())
+        .with_tag(transaction.tag().clone())
+        .with_attachment_ts(transaction.attachment_ts().clone())
+        .with_bundle(*transaction.bundle())
+        .with_trunk(trunk)
+        .with_branch(branch)
+        .with_attachment_lbts(transaction.attachment_lbts().clone())
+        .with_attachment_ubts(transaction.attachment_ubts().clone())
+        .with_nonce(transaction.nonce().clone())
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_transaction::{bundled::IncomingBundleBuilder, Vertex};
+
+    use std::{collections::HashSet, convert::TryFrom};
+
+    #[test]
+    fn parents_precede_children() {
+        let tangle = TangleGenerator::new().with_width(4).with_depth(8).generate();
+        let mut known = HashSet::new();
+
+        known.insert(tangle.solid_entry_point);
+
+        for (hash, transaction) in tangle.transactions.iter() {
+            assert!(known.contains(transaction.trunk()));
+            assert!(known.contains(transaction.branch()));
+            known.insert(*hash);
+        }
+
+        assert_eq!(tangle.tails.len(), 4 * 8 + 8 / DEFAULT_MILESTONE_INTERVAL);
+        assert_eq!(tangle.milestones.len(), 8 / DEFAULT_MILESTONE_INTERVAL);
+    }
+
+    #[test]
+    fn bundles_are_valid() {
+        let tangle = TangleGenerator::new()
+            .with_width(4)
+            .with_depth(4)
+            .with_value_ratio(0.5)
+            .with_conflict_ratio(0.5)
+            .with_funds(4, 100)
+            .generate();
+        let transactions = tangle.transactions.iter().cloned().collect::<HashMap<_, _>>();
+
+        for tail in tangle.tails.iter() {
+            let mut builder = IncomingBundleBuilder::new();
+            let mut transaction = transactions.get(tail).unwrap();
+
+            builder.push(transaction.clone());
+
+            while !transaction.is_head() {
+                transaction = transactions.get(transaction.trunk()).unwrap();
+                builder.push(transaction.clone());
+            }
+
+            assert!(builder.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn milestones_encode_their_index() {
+        let tangle = TangleGenerator::new()
+            .with_width(2)
+            .with_depth(6)
+            .with_milestone_interval(2)
+            .generate();
+        let transactions = tangle.transactions.iter().cloned().collect::<HashMap<_, _>>();
+
+        assert_eq!(tangle.milestones.len(), 3);
+
+        for milestone in tangle.milestones.iter() {
+            let tail = transactions.get(milestone.hash()).unwrap();
+
+            assert!(tail.is_tail());
+            assert_eq!(
+                i64::try_from(tail.obsolete_tag().to_inner()).unwrap() as u32,
+                *milestone.index()
+            );
+        }
+    }
+}
@@ -11,7 +11,11 @@
 
 use crate::field::rand_trits_field;
 
-use bee_crypto::ternary::Hash;
+use bee_crypto::ternary::{
+    sponge::{CurlP81, Sponge},
+    Hash,
+};
+use bee_ternary::{T1B1Buf, TritBuf};
 use bee_transaction::{
     bundled::{
         Address, BundledTransaction as Transaction, BundledTransactionBuilder as TransactionBuilder,
@@ -82,3 +86,17 @@ pub fn create_random_attached_tx(branch: Hash, trunk: Hash) -> (Hash, Transactio
 
     (rand_trits_field::<Hash>(), builder.build().unwrap())
 }
+
+pub fn hash_tx(transaction: &Transaction) -> Hash {
+    let mut trits = TritBuf::<T1B1Buf>::zeros(Transaction::trit_len());
+    let mut sponge = CurlP81::default();
+
+    transaction.into_trits_allocated(&mut trits);
+    let _ = sponge.absorb(&trits);
+
+    Hash::from_inner_unchecked(
+        sponge
+            .squeeze()
+            .unwrap_or_else(|_| panic!("Panicked when unwrapping the sponge hash function.")),
+    )
+}
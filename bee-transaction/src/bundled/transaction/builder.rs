@@ -162,7 +162,7 @@ impl BundledTransactionBuilder {
             .0;
         let address = self.address.ok_or(BundledTransactionError::MissingField("address"))?;
 
-        if value.abs() > IOTA_SUPPLY {
+        if value > IOTA_SUPPLY || value < -IOTA_SUPPLY {
             return Err(BundledTransactionError::InvalidValue(value));
         }
 
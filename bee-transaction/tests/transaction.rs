@@ -0,0 +1,104 @@
+// Copyright 2020 IOTA Stiftung
+//
+// Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file except in compliance with
+// the License. You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software distributed under the License is distributed on
+// an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and limitations under the License.
+
+use bee_crypto::ternary::Hash;
+use bee_ternary::{Btrit, T1B1Buf, T5B1Buf, TritBuf, Trits, T5B1};
+use bee_test::{
+    strategy::{address, invalid_value, transaction, value, value_address},
+    transaction::clone_tx,
+};
+use bee_transaction::bundled::{
+    Address, BundledTransaction, BundledTransactionBuilder, BundledTransactionError, BundledTransactionField, Index,
+    Nonce, Payload, Tag, Timestamp, Value, ADDRESS_TRIT_LEN, TRANSACTION_BYTE_LEN, TRANSACTION_TRIT_LEN,
+};
+
+use proptest::prelude::*;
+
+fn builder_with(address: Address, value: Value) -> BundledTransactionBuilder {
+    BundledTransactionBuilder::new()
+        .with_payload(Payload::zeros())
+        .with_address(address)
+        .with_value(value)
+        .with_obsolete_tag(Tag::zeros())
+        .with_timestamp(Timestamp::from_inner_unchecked(0))
+        .with_index(Index::from_inner_unchecked(0))
+        .with_last_index(Index::from_inner_unchecked(0))
+        .with_tag(Tag::zeros())
+        .with_attachment_ts(Timestamp::from_inner_unchecked(0))
+        .with_bundle(Hash::zeros())
+        .with_trunk(Hash::zeros())
+        .with_branch(Hash::zeros())
+        .with_attachment_lbts(Timestamp::from_inner_unchecked(0))
+        .with_attachment_ubts(Timestamp::from_inner_unchecked(0))
+        .with_nonce(Nonce::zeros())
+}
+
+proptest! {
+    #[test]
+    fn build_keeps_fields(tx in transaction()) {
+        prop_assert_eq!(clone_tx(&tx), tx);
+    }
+
+    #[test]
+    fn trits_roundtrip(tx in transaction()) {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(BundledTransaction::trit_len());
+
+        tx.into_trits_allocated(&mut trits);
+
+        prop_assert_eq!(BundledTransaction::from_trits(&*trits).unwrap(), tx);
+    }
+
+    #[test]
+    fn bytes_roundtrip(tx in transaction()) {
+        let mut trits = TritBuf::<T1B1Buf>::zeros(BundledTransaction::trit_len());
+
+        tx.into_trits_allocated(&mut trits);
+
+        let bytes = trits.encode::<T5B1Buf>();
+
+        prop_assert_eq!(bytes.as_i8_slice().len(), TRANSACTION_BYTE_LEN);
+
+        let decoded = Trits::<T5B1>::try_from_raw(bytes.as_i8_slice(), TRANSACTION_TRIT_LEN)
+            .unwrap()
+            .encode::<T1B1Buf>();
+
+        prop_assert_eq!(BundledTransaction::from_trits(&*decoded).unwrap(), tx);
+    }
+
+    #[test]
+    fn build_accepts_values_within_supply(address in value_address(), value in value()) {
+        let inner = *value.to_inner();
+
+        prop_assert_eq!(*builder_with(address, value).build().unwrap().value().to_inner(), inner);
+    }
+
+    #[test]
+    fn build_rejects_values_beyond_supply(address in value_address(), value in invalid_value()) {
+        let inner = *value.to_inner();
+
+        match builder_with(address, value).build() {
+            Err(BundledTransactionError::InvalidValue(v)) => prop_assert_eq!(v, inner),
+            _ => prop_assert!(false, "value {} beyond supply was accepted", inner),
+        }
+    }
+
+    #[test]
+    fn build_rejects_values_on_addresses_with_non_zero_last_trit(address in address(), value in value()) {
+        let inner = *value.to_inner();
+        let last_trit_is_zero = address.to_inner().get(ADDRESS_TRIT_LEN - 1) == Some(Btrit::Zero);
+
+        match builder_with(address, value).build() {
+            Ok(_) => prop_assert!(inner == 0 || last_trit_is_zero),
+            Err(BundledTransactionError::InvalidAddress) => prop_assert!(inner != 0 && !last_trit_is_zero),
+            Err(e) => prop_assert!(false, "unexpected error {:?}", e),
+        }
+    }
+}